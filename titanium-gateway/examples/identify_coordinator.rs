@@ -0,0 +1,35 @@
+//! Identify coordinator shared by several cluster processes.
+//!
+//! Run one coordinator per bot, then point every process at it with
+//! `ClusterConfig::with_identify_queue(Arc::new(RemoteIdentifyQueue::new(addr)))`.
+//!
+//! # Usage
+//!
+//! ```bash
+//! MAX_CONCURRENCY=16 COORDINATOR_ADDR=127.0.0.1:7400 cargo run --example identify_coordinator
+//! ```
+
+use titanium_gateway::IdentifyCoordinator;
+use tracing::Level;
+use tracing_subscriber::FmtSubscriber;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize logging
+    let subscriber = FmtSubscriber::builder()
+        .with_max_level(Level::INFO)
+        .finish();
+    tracing::subscriber::set_global_default(subscriber)?;
+
+    // max_concurrency from the /gateway/bot response
+    let max_concurrency: usize = std::env::var("MAX_CONCURRENCY")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(1);
+    let addr = std::env::var("COORDINATOR_ADDR").unwrap_or_else(|_| "127.0.0.1:7400".to_string());
+
+    IdentifyCoordinator::new(max_concurrency)
+        .serve(addr)
+        .await?;
+    Ok(())
+}
@@ -85,24 +85,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         guild_count
                     );
                 }
-                Event::GuildCreate(guild) => {
-                    // Only log after all shards are ready (lazy-load complete)
-                    if ready_shards >= gateway_info.shards {
-                        info!(
-                            "[Shard {}] New guild: {} ({})",
-                            shard_id, guild.name, guild.id
-                        );
-                    }
+                // Only log after all shards are ready (lazy-load complete)
+                Event::GuildCreate(guild) if ready_shards >= gateway_info.shards => {
+                    info!(
+                        "[Shard {}] New guild: {} ({})",
+                        shard_id, guild.name, guild.id
+                    );
                 }
-                Event::MessageCreate(message) => {
-                    // Example: respond to ping
-                    if message.content == "!ping" {
-                        info!(
-                            "[Shard {}] Ping from {} in {}",
-                            shard_id, message.author.username, message.channel_id
-                        );
-                        // In production, use HTTP client to send response
-                    }
+                // Example: respond to ping
+                Event::MessageCreate(message) if message.content == "!ping" => {
+                    info!(
+                        "[Shard {}] Ping from {} in {}",
+                        shard_id, message.author.username, message.channel_id
+                    );
+                    // In production, use HTTP client to send response
                 }
                 _ => {}
             }
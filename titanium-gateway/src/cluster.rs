@@ -4,17 +4,35 @@
 //! For very large bots (1M+ guilds), multiple Clusters can run on
 //! different machines with coordinated shard ranges.
 
+use crate::compression::TransportCompression;
 use crate::error::GatewayError;
+use crate::etf::GatewayEncoding;
 use crate::event::Event;
-use crate::ratelimit::IdentifyRateLimiter;
-use crate::shard::{Shard, ShardConfig, ShardState};
+use crate::event_flags::EventTypeFlags;
+use crate::identify::{IdentifyQueue, InMemoryIdentifyQueue};
+use crate::lifecycle::{ClusterEvent, LIFECYCLE_CAPACITY};
+use crate::members::MemberChunkStream;
+use crate::metrics::{GatewayMetrics, ShardMetrics};
+use crate::payload::{PresenceUpdate, RequestGuildMembersPayload};
+use crate::proxy::ProxyConfig;
+use crate::queue::{EventQueue, EventSender, OverflowPolicy, OverflowSender};
+use crate::raw::{DispatchMode, RawDispatch};
+use crate::recording::SessionRecorder;
+use crate::reshard::EventRouter;
+use crate::shard::{Shard, ShardConfig, ShardSession, ShardState};
+use crate::voice::VoiceConnectionFuture;
 
 use dashmap::DashMap;
-use flume::{Receiver, Sender};
+use flume::Receiver;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use titanium_model::Intents;
+use std::time::Duration;
+use titanium_model::{Intents, Snowflake};
+use tokio::sync::broadcast;
 use tokio::task::JoinHandle;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 /// Specifies which shards a Cluster should manage.
 #[derive(Debug, Clone)]
@@ -84,6 +102,42 @@ pub struct ClusterConfig {
 
     /// Large guild threshold.
     pub large_threshold: u8,
+
+    /// Transport compression used by every shard.
+    pub compression: TransportCompression,
+
+    /// Payload encoding used by every shard.
+    pub encoding: GatewayEncoding,
+
+    /// Proxy every shard connects through.
+    pub proxy: Option<ProxyConfig>,
+
+    /// Presence every shard identifies with.
+    pub presence: Option<PresenceUpdate>,
+
+    /// Saved sessions to resume, keyed by shard ID.
+    pub sessions: HashMap<u16, ShardSession>,
+
+    /// Dispatch types every shard deserializes and delivers.
+    pub event_types: EventTypeFlags,
+
+    /// Whether dispatches are delivered parsed, raw or both.
+    pub dispatch_mode: DispatchMode,
+
+    /// Capacity of the event and raw dispatch channels, unbounded if `None`.
+    pub event_capacity: Option<usize>,
+
+    /// What to do when the event channel is full.
+    pub overflow_policy: OverflowPolicy,
+
+    /// Identify queue shared with other processes, if any.
+    ///
+    /// Without one, the cluster queues identifies itself based on
+    /// `max_concurrency`.
+    pub identify_queue: Option<Arc<dyn IdentifyQueue>>,
+
+    /// Recorder of the frames received by every shard.
+    pub recorder: Option<Arc<SessionRecorder>>,
 }
 
 impl ClusterConfig {
@@ -96,6 +150,17 @@ impl ClusterConfig {
             gateway_url: crate::DEFAULT_GATEWAY_URL.to_string(),
             max_concurrency: 1,
             large_threshold: 250,
+            compression: TransportCompression::default(),
+            encoding: GatewayEncoding::default(),
+            proxy: None,
+            presence: None,
+            sessions: HashMap::new(),
+            event_types: EventTypeFlags::all(),
+            dispatch_mode: DispatchMode::default(),
+            event_capacity: None,
+            overflow_policy: OverflowPolicy::default(),
+            identify_queue: None,
+            recorder: None,
         }
     }
 
@@ -111,6 +176,85 @@ impl ClusterConfig {
         self
     }
 
+    /// Set the transport compression used by every shard.
+    pub fn with_compression(mut self, compression: TransportCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Set the payload encoding used by every shard.
+    pub fn with_encoding(mut self, encoding: GatewayEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Connect all shards through an HTTP `CONNECT` or SOCKS5 proxy.
+    pub fn with_proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Set the presence every shard identifies with.
+    pub fn with_presence(mut self, presence: PresenceUpdate) -> Self {
+        self.presence = Some(presence);
+        self
+    }
+
+    /// Resume sessions saved by a previous process.
+    ///
+    /// Takes the sessions returned by [`Cluster::shutdown_resumable`]. Shards without a
+    /// session, or whose resume is rejected, identify as usual.
+    pub fn with_sessions(mut self, sessions: impl IntoIterator<Item = ShardSession>) -> Self {
+        self.sessions
+            .extend(sessions.into_iter().map(|s| (s.shard_id, s)));
+        self
+    }
+
+    /// Only deserialize and deliver the given dispatch types.
+    ///
+    /// See [`ShardConfig::with_event_types`].
+    pub fn with_event_types(mut self, event_types: EventTypeFlags) -> Self {
+        self.event_types = event_types;
+        self
+    }
+
+    /// Choose between parsed events and raw dispatches.
+    ///
+    /// Raw dispatches are received through [`Cluster::raw_dispatches`].
+    pub fn with_dispatch_mode(mut self, dispatch_mode: DispatchMode) -> Self {
+        self.dispatch_mode = dispatch_mode;
+        self
+    }
+
+    /// Bound the event channel to `capacity` events.
+    ///
+    /// By default the channel is unbounded, so a slow consumer makes it grow
+    /// without limit, e.g. during the `GUILD_CREATE` burst after startup.
+    /// The raw dispatch channel gets the same bound and policy. Events
+    /// dropped by `policy` are counted in the `events_dropped` metrics.
+    pub fn with_event_capacity(mut self, capacity: usize, policy: OverflowPolicy) -> Self {
+        self.event_capacity = Some(capacity);
+        self.overflow_policy = policy;
+        self
+    }
+
+    /// Queue identifies through `queue`, e.g. a [`RemoteIdentifyQueue`]
+    /// shared by every process running shards of the bot.
+    ///
+    /// [`RemoteIdentifyQueue`]: crate::RemoteIdentifyQueue
+    pub fn with_identify_queue(mut self, queue: Arc<dyn IdentifyQueue>) -> Self {
+        self.identify_queue = Some(queue);
+        self
+    }
+
+    /// Record the frames received by every shard into one recording.
+    ///
+    /// Replay it with [`SessionReplay`](crate::SessionReplay).
+    pub fn with_recorder(mut self, recorder: Arc<SessionRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
     /// Create a new cluster configuration with auto-detected shard count.
     ///
     /// This requires the `auto-sharding` feature.
@@ -143,6 +287,17 @@ impl ClusterConfig {
             gateway_url: info.url,
             max_concurrency: info.session_start_limit.max_concurrency as usize,
             large_threshold: 250,
+            compression: TransportCompression::default(),
+            encoding: GatewayEncoding::default(),
+            proxy: None,
+            presence: None,
+            sessions: HashMap::new(),
+            event_types: EventTypeFlags::all(),
+            dispatch_mode: DispatchMode::default(),
+            event_capacity: None,
+            overflow_policy: OverflowPolicy::default(),
+            identify_queue: None,
+            recorder: None,
         })
     }
 }
@@ -155,12 +310,33 @@ struct ShardRunner {
     handle: JoinHandle<Result<(), GatewayError>>,
 }
 
+/// Shards sharing one total shard count.
+struct ShardSet {
+    /// Total number of shards.
+    total: u16,
+    /// Generation routing the events of this set.
+    generation: u64,
+    /// Running shards.
+    shards: DashMap<u16, ShardRunner>,
+}
+
+impl ShardSet {
+    fn new(total: u16, generation: u64) -> Self {
+        Self {
+            total,
+            generation,
+            shards: DashMap::new(),
+        }
+    }
+}
+
 /// A Cluster manages multiple Gateway Shards.
 ///
 /// The Cluster handles:
 /// - Spawning and managing shard tasks
 /// - Coordinating identify rate limiting across shards
 /// - Aggregating events from all shards
+/// - Resharding without downtime
 ///
 /// # Example
 ///
@@ -190,14 +366,32 @@ pub struct Cluster {
     /// Cluster configuration.
     config: ClusterConfig,
 
-    /// Running shards.
-    shards: DashMap<u16, ShardRunner>,
+    /// Shards whose events are delivered.
+    active: RwLock<Arc<ShardSet>>,
+
+    /// Generation of the most recent shard set.
+    generation: AtomicU64,
 
     /// Shared rate limiter for identify.
-    rate_limiter: Arc<IdentifyRateLimiter>,
+    identify_queue: Arc<dyn IdentifyQueue>,
+
+    /// Event channel shared by all shards.
+    events: Arc<EventQueue>,
+
+    /// Channel of raw dispatches from all shards.
+    raw_tx: Arc<OverflowSender<RawDispatch>>,
+
+    /// Receiving end of the raw dispatch channel, handed out to consumers.
+    raw_rx: Receiver<RawDispatch>,
 
-    /// Channel to send shard events.
-    event_tx: Sender<(u16, Event<'static>)>,
+    /// Filters events while two shard sets overlap.
+    router: Arc<EventRouter>,
+
+    /// Lifecycle event subscribers.
+    lifecycle_tx: broadcast::Sender<ClusterEvent>,
+
+    /// Metrics shared by all shards.
+    metrics: Arc<GatewayMetrics>,
 }
 
 impl Cluster {
@@ -206,14 +400,29 @@ impl Cluster {
     /// Returns the Cluster and a receiver for events from all shards.
     /// Events are tagged with the shard ID they came from.
     pub fn new(config: ClusterConfig) -> (Self, Receiver<(u16, Event<'static>)>) {
-        let (event_tx, event_rx) = flume::unbounded();
-        let rate_limiter = Arc::new(IdentifyRateLimiter::new(config.max_concurrency));
+        let router = Arc::new(EventRouter::default());
+        let (events, event_rx) = EventQueue::new(
+            config.event_capacity,
+            config.overflow_policy,
+            router.clone(),
+        );
+        let (raw_tx, raw_rx) = OverflowSender::new(config.event_capacity, config.overflow_policy);
+        let identify_queue = config.identify_queue.clone().unwrap_or_else(|| {
+            Arc::new(InMemoryIdentifyQueue::new(config.max_concurrency)) as Arc<dyn IdentifyQueue>
+        });
+        let active = ShardSet::new(config.shard_range.total_shards(), 0);
 
         let cluster = Self {
             config,
-            shards: DashMap::new(),
-            rate_limiter,
-            event_tx,
+            active: RwLock::new(Arc::new(active)),
+            generation: AtomicU64::new(0),
+            identify_queue,
+            events: Arc::new(events),
+            raw_tx: Arc::new(raw_tx),
+            raw_rx,
+            router,
+            lifecycle_tx: broadcast::channel(LIFECYCLE_CAPACITY).0,
+            metrics: Arc::new(GatewayMetrics::new()),
         };
 
         (cluster, event_rx)
@@ -237,104 +446,622 @@ impl Cluster {
             "Starting cluster"
         );
 
+        let active = self.active();
         for shard_id in shard_ids {
-            self.spawn_shard(shard_id, total_shards)?;
+            let session = self.config.sessions.get(&shard_id).cloned();
+            self.spawn_shard(&active, shard_id, self.config.presence.clone(), session)?;
         }
 
         Ok(())
     }
 
-    /// Spawn a single shard.
-    fn spawn_shard(&self, shard_id: u16, total_shards: u16) -> Result<(), GatewayError> {
+    /// Spawn a single shard into `set`.
+    fn spawn_shard(
+        &self,
+        set: &ShardSet,
+        shard_id: u16,
+        presence: Option<PresenceUpdate>,
+        session: Option<ShardSession>,
+    ) -> Result<(), GatewayError> {
+        let total_shards = set.total;
         let shard_config = ShardConfig {
             token: self.config.token.clone(),
             intents: self.config.intents,
             gateway_url: self.config.gateway_url.clone(),
             large_threshold: self.config.large_threshold,
+            compression: self.config.compression,
+            #[allow(deprecated)]
             compress: false,
+            encoding: self.config.encoding,
             max_reconnect_attempts: 10,
             reconnect_base_delay_ms: 1000,
             reconnect_max_delay_ms: 60000,
+            proxy: self.config.proxy.clone(),
+            presence,
+            session,
+            event_types: self.config.event_types,
+            dispatch_mode: self.config.dispatch_mode,
+            recorder: self.config.recorder.clone(),
         };
 
-        let shard = Arc::new(Shard::with_rate_limiter(
-            shard_id,
-            total_shards,
-            shard_config,
-            self.rate_limiter.clone(),
-        ));
-
-        // Create per-shard event channel that forwards to cluster channel
-        let (shard_tx, shard_rx) = flume::unbounded::<Event>();
-        let cluster_tx = self.event_tx.clone();
-        let shard_id_for_forward = shard_id;
-
-        // Spawn forwarding task
-        tokio::spawn(async move {
-            while let Ok(event) = shard_rx.recv_async().await {
-                if cluster_tx
-                    .send_async((shard_id_for_forward, event))
-                    .await
-                    .is_err()
-                {
-                    break;
-                }
-            }
-        });
+        let shard = Arc::new(
+            Shard::with_identify_queue(
+                shard_id,
+                total_shards,
+                shard_config,
+                self.identify_queue.clone(),
+            )
+            .with_cluster_events(self.lifecycle_tx.clone())
+            .with_gateway_metrics(self.metrics.clone())
+            .with_raw_dispatches(
+                self.raw_tx.clone(),
+                self.router.clone(),
+                set.generation,
+            ),
+        );
 
-        // Spawn shard task
+        // Spawn shard task, sending straight into the cluster channel
+        let events = EventSender::Cluster {
+            queue: self.events.clone(),
+            generation: set.generation,
+        };
         let shard_clone = shard.clone();
-        let handle = tokio::spawn(async move { shard_clone.run(shard_tx).await });
+        let handle = tokio::spawn(async move { shard_clone.run_with(events).await });
 
-        self.shards.insert(shard_id, ShardRunner { shard, handle });
+        set.shards.insert(shard_id, ShardRunner { shard, handle });
 
         info!(shard_id = shard_id, "Shard spawned");
         Ok(())
     }
 
+    /// Subscribe to lifecycle events of the cluster and all of its shards.
+    ///
+    /// Only events after subscribing are received. Subscribers that fall
+    /// more than 256 events behind skip the oldest ones.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use titanium_gateway::{ClusterEvent, ShardEvent};
+    ///
+    /// let mut lifecycle = cluster.lifecycle_events();
+    /// while let Ok(event) = lifecycle.recv().await {
+    ///     if let ClusterEvent::Shard(ShardEvent::Stopped { shard_id, reason }) = event {
+    ///         eprintln!("Shard {shard_id} stopped: {reason:?}");
+    ///         cluster.restart_shard(shard_id)?;
+    ///     }
+    /// }
+    /// ```
+    pub fn lifecycle_events(&self) -> broadcast::Receiver<ClusterEvent> {
+        self.lifecycle_tx.subscribe()
+    }
+
+    /// Receiver of raw dispatches from all shards, if the dispatch mode
+    /// includes them.
+    ///
+    /// While resharding, only the dispatches of the shards being replaced
+    /// are forwarded until the swap. Unlike parsed events, raw dispatches
+    /// are not deduplicated across the swap. The channel is bounded like the
+    /// event channel, see [`ClusterConfig::with_event_capacity`].
+    pub fn raw_dispatches(&self) -> Receiver<RawDispatch> {
+        self.raw_rx.clone()
+    }
+
+    /// Start a shard again after its task ended.
+    ///
+    /// The shard resumes its last session if it still has one.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::ShardNotFound` if the shard is not managed by
+    /// this cluster, or `GatewayError::Closed` if it is still running.
+    pub fn restart_shard(&self, shard_id: u16) -> Result<(), GatewayError> {
+        let active = self.active();
+        let (finished, presence, session) = active
+            .shards
+            .get(&shard_id)
+            .map(|r| {
+                (
+                    r.handle.is_finished(),
+                    r.shard.presence(),
+                    r.shard.session(),
+                )
+            })
+            .ok_or(GatewayError::ShardNotFound(shard_id))?;
+        if !finished {
+            return Err(GatewayError::Closed {
+                code: 0,
+                reason: format!("Shard {} is still running", shard_id),
+            });
+        }
+
+        info!(shard_id = shard_id, "Restarting shard");
+        self.spawn_shard(&active, shard_id, presence, session)
+    }
+
+    /// The shard set whose events are delivered.
+    fn active(&self) -> Arc<ShardSet> {
+        self.active.read().clone()
+    }
+
     /// Get the state of a specific shard.
     pub fn shard_state(&self, shard_id: u16) -> Option<ShardState> {
-        self.shards.get(&shard_id).map(|r| r.shard.state())
+        self.shard(shard_id).map(|shard| shard.state())
     }
 
     /// Get a reference to a specific shard.
     pub fn shard(&self, shard_id: u16) -> Option<Arc<Shard>> {
-        self.shards.get(&shard_id).map(|r| r.shard.clone())
+        let active = self.active();
+        let shard = active.shards.get(&shard_id).map(|r| r.shard.clone());
+        shard
+    }
+
+    /// Get a shard, failing with `GatewayError::ShardNotFound` if this cluster
+    /// does not manage it.
+    fn require_shard(&self, shard_id: u16) -> Result<Arc<Shard>, GatewayError> {
+        self.shard(shard_id)
+            .ok_or(GatewayError::ShardNotFound(shard_id))
     }
 
     /// Get the last measured latency for a specific shard.
     pub fn shard_latency(&self, shard_id: u16) -> Option<std::time::Duration> {
-        self.shards.get(&shard_id).and_then(|r| r.shard.latency())
+        self.shard(shard_id).and_then(|shard| shard.latency())
     }
 
     /// Get all shard IDs managed by this cluster.
     pub fn shard_ids(&self) -> Vec<u16> {
-        self.shards.iter().map(|r| *r.key()).collect()
+        let active = self.active();
+        let mut ids: Vec<u16> = active.shards.iter().map(|r| *r.key()).collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Get the metrics summed over all shards.
+    pub fn metrics(&self) -> &Arc<GatewayMetrics> {
+        &self.metrics
+    }
+
+    /// Get the metrics of every shard, ordered by shard ID.
+    pub fn shard_metrics(&self) -> Vec<Arc<ShardMetrics>> {
+        let active = self.active();
+        let mut metrics: Vec<Arc<ShardMetrics>> = active
+            .shards
+            .iter()
+            .map(|r| r.shard.metrics().clone())
+            .collect();
+        metrics.sort_by_key(|m| m.shard_id);
+        metrics
+    }
+
+    /// Get the total number of shards, across all clusters.
+    pub fn total_shards(&self) -> u16 {
+        self.active().total
     }
 
     /// Send a raw payload to a specific shard.
     pub fn send(&self, shard_id: u16, payload: serde_json::Value) -> Result<(), GatewayError> {
-        if let Some(runner) = self.shards.get(&shard_id) {
-            runner.shard.send_payload(&payload)
-        } else {
-            Err(GatewayError::Closed {
-                code: 0,
-                reason: format!("Shard {} not found", shard_id),
-            })
+        self.require_shard(shard_id)?.send_payload(&payload)
+    }
+
+    /// Send a raw payload to a specific shard, waiting for its command rate limit.
+    ///
+    /// See [`Shard::send_payload_async`].
+    pub async fn send_async(
+        &self,
+        shard_id: u16,
+        payload: serde_json::Value,
+    ) -> Result<(), GatewayError> {
+        let shard = self.require_shard(shard_id)?;
+        shard.send_payload_async(&payload).await
+    }
+
+    /// Get the shard ID responsible for a guild.
+    ///
+    /// Uses Discord's sharding formula: `(guild_id >> 22) % total_shards`.
+    pub fn shard_for_guild(&self, guild_id: Snowflake) -> u16 {
+        let total = u64::from(self.total_shards().max(1));
+        ((guild_id.get() >> 22) % total) as u16
+    }
+
+    /// Update the presence on every shard (Op 3).
+    ///
+    /// All shards are updated even if one fails; the first error is returned.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::RateLimited` if a shard's command rate limit is
+    /// exhausted, or `GatewayError::Closed` if a shard's command channel is closed.
+    pub fn update_presence(&self, presence: PresenceUpdate) -> Result<(), GatewayError> {
+        let mut result = Ok(());
+        let active = self.active();
+        for runner in active.shards.iter() {
+            if let Err(e) = runner.shard.update_presence(presence.clone()) {
+                warn!(shard_id = *runner.key(), error = %e, "Failed to update presence");
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+
+    /// Update the presence on every shard (Op 3), waiting for their command
+    /// rate limits.
+    ///
+    /// All shards are updated even if one fails; the first error is returned.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::Closed` if a shard's command channel is closed.
+    pub async fn update_presence_async(
+        &self,
+        presence: PresenceUpdate,
+    ) -> Result<(), GatewayError> {
+        let active = self.active();
+        let shards: Vec<Arc<Shard>> = active.shards.iter().map(|r| r.shard.clone()).collect();
+        let results = futures_util::future::join_all(
+            shards
+                .iter()
+                .map(|shard| shard.update_presence_async(presence.clone())),
+        )
+        .await;
+
+        let mut result = Ok(());
+        for (shard, shard_result) in shards.iter().zip(results) {
+            if let Err(e) = shard_result {
+                warn!(shard_id = shard.shard_id(), error = %e, "Failed to update presence");
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+
+    /// Join, move between or leave voice channels (Op 4) on the shard that owns the guild.
+    ///
+    /// See [`Shard::update_voice_state`].
+    ///
+    /// # Errors
+    /// Returns `GatewayError::ShardNotFound` if the guild's shard is not managed by this cluster.
+    pub fn update_voice_state(
+        &self,
+        guild_id: Snowflake,
+        channel_id: Option<Snowflake>,
+        self_mute: bool,
+        self_deaf: bool,
+    ) -> Result<VoiceConnectionFuture, GatewayError> {
+        let shard_id = self.shard_for_guild(guild_id);
+        let shard = self.require_shard(shard_id)?;
+        shard.update_voice_state(guild_id, channel_id, self_mute, self_deaf)
+    }
+
+    /// Join, move between or leave voice channels (Op 4) on the shard that
+    /// owns the guild, waiting for its command rate limit.
+    ///
+    /// See [`Shard::update_voice_state_async`].
+    ///
+    /// # Errors
+    /// Returns `GatewayError::ShardNotFound` if the guild's shard is not managed by this cluster.
+    pub async fn update_voice_state_async(
+        &self,
+        guild_id: Snowflake,
+        channel_id: Option<Snowflake>,
+        self_mute: bool,
+        self_deaf: bool,
+    ) -> Result<VoiceConnectionFuture, GatewayError> {
+        let shard = self.require_shard(self.shard_for_guild(guild_id))?;
+        shard
+            .update_voice_state_async(guild_id, channel_id, self_mute, self_deaf)
+            .await
+    }
+
+    /// Request members of a guild (Op 8) on the shard that owns it.
+    ///
+    /// See [`Shard::request_guild_members`].
+    ///
+    /// # Errors
+    /// Returns `GatewayError::ShardNotFound` if the guild's shard is not managed by this cluster.
+    pub fn request_guild_members(
+        &self,
+        request: RequestGuildMembersPayload,
+    ) -> Result<MemberChunkStream, GatewayError> {
+        let shard_id = self.shard_for_guild(request.guild_id);
+        let shard = self.require_shard(shard_id)?;
+        shard.request_guild_members(request)
+    }
+
+    /// Request members of a guild (Op 8) on the shard that owns it, waiting
+    /// for its command rate limit.
+    ///
+    /// See [`Shard::request_guild_members_async`].
+    ///
+    /// # Errors
+    /// Returns `GatewayError::ShardNotFound` if the guild's shard is not managed by this cluster.
+    pub async fn request_guild_members_async(
+        &self,
+        request: RequestGuildMembersPayload,
+    ) -> Result<MemberChunkStream, GatewayError> {
+        let shard = self.require_shard(self.shard_for_guild(request.guild_id))?;
+        shard.request_guild_members_async(request).await
+    }
+
+    /// Request the soundboard sounds of the given guilds (Op 31).
+    ///
+    /// Guilds are grouped by the shard that owns them. Discord responds with
+    /// an [`Event::SoundboardSounds`] dispatch per guild on that shard.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::ShardNotFound` if a guild's shard is not managed by this cluster.
+    pub fn request_soundboard_sounds(
+        &self,
+        guild_ids: impl IntoIterator<Item = Snowflake>,
+    ) -> Result<(), GatewayError> {
+        for (shard_id, guild_ids) in self.guilds_by_shard(guild_ids) {
+            self.require_shard(shard_id)?
+                .request_soundboard_sounds(guild_ids)?;
+        }
+        Ok(())
+    }
+
+    /// Request the soundboard sounds of the given guilds (Op 31), waiting for
+    /// the command rate limits of their shards.
+    ///
+    /// See [`request_soundboard_sounds`](Self::request_soundboard_sounds).
+    ///
+    /// # Errors
+    /// Returns `GatewayError::ShardNotFound` if a guild's shard is not managed by this cluster.
+    pub async fn request_soundboard_sounds_async(
+        &self,
+        guild_ids: impl IntoIterator<Item = Snowflake>,
+    ) -> Result<(), GatewayError> {
+        for (shard_id, guild_ids) in self.guilds_by_shard(guild_ids) {
+            self.require_shard(shard_id)?
+                .request_soundboard_sounds_async(guild_ids)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Group guilds by the shard that owns them.
+    fn guilds_by_shard(
+        &self,
+        guild_ids: impl IntoIterator<Item = Snowflake>,
+    ) -> HashMap<u16, Vec<Snowflake>> {
+        let mut by_shard: HashMap<u16, Vec<Snowflake>> = HashMap::new();
+        for guild_id in guild_ids {
+            by_shard
+                .entry(self.shard_for_guild(guild_id))
+                .or_default()
+                .push(guild_id);
         }
+
+        by_shard
     }
 
     /// Shutdown all shards gracefully.
+    ///
+    /// Sessions end, so the bot goes offline right away.
     pub async fn shutdown(&self) {
         info!("Shutting down cluster");
 
+        Self::shutdown_set(&self.active(), false).await;
+
+        info!("Cluster shutdown complete");
+    }
+
+    /// Shutdown all shards gracefully, keeping their sessions resumable.
+    ///
+    /// Returns the session of every shard that had one, to resume from with
+    /// [`ClusterConfig::with_sessions`] after a restart. Until they are
+    /// resumed or time out, Discord keeps the bot online.
+    pub async fn shutdown_resumable(&self) -> Vec<ShardSession> {
+        info!("Shutting down cluster, keeping sessions");
+
+        let sessions = Self::shutdown_set(&self.active(), true).await;
+
+        info!(sessions = sessions.len(), "Cluster shutdown complete");
+        sessions
+    }
+
+    /// Change the total shard count without downtime, using Discord's
+    /// recommended count from `/gateway/bot`.
+    ///
+    /// Returns the new total. See [`reshard_to`](Self::reshard_to).
+    ///
+    /// This requires the `auto-sharding` feature.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::Closed` if the recommendation cannot be fetched,
+    /// not enough session starts are left, or resharding fails.
+    #[cfg(feature = "auto-sharding")]
+    pub async fn reshard(&self, timeout: Duration) -> Result<u16, GatewayError> {
+        use titanium_http::HttpClient;
+
+        let client = HttpClient::new(&self.config.token).map_err(|_| GatewayError::Closed {
+            code: 0,
+            reason: "Failed to create HTTP client for resharding".into(),
+        })?;
+        let info = client
+            .get_gateway_bot()
+            .await
+            .map_err(|e| GatewayError::Closed {
+                code: 0,
+                reason: format!("Failed to fetch gateway info: {}", e),
+            })?;
+
+        let remaining = info.session_start_limit.remaining;
+        if remaining < u32::from(info.shards) {
+            return Err(GatewayError::Closed {
+                code: 0,
+                reason: format!(
+                    "Not enough session starts left: {} needed, {} remaining",
+                    info.shards, remaining
+                ),
+            });
+        }
+
+        self.reshard_to(info.shards, timeout).await?;
+        Ok(info.shards)
+    }
+
+    /// Change the total shard count without downtime.
+    ///
+    /// Brings up a second set of shards with the new total next to the
+    /// running one. Once every new shard received Ready and, with the
+    /// `GUILDS` intent, all of its guilds, events are delivered from the new
+    /// set and the old set is shut down. Events only the new set received
+    /// before the swap are delivered first, and duplicates of already
+    /// delivered events right after it are discarded; see the note on events
+    /// without an ID below.
+    ///
+    /// Only clusters managing [`ShardRange::All`] can reshard. Presences set
+    /// with [`update_presence`](Self::update_presence) carry over.
+    ///
+    /// Events are matched across the two sets by ID, so only create events
+    /// (messages, interactions, channels, ...) are guaranteed to be delivered
+    /// exactly once. Other events arriving right at the swap may be delivered
+    /// twice or not at all.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::Closed` if the cluster does not manage all
+    /// shards, another reshard is running, or the new set is not ready within
+    /// `timeout`. The old set keeps running in that case.
+    pub async fn reshard_to(
+        &self,
+        total_shards: u16,
+        timeout: Duration,
+    ) -> Result<(), GatewayError> {
+        if !matches!(self.config.shard_range, ShardRange::All { .. }) {
+            return Err(GatewayError::Closed {
+                code: 0,
+                reason: "Resharding requires ShardRange::All".to_string(),
+            });
+        }
+        if total_shards == 0 {
+            return Err(GatewayError::Closed {
+                code: 0,
+                reason: "Total shard count must be at least 1".to_string(),
+            });
+        }
+
+        let old = self.active();
+        if old.total == total_shards {
+            return Ok(());
+        }
+
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let shard_ids: Vec<u16> = (0..total_shards).collect();
+        // Without guild creates there is nothing to wait for
+        let wait_for_guilds = self.config.intents.contains(Intents::GUILDS)
+            && self
+                .config
+                .event_types
+                .contains(EventTypeFlags::GUILD_CREATE)
+            && self.config.dispatch_mode.is_parsed();
+        if !self.router.begin(generation, &shard_ids, wait_for_guilds) {
+            return Err(GatewayError::Closed {
+                code: 0,
+                reason: "A reshard is already in progress".to_string(),
+            });
+        }
+
+        info!(from = old.total, to = total_shards, "Resharding cluster");
+        self.emit(ClusterEvent::ReshardStarted {
+            from: old.total,
+            to: total_shards,
+        });
+
+        // Keep the presence the running shards use now
+        let presence = old
+            .shards
+            .iter()
+            .next()
+            .and_then(|runner| runner.shard.presence())
+            .or_else(|| self.config.presence.clone());
+
+        let next = Arc::new(ShardSet::new(total_shards, generation));
+        for shard_id in shard_ids {
+            if let Err(e) = self.spawn_shard(&next, shard_id, presence.clone(), None) {
+                self.router.abort();
+                Self::shutdown_set(&next, false).await;
+                self.emit(ClusterEvent::ReshardFailed {
+                    to: total_shards,
+                    reason: e.to_string(),
+                });
+                return Err(e);
+            }
+        }
+
+        // Wait for every new shard and its guilds
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let progressed = self.router.progressed();
+            if self.router.is_ready() {
+                break;
+            }
+            if tokio::time::timeout_at(deadline, progressed).await.is_err() {
+                warn!(
+                    to = total_shards,
+                    "New shards not ready in time, aborting reshard"
+                );
+                self.router.abort();
+                Self::shutdown_set(&next, false).await;
+
+                let reason = "Timed out waiting for resharded shards".to_string();
+                self.emit(ClusterEvent::ReshardFailed {
+                    to: total_shards,
+                    reason: reason.clone(),
+                });
+                return Err(GatewayError::Closed { code: 0, reason });
+            }
+        }
+
+        // Swap the event source
+        {
+            let mut active = self.active.write();
+            *active = next;
+            self.router.swap();
+        }
+
+        // Deliver the replay before the new set's live events. The task
+        // finishes it even if this future is dropped.
+        let router = self.router.clone();
+        let events = self.events.clone();
+        let replay = tokio::spawn(async move {
+            while let Some((shard_id, event)) = router.next_replay() {
+                let _ = events.send(shard_id, event, EventTypeFlags::empty()).await;
+            }
+        });
+        if let Err(e) = replay.await {
+            error!(error = %e, "Reshard replay task panicked");
+        }
+
+        Self::shutdown_set(&old, false).await;
+
+        info!(total = total_shards, "Reshard complete");
+        self.emit(ClusterEvent::ReshardCompleted {
+            total: total_shards,
+        });
+        Ok(())
+    }
+
+    /// Publish a lifecycle event.
+    fn emit(&self, event: ClusterEvent) {
+        // Fails only without subscribers
+        let _ = self.lifecycle_tx.send(event);
+    }
+
+    /// Shut down every shard of `set` and collect the sessions kept resumable.
+    async fn shutdown_set(set: &ShardSet, resumable: bool) -> Vec<ShardSession> {
         // Request shutdown for all shards
-        for shard in self.shards.iter() {
-            shard.shard.shutdown();
+        for shard in set.shards.iter() {
+            if resumable {
+                shard.shard.shutdown_resumable();
+            } else {
+                shard.shard.shutdown();
+            }
         }
 
         // Wait for all shard tasks to complete
-        for mut entry in self.shards.iter_mut() {
+        for mut entry in set.shards.iter_mut() {
             let runner = entry.value_mut();
             match (&mut runner.handle).await {
                 Ok(Err(e)) => {
@@ -347,7 +1074,13 @@ impl Cluster {
             }
         }
 
-        info!("Cluster shutdown complete");
+        let mut sessions: Vec<ShardSession> = set
+            .shards
+            .iter()
+            .filter_map(|runner| runner.shard.session())
+            .collect();
+        sessions.sort_by_key(|s| s.shard_id);
+        sessions
     }
 }
 
@@ -355,6 +1088,18 @@ impl Cluster {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_shard_for_guild() {
+        let config = ClusterConfig::new("token", Intents::GUILDS, ShardRange::All { total: 4 });
+        let (cluster, _rx) = Cluster::new(config);
+
+        let guild_id = Snowflake::new(81_384_788_765_712_384);
+        assert_eq!(
+            cluster.shard_for_guild(guild_id),
+            ((81_384_788_765_712_384u64 >> 22) % 4) as u16
+        );
+    }
+
     #[test]
     fn test_shard_range_all() {
         let range = ShardRange::All { total: 10 };
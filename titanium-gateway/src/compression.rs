@@ -1,8 +1,11 @@
-//! Zlib-stream decompression for Discord Gateway.
+//! Transport compression for Discord Gateway.
 //!
 //! Discord's Gateway supports zlib-stream compression where all messages
 //! are part of a single zlib context. Messages end with the zlib SYNC_FLUSH
 //! suffix (0x00 0x00 0xFF 0xFF).
+//!
+//! With the `zstd` feature, `zstd-stream` is also available. Every message
+//! is a flushed block of a single zstd frame spanning the connection.
 
 use flate2::{Decompress, FlushDecompress, Status};
 
@@ -132,6 +135,150 @@ impl Default for ZlibDecompressor {
     }
 }
 
+/// Transport compression requested with the `compress` query parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransportCompression {
+    /// No transport compression.
+    #[default]
+    None,
+    /// `zlib-stream` compression.
+    ZlibStream,
+    /// `zstd-stream` compression (smaller payloads, cheaper to decode).
+    #[cfg(feature = "zstd")]
+    ZstdStream,
+}
+
+impl TransportCompression {
+    /// Get the value for the Gateway URL `compress` query parameter.
+    pub fn as_str(&self) -> Option<&'static str> {
+        match self {
+            TransportCompression::None => None,
+            TransportCompression::ZlibStream => Some("zlib-stream"),
+            #[cfg(feature = "zstd")]
+            TransportCompression::ZstdStream => Some("zstd-stream"),
+        }
+    }
+}
+
+/// Zstd-stream decompressor for Gateway messages.
+///
+/// Like zlib-stream, all messages share one decompression context for the
+/// lifetime of the connection. Discord flushes after every message, so each
+/// WebSocket frame decompresses to exactly one payload.
+#[cfg(feature = "zstd")]
+pub struct ZstdDecompressor {
+    /// Persistent output buffer for decompression.
+    output_buffer: Vec<u8>,
+    /// Low-level zstd decompression context.
+    context: zstd::zstd_safe::DCtx<'static>,
+}
+
+#[cfg(feature = "zstd")]
+impl ZstdDecompressor {
+    /// Create a new zstd-stream decompressor.
+    pub fn new() -> Self {
+        Self {
+            output_buffer: Vec::with_capacity(32 * 1024), // 32KB output buffer
+            context: zstd::zstd_safe::DCtx::create(),
+        }
+    }
+
+    /// Decompress one message.
+    ///
+    /// Returns `Some(&mut [u8])` with the decompressed payload, or `None` if
+    /// the frame produced no output.
+    pub fn push(&mut self, data: &[u8]) -> Result<Option<&mut [u8]>, std::io::Error> {
+        use zstd::zstd_safe::{InBuffer, OutBuffer};
+
+        self.output_buffer.clear();
+        let mut input = InBuffer::around(data);
+
+        loop {
+            // Reserve space if needed
+            if self.output_buffer.len() == self.output_buffer.capacity() {
+                self.output_buffer.reserve(32 * 1024);
+            }
+
+            // Writes into spare capacity and updates the length.
+            let len = self.output_buffer.len();
+            let mut output = OutBuffer::around_pos(&mut self.output_buffer, len);
+            self.context
+                .decompress_stream(&mut output, &mut input)
+                .map_err(|code| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        zstd::zstd_safe::get_error_name(code),
+                    )
+                })?;
+            let output_full = output.pos() == output.capacity();
+
+            // Done once all input is consumed and zstd has nothing buffered,
+            // which it signals by not filling the output buffer.
+            if input.pos() == data.len() && !output_full {
+                break;
+            }
+        }
+
+        if self.output_buffer.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(&mut self.output_buffer))
+    }
+
+    /// Reset the decompressor (for new connections).
+    pub fn reset(&mut self) {
+        self.output_buffer.clear();
+        // Resetting the session of a valid context cannot fail.
+        let _ = self
+            .context
+            .reset(zstd::zstd_safe::ResetDirective::SessionOnly);
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl Default for ZstdDecompressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Decompressor for the configured [`TransportCompression`].
+pub(crate) enum TransportDecompressor {
+    Zlib(ZlibDecompressor),
+    #[cfg(feature = "zstd")]
+    Zstd(ZstdDecompressor),
+}
+
+impl TransportDecompressor {
+    /// Create a decompressor, or `None` if `compression` is disabled.
+    pub(crate) fn new(compression: TransportCompression) -> Option<Self> {
+        match compression {
+            TransportCompression::None => None,
+            TransportCompression::ZlibStream => Some(Self::Zlib(ZlibDecompressor::new())),
+            #[cfg(feature = "zstd")]
+            TransportCompression::ZstdStream => Some(Self::Zstd(ZstdDecompressor::new())),
+        }
+    }
+
+    /// Push a compressed frame, returning a complete message if one is available.
+    pub(crate) fn push(&mut self, data: &[u8]) -> Result<Option<&mut [u8]>, std::io::Error> {
+        match self {
+            Self::Zlib(d) => d.push(data),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(d) => d.push(data),
+        }
+    }
+
+    /// Reset the decompressor (for new connections).
+    pub(crate) fn reset(&mut self) {
+        match self {
+            Self::Zlib(d) => d.reset(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(d) => d.reset(),
+        }
+    }
+}
+
 /// Transport-level zlib compression (per-message).
 ///
 /// Unlike zlib-stream, this decompresses individual messages.
@@ -206,4 +353,34 @@ mod tests {
         d.reset(); // Should work
         assert!(d.buffer.is_empty());
     }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_zstd_stream() {
+        use zstd::zstd_safe::{CCtx, InBuffer, OutBuffer};
+
+        let messages = [
+            r#"{"op":10,"d":{"heartbeat_interval":41250}}"#,
+            r#"{"t":"READY","s":1,"op":0,"d":{"v":10}}"#,
+        ];
+
+        // One zstd frame for the whole connection, flushed after each message
+        let mut cctx = CCtx::create();
+        let mut decompressor = ZstdDecompressor::new();
+        for message in messages {
+            let mut frame = Vec::with_capacity(256);
+            let mut input = InBuffer::around(message.as_bytes());
+            let mut output = OutBuffer::around(&mut frame);
+            cctx.compress_stream(&mut output, &mut input).unwrap();
+            cctx.flush_stream(&mut output).unwrap();
+
+            let decompressed = decompressor.push(&frame).unwrap().unwrap();
+            assert_eq!(decompressed, message.as_bytes());
+        }
+
+        assert_eq!(
+            TransportCompression::ZstdStream.as_str(),
+            Some("zstd-stream")
+        );
+    }
 }
@@ -16,6 +16,10 @@ pub enum GatewayError {
     #[error("JSON decode error: {0}")]
     JsonDecode(String),
 
+    /// Failed to encode or decode an ETF payload.
+    #[error("ETF error: {0}")]
+    Etf(String),
+
     /// Session was invalidated by Discord.
     /// The boolean indicates if the session is resumable.
     #[error("Session invalidated, resumable: {resumable}")]
@@ -57,10 +61,18 @@ pub enum GatewayError {
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 
+    /// Proxy handshake failed.
+    #[error("Proxy error: {0}")]
+    Proxy(String),
+
     /// Shard is not connected.
     #[error("Shard not connected")]
     NotConnected,
 
+    /// Shard is not managed by this cluster.
+    #[error("Shard {0} not found")]
+    ShardNotFound(u16),
+
     /// Rate limited.
     #[error("Rate limited, retry after {retry_after_ms}ms")]
     RateLimited {
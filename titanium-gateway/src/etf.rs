@@ -1,4 +1,4 @@
-//! Erlang Term Format (ETF) decoder for Discord Gateway.
+//! Erlang Term Format (ETF) support for Discord Gateway.
 //!
 //! Discord supports ETF as an alternative to JSON for Gateway payloads.
 //! ETF is more compact (~30% smaller) and can be faster to parse.
@@ -17,15 +17,26 @@
 //! # Usage
 //!
 //! ```ignore
-//! use titanium_gateway::etf::EtfDecoder;
+//! use titanium_gateway::etf::{self, EtfDecoder, EtfEncoder};
 //!
 //! let bytes: &[u8] = /* ETF-encoded payload */;
 //! let value = EtfDecoder::decode(bytes)?;
-//! let json = EtfDecoder::to_json_value(&value)?;
+//! let payload: GatewayPayload<Hello> = etf::from_term(&value)?;
+//!
+//! let outgoing = EtfEncoder::encode(&payload)?;
 //! ```
+//!
+//! Shards use ETF when [`ShardConfig::encoding`](crate::ShardConfig::encoding)
+//! is set to [`GatewayEncoding::Etf`].
 
 use crate::error::GatewayError;
 
+mod de;
+mod ser;
+
+pub use de::{from_slice, from_term, EtfDeserializer};
+pub use ser::{Compound, EtfEncoder};
+
 /// ETF format version tag.
 const ETF_VERSION: u8 = 131;
 
@@ -100,7 +111,7 @@ impl<'a> EtfDecoder<'a> {
         // Check version byte
         let version = self.read_u8()?;
         if version != ETF_VERSION {
-            return Err(GatewayError::JsonDecode(format!(
+            return Err(GatewayError::Etf(format!(
                 "Invalid ETF version: expected {}, got {}",
                 ETF_VERSION, version
             )));
@@ -128,20 +139,21 @@ impl<'a> EtfDecoder<'a> {
                 // Old float format: 31 bytes ASCII representation
                 let bytes = self.read_bytes(31)?;
                 let s = std::str::from_utf8(bytes)
-                    .map_err(|e| GatewayError::JsonDecode(format!("Invalid float string: {}", e)))?
+                    .map_err(|e| GatewayError::Etf(format!("Invalid float string: {}", e)))?
                     .trim_end_matches('\0');
                 let value: f64 = s
                     .parse()
-                    .map_err(|e| GatewayError::JsonDecode(format!("Invalid float: {}", e)))?;
+                    .map_err(|e| GatewayError::Etf(format!("Invalid float: {}", e)))?;
                 Ok(EtfTerm::Float(value))
             }
 
             tags::NEW_FLOAT => {
                 let bytes = self.read_bytes(8)?;
-                let value =
-                    f64::from_be_bytes(bytes.try_into().map_err(|_| {
-                        GatewayError::JsonDecode("Invalid float bytes".to_string())
-                    })?);
+                let value = f64::from_be_bytes(
+                    bytes
+                        .try_into()
+                        .map_err(|_| GatewayError::Etf("Invalid float bytes".to_string()))?,
+                );
                 Ok(EtfTerm::Float(value))
             }
 
@@ -267,9 +279,9 @@ impl<'a> EtfDecoder<'a> {
 
                 let mut decoder = ZlibDecoder::new(compressed_data);
                 let mut decompressed = Vec::with_capacity(uncompressed_size);
-                decoder.read_to_end(&mut decompressed).map_err(|e| {
-                    GatewayError::JsonDecode(format!("ETF decompression failed: {}", e))
-                })?;
+                decoder
+                    .read_to_end(&mut decompressed)
+                    .map_err(|e| GatewayError::Etf(format!("ETF decompression failed: {}", e)))?;
 
                 // Skip to end of compressed data
                 self.pos = self.data.len();
@@ -279,7 +291,7 @@ impl<'a> EtfDecoder<'a> {
                 inner.decode_value()
             }
 
-            _ => Err(GatewayError::JsonDecode(format!(
+            _ => Err(GatewayError::Etf(format!(
                 "Unknown ETF tag: {} at position {}",
                 tag,
                 self.pos - 1
@@ -291,9 +303,7 @@ impl<'a> EtfDecoder<'a> {
     #[inline]
     fn read_u8(&mut self) -> Result<u8, GatewayError> {
         if self.pos >= self.data.len() {
-            return Err(GatewayError::JsonDecode(
-                "Unexpected end of ETF data".to_string(),
-            ));
+            return Err(GatewayError::Etf("Unexpected end of ETF data".to_string()));
         }
         let byte = self.data[self.pos];
         self.pos += 1;
@@ -304,9 +314,7 @@ impl<'a> EtfDecoder<'a> {
     #[inline]
     fn read_u16(&mut self) -> Result<u16, GatewayError> {
         if self.pos + 2 > self.data.len() {
-            return Err(GatewayError::JsonDecode(
-                "Unexpected end of ETF data".to_string(),
-            ));
+            return Err(GatewayError::Etf("Unexpected end of ETF data".to_string()));
         }
         let value = u16::from_be_bytes([self.data[self.pos], self.data[self.pos + 1]]);
         self.pos += 2;
@@ -317,9 +325,7 @@ impl<'a> EtfDecoder<'a> {
     #[inline]
     fn read_u32(&mut self) -> Result<u32, GatewayError> {
         if self.pos + 4 > self.data.len() {
-            return Err(GatewayError::JsonDecode(
-                "Unexpected end of ETF data".to_string(),
-            ));
+            return Err(GatewayError::Etf("Unexpected end of ETF data".to_string()));
         }
         let value = u32::from_be_bytes([
             self.data[self.pos],
@@ -335,9 +341,7 @@ impl<'a> EtfDecoder<'a> {
     #[inline]
     fn read_i32(&mut self) -> Result<i32, GatewayError> {
         if self.pos + 4 > self.data.len() {
-            return Err(GatewayError::JsonDecode(
-                "Unexpected end of ETF data".to_string(),
-            ));
+            return Err(GatewayError::Etf("Unexpected end of ETF data".to_string()));
         }
         let value = i32::from_be_bytes([
             self.data[self.pos],
@@ -352,9 +356,7 @@ impl<'a> EtfDecoder<'a> {
     /// Read n bytes.
     fn read_bytes(&mut self, n: usize) -> Result<&'a [u8], GatewayError> {
         if self.pos + n > self.data.len() {
-            return Err(GatewayError::JsonDecode(
-                "Unexpected end of ETF data".to_string(),
-            ));
+            return Err(GatewayError::Etf("Unexpected end of ETF data".to_string()));
         }
         let bytes = &self.data[self.pos..self.pos + n];
         self.pos += n;
@@ -380,7 +382,7 @@ impl<'a> EtfDecoder<'a> {
             }
             EtfTerm::Float(f) => serde_json::Number::from_f64(*f)
                 .map(serde_json::Value::Number)
-                .ok_or_else(|| GatewayError::JsonDecode("Invalid float value".to_string())),
+                .ok_or_else(|| GatewayError::Etf("Invalid float value".to_string())),
             EtfTerm::Atom(s) => {
                 // Discord uses atoms for special values
                 match s.as_str() {
@@ -559,4 +561,40 @@ mod tests {
         let json = EtfDecoder::to_json_value(&term).unwrap();
         assert_eq!(json, serde_json::Value::Bool(false));
     }
+
+    #[test]
+    fn test_encode_roundtrip() {
+        use crate::opcode::OpCode;
+        use crate::payload::{GatewayPayload, HelloPayload};
+
+        let payload = GatewayPayload {
+            op: OpCode::Hello,
+            d: Some(serde_json::json!({ "heartbeat_interval": 41250 })),
+            s: None,
+            t: None,
+        };
+        let bytes = EtfEncoder::encode(&payload).unwrap();
+        assert_eq!(bytes[0], ETF_VERSION);
+
+        let decoded: GatewayPayload<HelloPayload> = from_slice(&bytes).unwrap();
+        assert_eq!(decoded.op, OpCode::Hello);
+        assert_eq!(decoded.d.unwrap().heartbeat_interval, 41250);
+        assert!(decoded.s.is_none());
+
+        let big = EtfEncoder::encode(&-(1i64 << 40)).unwrap();
+        assert_eq!(
+            EtfDecoder::decode(&big).unwrap(),
+            EtfTerm::BigInt(-(1i128 << 40))
+        );
+
+        // Mismatches are reported as ETF errors, not JSON ones
+        assert!(matches!(
+            from_slice::<HelloPayload>(&big),
+            Err(GatewayError::Etf(_))
+        ));
+        assert!(matches!(
+            EtfDecoder::decode(&[0]),
+            Err(GatewayError::Etf(_))
+        ));
+    }
 }
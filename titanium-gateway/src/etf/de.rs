@@ -0,0 +1,281 @@
+//! Serde deserializer reading [`EtfTerm`]s straight into typed values.
+//!
+//! This skips the `serde_json::Value` round trip of
+//! [`EtfDecoder::to_json_value`](super::EtfDecoder::to_json_value).
+
+use super::{EtfDecoder, EtfTerm};
+use crate::error::GatewayError;
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use serde::Deserialize;
+
+impl de::Error for GatewayError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        GatewayError::Etf(msg.to_string())
+    }
+}
+
+/// Deserialize a value from a decoded term.
+///
+/// # Errors
+/// Returns `GatewayError::Etf` if the term does not match `T`.
+pub fn from_term<'de, T: Deserialize<'de>>(term: &'de EtfTerm) -> Result<T, GatewayError> {
+    T::deserialize(EtfDeserializer::new(term))
+}
+
+/// Decode ETF bytes and deserialize them into `T`.
+///
+/// # Errors
+/// Returns `GatewayError::Etf` if the bytes are not valid ETF or do not match `T`.
+pub fn from_slice<T: DeserializeOwned>(data: &[u8]) -> Result<T, GatewayError> {
+    let term = EtfDecoder::decode(data)?;
+    from_term(&term)
+}
+
+/// Serde [`Deserializer`](de::Deserializer) over a borrowed [`EtfTerm`].
+///
+/// Follows Discord's ETF conventions: the atoms `nil`, `true` and `false`
+/// map to null and booleans, binaries map to strings, and the empty list
+/// (`NIL`) maps to an empty sequence. Integers are accepted where strings are
+/// expected, since Discord sends snowflakes as 64-bit integers in ETF.
+#[derive(Debug, Clone, Copy)]
+pub struct EtfDeserializer<'de> {
+    term: &'de EtfTerm,
+}
+
+impl<'de> EtfDeserializer<'de> {
+    /// Create a deserializer for `term`.
+    pub fn new(term: &'de EtfTerm) -> Self {
+        Self { term }
+    }
+
+    fn is_null(&self) -> bool {
+        matches!(self.term, EtfTerm::Atom(a) if a == "nil" || a == "null")
+    }
+
+    fn as_str(&self) -> Option<&'de str> {
+        match self.term {
+            EtfTerm::Atom(s) | EtfTerm::String(s) => Some(s),
+            EtfTerm::Binary(b) => std::str::from_utf8(b).ok(),
+            _ => None,
+        }
+    }
+
+    fn integer_string(&self) -> Option<String> {
+        match self.term {
+            EtfTerm::SmallInt(n) => Some(n.to_string()),
+            EtfTerm::Int(n) => Some(n.to_string()),
+            EtfTerm::BigInt(n) => Some(n.to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for EtfDeserializer<'de> {
+    type Error = GatewayError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, GatewayError> {
+        match self.term {
+            EtfTerm::SmallInt(n) => visitor.visit_u64(u64::from(*n)),
+            EtfTerm::Int(n) => visitor.visit_i64(i64::from(*n)),
+            EtfTerm::BigInt(n) => {
+                if let Ok(n) = u64::try_from(*n) {
+                    visitor.visit_u64(n)
+                } else if let Ok(n) = i64::try_from(*n) {
+                    visitor.visit_i64(n)
+                } else {
+                    visitor.visit_i128(*n)
+                }
+            }
+            EtfTerm::Float(f) => visitor.visit_f64(*f),
+            EtfTerm::Atom(atom) => match atom.as_str() {
+                "nil" | "null" => visitor.visit_unit(),
+                "true" => visitor.visit_bool(true),
+                "false" => visitor.visit_bool(false),
+                _ => visitor.visit_borrowed_str(atom),
+            },
+            EtfTerm::String(s) => visitor.visit_borrowed_str(s),
+            EtfTerm::Binary(bytes) => match std::str::from_utf8(bytes) {
+                Ok(s) => visitor.visit_borrowed_str(s),
+                Err(_) => visitor.visit_borrowed_bytes(bytes),
+            },
+            EtfTerm::Nil => visitor.visit_seq(SeqDeserializer { iter: [].iter() }),
+            EtfTerm::List(items) | EtfTerm::Tuple(items) => {
+                visitor.visit_seq(SeqDeserializer { iter: items.iter() })
+            }
+            EtfTerm::Map(pairs) => visitor.visit_map(MapDeserializer {
+                iter: pairs.iter(),
+                value: None,
+            }),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, GatewayError> {
+        if self.is_null() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, GatewayError> {
+        if self.is_null() || matches!(self.term, EtfTerm::Nil) {
+            visitor.visit_unit()
+        } else {
+            self.deserialize_any(visitor)
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, GatewayError> {
+        match self.integer_string() {
+            Some(s) => visitor.visit_string(s),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, GatewayError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, GatewayError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, GatewayError> {
+        if let Some(variant) = self.as_str() {
+            return visitor.visit_enum(variant.into_deserializer());
+        }
+        match self.term {
+            EtfTerm::Map(pairs) if pairs.len() == 1 => visitor.visit_enum(EnumDeserializer {
+                variant: &pairs[0].0,
+                value: &pairs[0].1,
+            }),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        bytes byte_buf unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+struct SeqDeserializer<'de> {
+    iter: std::slice::Iter<'de, EtfTerm>,
+}
+
+impl<'de> SeqAccess<'de> for SeqDeserializer<'de> {
+    type Error = GatewayError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, GatewayError> {
+        self.iter
+            .next()
+            .map(|term| seed.deserialize(EtfDeserializer::new(term)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct MapDeserializer<'de> {
+    iter: std::slice::Iter<'de, (EtfTerm, EtfTerm)>,
+    value: Option<&'de EtfTerm>,
+}
+
+impl<'de> MapAccess<'de> for MapDeserializer<'de> {
+    type Error = GatewayError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, GatewayError> {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(EtfDeserializer::new(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, GatewayError> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| GatewayError::Etf("ETF map value missing".to_string()))?;
+        seed.deserialize(EtfDeserializer::new(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct EnumDeserializer<'de> {
+    variant: &'de EtfTerm,
+    value: &'de EtfTerm,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer<'de> {
+    type Error = GatewayError;
+    type Variant = EtfDeserializer<'de>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), GatewayError> {
+        let variant = seed.deserialize(EtfDeserializer::new(self.variant))?;
+        Ok((variant, EtfDeserializer::new(self.value)))
+    }
+}
+
+impl<'de> VariantAccess<'de> for EtfDeserializer<'de> {
+    type Error = GatewayError;
+
+    fn unit_variant(self) -> Result<(), GatewayError> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, GatewayError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, GatewayError> {
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, GatewayError> {
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+}
@@ -0,0 +1,402 @@
+//! Serde serializer producing ETF for outgoing gateway payloads.
+
+use super::{tags, ETF_VERSION};
+use crate::error::GatewayError;
+use serde::ser::{self, Serialize};
+
+impl ser::Error for GatewayError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        GatewayError::Etf(msg.to_string())
+    }
+}
+
+/// ETF encoder for outgoing payloads.
+///
+/// Mirrors Discord's ETF conventions: `None`/unit become the `nil` atom,
+/// booleans become the `true`/`false` atoms, and strings and map keys are
+/// sent as binaries.
+///
+/// ```
+/// use titanium_gateway::etf::{from_slice, EtfEncoder};
+/// use titanium_gateway::{GatewayPayload, OpCode};
+///
+/// let bytes = EtfEncoder::encode(&GatewayPayload::new(OpCode::Heartbeat, Some(42u64))).unwrap();
+/// let payload: GatewayPayload<Option<u64>> = from_slice(&bytes).unwrap();
+/// assert_eq!(payload.d, Some(Some(42)));
+/// ```
+#[derive(Debug)]
+pub struct EtfEncoder<'a> {
+    out: &'a mut Vec<u8>,
+}
+
+impl<'a> EtfEncoder<'a> {
+    /// Create an encoder appending terms (without the version byte) to `out`.
+    pub fn new(out: &'a mut Vec<u8>) -> Self {
+        Self { out }
+    }
+
+    /// Encode a value into a complete ETF payload.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::Etf` if the value cannot be represented in ETF.
+    pub fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, GatewayError> {
+        let mut out = Vec::with_capacity(128);
+        out.push(ETF_VERSION);
+        value.serialize(EtfEncoder::new(&mut out))?;
+        Ok(out)
+    }
+
+    fn write_atom(&mut self, atom: &str) {
+        // Atoms used here are short ASCII literals.
+        self.out.push(tags::SMALL_ATOM_UTF8);
+        self.out.push(atom.len() as u8);
+        self.out.extend_from_slice(atom.as_bytes());
+    }
+
+    fn write_binary(&mut self, bytes: &[u8]) -> Result<(), GatewayError> {
+        let len = u32::try_from(bytes.len())
+            .map_err(|_| GatewayError::Etf("ETF binary too large".to_string()))?;
+        self.out.push(tags::BINARY);
+        self.out.extend_from_slice(&len.to_be_bytes());
+        self.out.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    fn write_int(&mut self, value: i128) {
+        if let Ok(small) = u8::try_from(value) {
+            self.out.extend_from_slice(&[tags::SMALL_INTEGER, small]);
+        } else if let Ok(int) = i32::try_from(value) {
+            self.out.push(tags::INTEGER);
+            self.out.extend_from_slice(&int.to_be_bytes());
+        } else {
+            let magnitude = value.unsigned_abs().to_le_bytes();
+            let len = magnitude.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+            self.out.push(tags::SMALL_BIG);
+            self.out.push(len as u8);
+            self.out.push(u8::from(value < 0));
+            self.out.extend_from_slice(&magnitude[..len]);
+        }
+    }
+
+    /// Write a container header with a placeholder length, patched by [`Compound`].
+    fn begin(self, tag: u8) -> Compound<'a> {
+        self.out.push(tag);
+        let len_pos = self.out.len();
+        self.out.extend_from_slice(&[0; 4]);
+        Compound {
+            out: self.out,
+            tag,
+            len_pos,
+            count: 0,
+        }
+    }
+
+    /// Write `{variant => ...}` for enum variants carrying data.
+    fn begin_variant(&mut self, variant: &str) -> Result<(), GatewayError> {
+        self.out.push(tags::MAP);
+        self.out.extend_from_slice(&1u32.to_be_bytes());
+        self.write_binary(variant.as_bytes())
+    }
+}
+
+/// In-progress list or map.
+#[derive(Debug)]
+pub struct Compound<'a> {
+    out: &'a mut Vec<u8>,
+    tag: u8,
+    len_pos: usize,
+    count: u32,
+}
+
+impl Compound<'_> {
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), GatewayError> {
+        value.serialize(EtfEncoder::new(self.out))
+    }
+
+    fn finish(self) -> Result<(), GatewayError> {
+        self.out[self.len_pos..self.len_pos + 4].copy_from_slice(&self.count.to_be_bytes());
+        if self.tag == tags::LIST {
+            // Proper lists end with a NIL tail
+            self.out.push(tags::NIL);
+        }
+        Ok(())
+    }
+}
+
+impl<'a> ser::Serializer for EtfEncoder<'a> {
+    type Ok = ();
+    type Error = GatewayError;
+    type SerializeSeq = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = Compound<'a>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
+    fn serialize_bool(mut self, v: bool) -> Result<(), GatewayError> {
+        self.write_atom(if v { "true" } else { "false" });
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), GatewayError> {
+        self.serialize_i128(i128::from(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), GatewayError> {
+        self.serialize_i128(i128::from(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), GatewayError> {
+        self.serialize_i128(i128::from(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), GatewayError> {
+        self.serialize_i128(i128::from(v))
+    }
+
+    fn serialize_i128(mut self, v: i128) -> Result<(), GatewayError> {
+        self.write_int(v);
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), GatewayError> {
+        self.serialize_i128(i128::from(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), GatewayError> {
+        self.serialize_i128(i128::from(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), GatewayError> {
+        self.serialize_i128(i128::from(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), GatewayError> {
+        self.serialize_i128(i128::from(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), GatewayError> {
+        self.serialize_f64(f64::from(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), GatewayError> {
+        self.out.push(tags::NEW_FLOAT);
+        self.out.extend_from_slice(&v.to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), GatewayError> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(mut self, v: &str) -> Result<(), GatewayError> {
+        self.write_binary(v.as_bytes())
+    }
+
+    fn serialize_bytes(mut self, v: &[u8]) -> Result<(), GatewayError> {
+        self.write_binary(v)
+    }
+
+    fn serialize_none(mut self) -> Result<(), GatewayError> {
+        self.write_atom("nil");
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), GatewayError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), GatewayError> {
+        self.serialize_none()
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), GatewayError> {
+        self.serialize_none()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<(), GatewayError> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), GatewayError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        mut self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), GatewayError> {
+        self.begin_variant(variant)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Compound<'a>, GatewayError> {
+        Ok(self.begin(tags::LIST))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Compound<'a>, GatewayError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a>, GatewayError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        mut self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>, GatewayError> {
+        self.begin_variant(variant)?;
+        Ok(self.begin(tags::LIST))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Compound<'a>, GatewayError> {
+        Ok(self.begin(tags::MAP))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>, GatewayError> {
+        Ok(self.begin(tags::MAP))
+    }
+
+    fn serialize_struct_variant(
+        mut self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>, GatewayError> {
+        self.begin_variant(variant)?;
+        Ok(self.begin(tags::MAP))
+    }
+}
+
+impl ser::SerializeSeq for Compound<'_> {
+    type Ok = ();
+    type Error = GatewayError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), GatewayError> {
+        self.count += 1;
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), GatewayError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for Compound<'_> {
+    type Ok = ();
+    type Error = GatewayError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), GatewayError> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), GatewayError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound<'_> {
+    type Ok = ();
+    type Error = GatewayError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), GatewayError> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), GatewayError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound<'_> {
+    type Ok = ();
+    type Error = GatewayError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), GatewayError> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), GatewayError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeMap for Compound<'_> {
+    type Ok = ();
+    type Error = GatewayError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), GatewayError> {
+        self.count += 1;
+        self.element(key)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), GatewayError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), GatewayError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for Compound<'_> {
+    type Ok = ();
+    type Error = GatewayError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), GatewayError> {
+        self.count += 1;
+        self.element(key)?;
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), GatewayError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for Compound<'_> {
+    type Ok = ();
+    type Error = GatewayError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), GatewayError> {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> Result<(), GatewayError> {
+        self.finish()
+    }
+}
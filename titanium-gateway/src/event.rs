@@ -266,6 +266,9 @@ pub enum Event<'a> {
     /// Guild soundboard sounds were updated.
     GuildSoundboardSoundsUpdate(Arc<GuildSoundboardSoundsUpdateEvent<'a>>),
 
+    /// Response to a Request Soundboard Sounds command.
+    SoundboardSounds(Arc<SoundboardSoundsEvent<'a>>),
+
     // =========================================================================
     // Presence & Typing Events
     // =========================================================================
@@ -622,6 +625,10 @@ macro_rules! impl_parse_event {
                     $deser!(GuildSoundboardSoundsUpdateEvent);
                 Ok(Event::GuildSoundboardSoundsUpdate(Arc::new(sounds)))
             }
+            "SOUNDBOARD_SOUNDS" => {
+                let sounds: SoundboardSoundsEvent = $deser!(SoundboardSoundsEvent);
+                Ok(Event::SoundboardSounds(Arc::new(sounds)))
+            }
 
             // Presence & Typing Events
             "TYPING_START" => {
@@ -708,6 +715,33 @@ pub fn parse_event<'a>(
     impl_parse_event!(event_name, deser_simd)
 }
 
+/// Parse an event from its name and decoded ETF data.
+///
+/// Event types are deserialized directly from the term without going
+/// through an intermediate JSON value.
+pub fn parse_event_etf(
+    event_name: &str,
+    data: &crate::etf::EtfTerm,
+) -> Result<Event<'static>, GatewayError> {
+    use serde::Deserialize;
+
+    macro_rules! deser_etf {
+        (UNKNOWN_VARIANT) => {
+            Ok(Event::Unknown {
+                name: event_name.to_owned(),
+                data: titanium_model::json::Value::deserialize(crate::etf::EtfDeserializer::new(
+                    data,
+                ))?,
+            })
+        };
+        ($T:ty) => {
+            crate::etf::from_term::<$T>(data)?
+        };
+    }
+
+    impl_parse_event!(event_name, deser_etf)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
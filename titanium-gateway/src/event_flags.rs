@@ -0,0 +1,396 @@
+//! Dispatch event type filtering.
+//!
+//! Shards only deserialize the dispatches whose type is in the configured
+//! [`EventTypeFlags`]. Other dispatches are dropped after reading their name
+//! (and sequence number), which saves most of the parsing work for events
+//! the application ignores.
+
+use bitflags::bitflags;
+
+bitflags! {
+    /// Set of dispatch event types to deserialize and deliver.
+    ///
+    /// Each flag is named after its dispatch (`t` field). `READY` and
+    /// `RESUMED` are always processed and delivered, whether set or not.
+    /// Guild member chunks and voice updates answering a pending
+    /// [`Shard::request_guild_members`](crate::Shard::request_guild_members)
+    /// or [`Shard::update_voice_state`](crate::Shard::update_voice_state) are
+    /// processed for those calls, but only delivered if set.
+    ///
+    /// Shard metrics track guilds through `GUILD_CREATE` and `GUILD_DELETE`,
+    /// so keep them to get accurate guild counts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use titanium_gateway::EventTypeFlags;
+    ///
+    /// let types = EventTypeFlags::all()
+    ///     - EventTypeFlags::PRESENCE_UPDATE
+    ///     - EventTypeFlags::TYPING_START;
+    /// assert!(types.wants("MESSAGE_CREATE"));
+    /// assert!(!types.wants("TYPING_START"));
+    /// ```
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct EventTypeFlags: u128 {
+        /// `READY` dispatches.
+        const READY = 1 << 0;
+        /// `RESUMED` dispatches.
+        const RESUMED = 1 << 1;
+        /// `GUILD_CREATE` dispatches.
+        const GUILD_CREATE = 1 << 2;
+        /// `GUILD_UPDATE` dispatches.
+        const GUILD_UPDATE = 1 << 3;
+        /// `GUILD_DELETE` dispatches.
+        const GUILD_DELETE = 1 << 4;
+        /// `GUILD_BAN_ADD` dispatches.
+        const GUILD_BAN_ADD = 1 << 5;
+        /// `GUILD_BAN_REMOVE` dispatches.
+        const GUILD_BAN_REMOVE = 1 << 6;
+        /// `GUILD_EMOJIS_UPDATE` dispatches.
+        const GUILD_EMOJIS_UPDATE = 1 << 7;
+        /// `GUILD_STICKERS_UPDATE` dispatches.
+        const GUILD_STICKERS_UPDATE = 1 << 8;
+        /// `GUILD_INTEGRATIONS_UPDATE` dispatches.
+        const GUILD_INTEGRATIONS_UPDATE = 1 << 9;
+        /// `GUILD_AUDIT_LOG_ENTRY_CREATE` dispatches.
+        const GUILD_AUDIT_LOG_ENTRY_CREATE = 1 << 10;
+        /// `GUILD_MEMBER_ADD` dispatches.
+        const GUILD_MEMBER_ADD = 1 << 11;
+        /// `GUILD_MEMBER_REMOVE` dispatches.
+        const GUILD_MEMBER_REMOVE = 1 << 12;
+        /// `GUILD_MEMBER_UPDATE` dispatches.
+        const GUILD_MEMBER_UPDATE = 1 << 13;
+        /// `GUILD_MEMBERS_CHUNK` dispatches.
+        const GUILD_MEMBERS_CHUNK = 1 << 14;
+        /// `GUILD_ROLE_CREATE` dispatches.
+        const GUILD_ROLE_CREATE = 1 << 15;
+        /// `GUILD_ROLE_UPDATE` dispatches.
+        const GUILD_ROLE_UPDATE = 1 << 16;
+        /// `GUILD_ROLE_DELETE` dispatches.
+        const GUILD_ROLE_DELETE = 1 << 17;
+        /// `CHANNEL_CREATE` dispatches.
+        const CHANNEL_CREATE = 1 << 18;
+        /// `CHANNEL_UPDATE` dispatches.
+        const CHANNEL_UPDATE = 1 << 19;
+        /// `CHANNEL_DELETE` dispatches.
+        const CHANNEL_DELETE = 1 << 20;
+        /// `CHANNEL_PINS_UPDATE` dispatches.
+        const CHANNEL_PINS_UPDATE = 1 << 21;
+        /// `THREAD_CREATE` dispatches.
+        const THREAD_CREATE = 1 << 22;
+        /// `THREAD_UPDATE` dispatches.
+        const THREAD_UPDATE = 1 << 23;
+        /// `THREAD_DELETE` dispatches.
+        const THREAD_DELETE = 1 << 24;
+        /// `THREAD_LIST_SYNC` dispatches.
+        const THREAD_LIST_SYNC = 1 << 25;
+        /// `THREAD_MEMBER_UPDATE` dispatches.
+        const THREAD_MEMBER_UPDATE = 1 << 26;
+        /// `THREAD_MEMBERS_UPDATE` dispatches.
+        const THREAD_MEMBERS_UPDATE = 1 << 27;
+        /// `MESSAGE_CREATE` dispatches.
+        const MESSAGE_CREATE = 1 << 28;
+        /// `MESSAGE_UPDATE` dispatches.
+        const MESSAGE_UPDATE = 1 << 29;
+        /// `MESSAGE_DELETE` dispatches.
+        const MESSAGE_DELETE = 1 << 30;
+        /// `MESSAGE_DELETE_BULK` dispatches.
+        const MESSAGE_DELETE_BULK = 1 << 31;
+        /// `MESSAGE_REACTION_ADD` dispatches.
+        const MESSAGE_REACTION_ADD = 1 << 32;
+        /// `MESSAGE_REACTION_REMOVE` dispatches.
+        const MESSAGE_REACTION_REMOVE = 1 << 33;
+        /// `MESSAGE_REACTION_REMOVE_ALL` dispatches.
+        const MESSAGE_REACTION_REMOVE_ALL = 1 << 34;
+        /// `MESSAGE_REACTION_REMOVE_EMOJI` dispatches.
+        const MESSAGE_REACTION_REMOVE_EMOJI = 1 << 35;
+        /// `INTERACTION_CREATE` dispatches.
+        const INTERACTION_CREATE = 1 << 36;
+        /// `INVITE_CREATE` dispatches.
+        const INVITE_CREATE = 1 << 37;
+        /// `INVITE_DELETE` dispatches.
+        const INVITE_DELETE = 1 << 38;
+        /// `STAGE_INSTANCE_CREATE` dispatches.
+        const STAGE_INSTANCE_CREATE = 1 << 39;
+        /// `STAGE_INSTANCE_UPDATE` dispatches.
+        const STAGE_INSTANCE_UPDATE = 1 << 40;
+        /// `STAGE_INSTANCE_DELETE` dispatches.
+        const STAGE_INSTANCE_DELETE = 1 << 41;
+        /// `GUILD_SCHEDULED_EVENT_CREATE` dispatches.
+        const GUILD_SCHEDULED_EVENT_CREATE = 1 << 42;
+        /// `GUILD_SCHEDULED_EVENT_UPDATE` dispatches.
+        const GUILD_SCHEDULED_EVENT_UPDATE = 1 << 43;
+        /// `GUILD_SCHEDULED_EVENT_DELETE` dispatches.
+        const GUILD_SCHEDULED_EVENT_DELETE = 1 << 44;
+        /// `GUILD_SCHEDULED_EVENT_USER_ADD` dispatches.
+        const GUILD_SCHEDULED_EVENT_USER_ADD = 1 << 45;
+        /// `GUILD_SCHEDULED_EVENT_USER_REMOVE` dispatches.
+        const GUILD_SCHEDULED_EVENT_USER_REMOVE = 1 << 46;
+        /// `AUTO_MODERATION_RULE_CREATE` dispatches.
+        const AUTO_MODERATION_RULE_CREATE = 1 << 47;
+        /// `AUTO_MODERATION_RULE_UPDATE` dispatches.
+        const AUTO_MODERATION_RULE_UPDATE = 1 << 48;
+        /// `AUTO_MODERATION_RULE_DELETE` dispatches.
+        const AUTO_MODERATION_RULE_DELETE = 1 << 49;
+        /// `AUTO_MODERATION_ACTION_EXECUTION` dispatches.
+        const AUTO_MODERATION_ACTION_EXECUTION = 1 << 50;
+        /// `INTEGRATION_CREATE` dispatches.
+        const INTEGRATION_CREATE = 1 << 51;
+        /// `INTEGRATION_UPDATE` dispatches.
+        const INTEGRATION_UPDATE = 1 << 52;
+        /// `INTEGRATION_DELETE` dispatches.
+        const INTEGRATION_DELETE = 1 << 53;
+        /// `WEBHOOKS_UPDATE` dispatches.
+        const WEBHOOKS_UPDATE = 1 << 54;
+        /// `ENTITLEMENT_CREATE` dispatches.
+        const ENTITLEMENT_CREATE = 1 << 55;
+        /// `ENTITLEMENT_UPDATE` dispatches.
+        const ENTITLEMENT_UPDATE = 1 << 56;
+        /// `ENTITLEMENT_DELETE` dispatches.
+        const ENTITLEMENT_DELETE = 1 << 57;
+        /// `SUBSCRIPTION_CREATE` dispatches.
+        const SUBSCRIPTION_CREATE = 1 << 58;
+        /// `SUBSCRIPTION_UPDATE` dispatches.
+        const SUBSCRIPTION_UPDATE = 1 << 59;
+        /// `SUBSCRIPTION_DELETE` dispatches.
+        const SUBSCRIPTION_DELETE = 1 << 60;
+        /// `SOUNDBOARD_SOUND_CREATE` dispatches.
+        const SOUNDBOARD_SOUND_CREATE = 1 << 61;
+        /// `SOUNDBOARD_SOUND_UPDATE` dispatches.
+        const SOUNDBOARD_SOUND_UPDATE = 1 << 62;
+        /// `SOUNDBOARD_SOUND_DELETE` dispatches.
+        const SOUNDBOARD_SOUND_DELETE = 1 << 63;
+        /// `SOUNDBOARD_SOUNDS_UPDATE` dispatches.
+        const SOUNDBOARD_SOUNDS_UPDATE = 1 << 64;
+        /// `GUILD_SOUNDBOARD_SOUNDS_UPDATE` dispatches.
+        const GUILD_SOUNDBOARD_SOUNDS_UPDATE = 1 << 65;
+        /// `SOUNDBOARD_SOUNDS` dispatches.
+        const SOUNDBOARD_SOUNDS = 1 << 66;
+        /// `TYPING_START` dispatches.
+        const TYPING_START = 1 << 67;
+        /// `PRESENCE_UPDATE` dispatches.
+        const PRESENCE_UPDATE = 1 << 68;
+        /// `USER_UPDATE` dispatches.
+        const USER_UPDATE = 1 << 69;
+        /// `VOICE_STATE_UPDATE` dispatches.
+        const VOICE_STATE_UPDATE = 1 << 70;
+        /// `VOICE_SERVER_UPDATE` dispatches.
+        const VOICE_SERVER_UPDATE = 1 << 71;
+        /// `VOICE_CHANNEL_EFFECT_SEND` dispatches.
+        const VOICE_CHANNEL_EFFECT_SEND = 1 << 72;
+        /// Dispatches this version does not know, delivered as `Event::Unknown`.
+        const UNKNOWN = 1 << 73;
+    }
+}
+
+impl EventTypeFlags {
+    /// Events the shard needs itself, always processed.
+    pub(crate) const REQUIRED: Self = Self::READY.union(Self::RESUMED);
+
+    /// Get the flag of a dispatch name, `UNKNOWN` if the name is not known.
+    pub fn from_event_name(name: &str) -> Self {
+        Self::from_name(name).unwrap_or(Self::UNKNOWN)
+    }
+
+    /// Whether dispatches named `name` are deserialized and delivered.
+    pub fn wants(self, name: &str) -> bool {
+        self.union(Self::REQUIRED)
+            .contains(Self::from_event_name(name))
+    }
+}
+
+impl Default for EventTypeFlags {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+/// Envelope fields of a JSON dispatch, read without parsing `d`.
+pub(crate) struct DispatchHeader<'a> {
+    /// Dispatch name (`t`).
+    pub(crate) name: &'a str,
+    /// Sequence number (`s`).
+    pub(crate) sequence: Option<u64>,
+}
+
+/// Envelope fields of any JSON payload, read without parsing `d`.
+pub(crate) struct PayloadHeader<'a> {
+    /// Opcode (`op`).
+    pub(crate) op: u64,
+    /// Dispatch name (`t`), for dispatches.
+    pub(crate) name: Option<&'a str>,
+    /// Sequence number (`s`), for dispatches.
+    pub(crate) sequence: Option<u64>,
+}
+
+/// Read the name and sequence number of a JSON dispatch payload.
+///
+/// Only scans the top-level object, skipping over nested values without
+/// decoding them. Returns `None` for other opcodes and for anything it does
+/// not understand, in which case the payload is parsed as usual.
+pub(crate) fn peek_dispatch(payload: &[u8]) -> Option<DispatchHeader<'_>> {
+    let header = peek_payload(payload)?;
+    if header.op != 0 {
+        return None;
+    }
+    Some(DispatchHeader {
+        name: header.name?,
+        sequence: header.sequence,
+    })
+}
+
+/// Read the envelope fields of a JSON payload, like [`peek_dispatch`].
+pub(crate) fn peek_payload(payload: &[u8]) -> Option<PayloadHeader<'_>> {
+    let mut scanner = Scanner {
+        bytes: payload,
+        pos: 0,
+    };
+    let (mut op, mut name, mut sequence) = (None, None, None);
+
+    scanner.skip_whitespace();
+    scanner.eat(b'{')?;
+    loop {
+        scanner.skip_whitespace();
+        match scanner.peek()? {
+            b'}' => break,
+            b',' => {
+                scanner.pos += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        let key = scanner.string()?;
+        scanner.skip_whitespace();
+        scanner.eat(b':')?;
+        scanner.skip_whitespace();
+        match key {
+            b"op" => op = Some(scanner.number()?),
+            b"s" if scanner.peek()?.is_ascii_digit() => sequence = Some(scanner.number()?),
+            b"t" if scanner.peek()? == b'"' => {
+                name = Some(std::str::from_utf8(scanner.string()?).ok()?);
+            }
+            _ => scanner.skip_value()?,
+        }
+
+        if op.is_some() && name.is_some() && sequence.is_some() {
+            break;
+        }
+    }
+
+    Some(PayloadHeader {
+        op: op?,
+        name,
+        sequence,
+    })
+}
+
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> Option<()> {
+        (self.peek()? == byte).then(|| self.pos += 1)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Read a string, returning its contents with escapes left in place.
+    fn string(&mut self) -> Option<&'a [u8]> {
+        self.eat(b'"')?;
+        let start = self.pos;
+        loop {
+            match self.peek()? {
+                b'"' => break,
+                b'\\' => self.pos += 2,
+                _ => self.pos += 1,
+            }
+        }
+        let contents = &self.bytes[start..self.pos];
+        self.pos += 1;
+        Some(contents)
+    }
+
+    fn number(&mut self) -> Option<u64> {
+        let start = self.pos;
+        while self.peek().is_some_and(|b| b.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.bytes[start..self.pos])
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    fn skip_value(&mut self) -> Option<()> {
+        match self.peek()? {
+            b'"' => {
+                self.string()?;
+            }
+            b'{' | b'[' => {
+                let mut depth = 0usize;
+                loop {
+                    match self.peek()? {
+                        b'"' => {
+                            self.string()?;
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                self.pos += 1;
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                    self.pos += 1;
+                }
+            }
+            _ => {
+                while !matches!(self.peek()?, b',' | b'}' | b']') {
+                    self.pos += 1;
+                }
+            }
+        }
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wants() {
+        let types = EventTypeFlags::MESSAGE_CREATE;
+        assert!(types.wants("MESSAGE_CREATE"));
+        assert!(types.wants("READY"));
+        assert!(!types.wants("GUILD_CREATE"));
+        assert!(!types.wants("SOMETHING_NEW"));
+        assert!(EventTypeFlags::UNKNOWN.wants("SOMETHING_NEW"));
+    }
+
+    #[test]
+    fn test_peek_dispatch() {
+        let payload = br#"{"d":{"a":[1,{"t":"NO"}],"b":"x\"}"},"op":0, "s":42,"t":"TYPING_START"}"#;
+        let header = peek_dispatch(payload).unwrap();
+        assert_eq!(header.name, "TYPING_START");
+        assert_eq!(header.sequence, Some(42));
+
+        assert!(peek_dispatch(br#"{"op":11,"d":null,"s":null,"t":null}"#).is_none());
+        assert!(peek_dispatch(br#"{"op":0,"d":{"#).is_none());
+    }
+}
@@ -0,0 +1,299 @@
+//! Identify queues.
+//!
+//! Discord lets a bot start `max_concurrency` sessions every 5 seconds, one
+//! per rate limit bucket, where shard `id` belongs to bucket
+//! `id % max_concurrency`. Every process running shards of the same bot must
+//! share that budget, so the queue is pluggable: [`InMemoryIdentifyQueue`]
+//! serves a single process, and [`RemoteIdentifyQueue`] asks an
+//! [`IdentifyCoordinator`] shared by several processes.
+
+use crate::error::GatewayError;
+use crate::ratelimit::IdentifyRateLimiter;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::Mutex;
+use tokio::time::{sleep, sleep_until, timeout, Instant};
+use tracing::{debug, info, warn};
+
+/// Time between two identifies of the same bucket.
+pub const IDENTIFY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Longest request line the coordinator reads.
+const MAX_LINE: u64 = 32;
+
+/// How long to wait for a connection to the coordinator.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default time to wait for the coordinator's answer.
+const DEFAULT_REPLY_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Pause after a failed accept, e.g. while out of file descriptors.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Boxed future returned by [`IdentifyQueue::request`].
+pub type IdentifyFuture<'a> = Pin<Box<dyn Future<Output = Result<(), GatewayError>> + Send + 'a>>;
+
+/// Decides when shards may identify.
+///
+/// Implement it to share the identify rate limit through your own
+/// infrastructure, e.g. a Redis lock per bucket.
+pub trait IdentifyQueue: Send + Sync + std::fmt::Debug {
+    /// Wait until shard `shard_id` may send Identify.
+    ///
+    /// Resuming does not count against the limit and does not go through
+    /// the queue.
+    fn request(&self, shard_id: u16) -> IdentifyFuture<'_>;
+}
+
+/// Identify queue of a single process.
+///
+/// Shards of different buckets identify concurrently, shards of the same
+/// bucket at least [`IDENTIFY_INTERVAL`] apart, in request order.
+#[derive(Debug)]
+pub struct InMemoryIdentifyQueue {
+    /// Earliest next identify of each bucket.
+    buckets: Vec<Mutex<Instant>>,
+}
+
+impl InMemoryIdentifyQueue {
+    /// Create a queue for `max_concurrency` buckets (from /gateway/bot).
+    #[must_use]
+    pub fn new(max_concurrency: usize) -> Self {
+        let now = Instant::now();
+        Self {
+            buckets: (0..max_concurrency.max(1))
+                .map(|_| Mutex::new(now))
+                .collect(),
+        }
+    }
+
+    /// Number of rate limit buckets.
+    pub fn max_concurrency(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Wait for the bucket of `shard_id`.
+    pub async fn acquire(&self, shard_id: u16) {
+        let bucket = &self.buckets[usize::from(shard_id) % self.buckets.len()];
+        // The lock is fair, so waiting shards take turns in request order
+        let mut next = bucket.lock().await;
+        sleep_until(*next).await;
+        *next = Instant::now() + IDENTIFY_INTERVAL;
+    }
+}
+
+impl Default for InMemoryIdentifyQueue {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl IdentifyQueue for InMemoryIdentifyQueue {
+    fn request(&self, shard_id: u16) -> IdentifyFuture<'_> {
+        Box::pin(async move {
+            self.acquire(shard_id).await;
+            Ok(())
+        })
+    }
+}
+
+impl IdentifyQueue for IdentifyRateLimiter {
+    fn request(&self, _shard_id: u16) -> IdentifyFuture<'_> {
+        Box::pin(self.acquire())
+    }
+}
+
+/// Identify coordinator shared by several processes.
+///
+/// Serves an [`InMemoryIdentifyQueue`] over TCP to [`RemoteIdentifyQueue`]s.
+/// The protocol is one line per request: the client sends the shard ID and
+/// the coordinator answers `ok` once the shard may identify. It has no
+/// authentication, so only bind it to a trusted network.
+///
+/// # Example
+///
+/// ```no_run
+/// use titanium_gateway::IdentifyCoordinator;
+///
+/// # async fn run() -> Result<(), titanium_gateway::GatewayError> {
+/// IdentifyCoordinator::new(16).serve("127.0.0.1:7400").await
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct IdentifyCoordinator {
+    queue: Arc<InMemoryIdentifyQueue>,
+}
+
+impl IdentifyCoordinator {
+    /// Create a coordinator for `max_concurrency` buckets.
+    #[must_use]
+    pub fn new(max_concurrency: usize) -> Self {
+        Self {
+            queue: Arc::new(InMemoryIdentifyQueue::new(max_concurrency)),
+        }
+    }
+
+    /// Serve requests.
+    ///
+    /// Failed accepts are logged and do not stop the coordinator.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::Io` if binding fails.
+    pub async fn serve(self, addr: impl ToSocketAddrs) -> Result<(), GatewayError> {
+        let listener = TcpListener::bind(addr).await?;
+        info!(
+            addr = %listener.local_addr()?,
+            max_concurrency = self.queue.max_concurrency(),
+            "Serving identify queue"
+        );
+
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    warn!(error = %e, "Failed to accept identify request");
+                    sleep(ACCEPT_RETRY_DELAY).await;
+                    continue;
+                }
+            };
+            let queue = self.queue.clone();
+            tokio::spawn(async move {
+                if let Err(e) = Self::respond(&queue, stream).await {
+                    debug!(peer = %peer, error = %e, "Identify request failed");
+                }
+            });
+        }
+    }
+
+    /// Answer the requests of one connection.
+    async fn respond(queue: &InMemoryIdentifyQueue, stream: TcpStream) -> std::io::Result<()> {
+        let (read, mut write) = stream.into_split();
+        let mut read = BufReader::new(read);
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+            if (&mut read).take(MAX_LINE).read_line(&mut line).await? == 0 {
+                return Ok(());
+            }
+            let Ok(shard_id) = line.trim().parse::<u16>() else {
+                write.write_all(b"error\n").await?;
+                return Ok(());
+            };
+
+            tokio::select! {
+                () = queue.acquire(shard_id) => {}
+                // The client sends nothing until answered, so this completes
+                // once it disconnects, giving up its turn
+                _ = read.fill_buf() => {
+                    debug!(shard_id = shard_id, "Identify client left before its turn");
+                    return Ok(());
+                }
+            }
+            write.write_all(b"ok\n").await?;
+        }
+    }
+}
+
+/// Identify queue backed by an [`IdentifyCoordinator`].
+///
+/// Point the clusters of every process running the bot to the same
+/// coordinator.
+#[derive(Debug)]
+pub struct RemoteIdentifyQueue {
+    addr: String,
+    reply_timeout: Duration,
+}
+
+impl RemoteIdentifyQueue {
+    /// Create a queue asking the coordinator at `addr`, e.g. `127.0.0.1:7400`.
+    #[must_use]
+    pub fn new(addr: impl Into<String>) -> Self {
+        Self {
+            addr: addr.into(),
+            reply_timeout: DEFAULT_REPLY_TIMEOUT,
+        }
+    }
+
+    /// Set how long to wait for the coordinator to grant a turn, 10 minutes
+    /// by default.
+    ///
+    /// It must exceed the longest wait in the queue, about
+    /// [`IDENTIFY_INTERVAL`] times the number of shards per bucket.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.reply_timeout = timeout;
+        self
+    }
+
+    async fn acquire(&self, shard_id: u16) -> Result<(), GatewayError> {
+        let stream = timeout(CONNECT_TIMEOUT, TcpStream::connect(&self.addr))
+            .await
+            .map_err(|_| Self::timed_out("connecting to"))??;
+        let (read, mut write) = stream.into_split();
+        write.write_all(format!("{shard_id}\n").as_bytes()).await?;
+
+        let mut reply = String::new();
+        let mut read = BufReader::new(read).take(MAX_LINE);
+        timeout(self.reply_timeout, read.read_line(&mut reply))
+            .await
+            .map_err(|_| Self::timed_out("waiting for"))??;
+        if reply.trim() == "ok" {
+            Ok(())
+        } else {
+            Err(GatewayError::Closed {
+                code: 0,
+                reason: format!("Identify coordinator refused shard {shard_id}"),
+            })
+        }
+    }
+
+    fn timed_out(action: &str) -> GatewayError {
+        GatewayError::Io(std::io::Error::new(
+            std::io::ErrorKind::TimedOut,
+            format!("Timed out {action} the identify coordinator"),
+        ))
+    }
+}
+
+impl IdentifyQueue for RemoteIdentifyQueue {
+    fn request(&self, shard_id: u16) -> IdentifyFuture<'_> {
+        Box::pin(self.acquire(shard_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bucket_rule() {
+        let queue = InMemoryIdentifyQueue::new(2);
+        let start = Instant::now();
+
+        // Shards 0 and 1 are in different buckets, 2 shares a bucket with 0
+        queue.acquire(0).await;
+        queue.acquire(1).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        queue.acquire(2).await;
+        assert_eq!(start.elapsed(), IDENTIFY_INTERVAL);
+    }
+
+    #[tokio::test]
+    async fn test_remote_queue() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let queue = Arc::new(InMemoryIdentifyQueue::new(1));
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            IdentifyCoordinator::respond(&queue, stream).await.unwrap();
+        });
+
+        let remote = RemoteIdentifyQueue::new(addr.to_string());
+        remote.request(3).await.unwrap();
+    }
+}
@@ -17,6 +17,9 @@
 //!
 //! - `simd` - Enable SIMD-accelerated JSON parsing (~2-3x faster on supported CPUs)
 //! - `etf` - Enable Erlang Term Format encoding (more compact than JSON)
+//! - `zstd` - Enable `zstd-stream` transport compression
+//! - `prometheus` - Serve metrics in Prometheus text format over HTTP
+//! - `testing` - Mock gateway server for integration tests
 //!
 //! # Example
 //!
@@ -40,28 +43,59 @@ mod compression;
 pub mod error;
 pub mod etf;
 pub mod event;
+mod event_flags;
 pub mod heartbeat;
+mod identify;
+mod lifecycle;
+mod members;
 mod metrics;
 mod opcode;
 mod parsing;
 mod payload;
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
+mod proxy;
+mod queue;
 mod ratelimit;
+mod raw;
+mod recording;
+mod reshard;
 mod shard;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod voice;
 
 // Public re-exports
 pub use cluster::{Cluster, ClusterConfig, ShardRange};
-pub use compression::{ZlibDecompressor, ZlibTransport};
+#[cfg(feature = "zstd")]
+pub use compression::ZstdDecompressor;
+pub use compression::{TransportCompression, ZlibDecompressor, ZlibTransport};
 pub use error::GatewayError;
-pub use etf::{EtfDecoder, EtfTerm, GatewayEncoding};
+pub use etf::{EtfDecoder, EtfDeserializer, EtfEncoder, EtfTerm, GatewayEncoding};
 pub use event::Event;
-pub use metrics::{GatewayMetrics, ShardMetrics};
+pub use event_flags::EventTypeFlags;
+pub use identify::{
+    IdentifyCoordinator, IdentifyFuture, IdentifyQueue, InMemoryIdentifyQueue, RemoteIdentifyQueue,
+    IDENTIFY_INTERVAL,
+};
+pub use lifecycle::{ClusterEvent, ShardEvent, ShardStopReason};
+pub use members::{CollectedMembers, MemberChunkStream};
+pub use metrics::{GatewayMetrics, MetricsSnapshot, ShardMetrics};
 pub use opcode::OpCode;
 pub use parsing::{from_str, from_string, to_string};
 pub use payload::{
-    ConnectionProperties, GatewayPayload, HelloPayload, IdentifyPayload, ReadyEvent, ResumePayload,
+    Activity, ActivityButton, ActivityEmoji, ActivityTimestamps, ActivityType,
+    ConnectionProperties, GatewayPayload, HelloPayload, IdentifyPayload, PresenceUpdate,
+    ReadyEvent, RequestGuildMembersPayload, RequestSoundboardSoundsPayload, ResumePayload, Status,
+    UpdateVoiceStatePayload,
 };
-pub use ratelimit::IdentifyRateLimiter;
-pub use shard::{Shard, ShardConfig, ShardState};
+pub use proxy::{ProxyConfig, ProxyKind};
+pub use queue::OverflowPolicy;
+pub use ratelimit::{CommandRateLimiter, IdentifyRateLimiter};
+pub use raw::{DispatchMode, RawDispatch};
+pub use recording::{RecordedFrame, ReplayPacing, SessionRecorder, SessionReplay};
+pub use shard::{Shard, ShardConfig, ShardSession, ShardState};
+pub use voice::{VoiceConnectionFuture, VoiceConnectionInfo};
 
 /// Discord Gateway API version used by this library.
 pub const GATEWAY_VERSION: u8 = 10;
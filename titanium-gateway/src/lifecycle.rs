@@ -0,0 +1,149 @@
+//! Shard and cluster lifecycle events.
+//!
+//! Dispatches only tell what happens on Discord. Lifecycle events tell what
+//! happens to the connections themselves: state transitions, invalidated
+//! sessions, close codes and shards that stopped for good. They are
+//! broadcast, so any number of supervisors can subscribe, and slow
+//! subscribers lag instead of holding up the shards.
+
+use crate::error::CloseCode;
+use crate::shard::ShardState;
+use std::time::Duration;
+
+/// Capacity of lifecycle event channels before slow subscribers lag.
+pub(crate) const LIFECYCLE_CAPACITY: usize = 256;
+
+/// Something that happened to a shard's connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ShardEvent {
+    /// The shard moved to another state.
+    StateChanged {
+        /// Shard ID.
+        shard_id: u16,
+        /// Previous state.
+        from: ShardState,
+        /// New state.
+        to: ShardState,
+    },
+
+    /// A new session started (Ready received).
+    Identified {
+        /// Shard ID.
+        shard_id: u16,
+        /// ID of the new session.
+        session_id: String,
+    },
+
+    /// The previous session was resumed (Resumed received).
+    Resumed {
+        /// Shard ID.
+        shard_id: u16,
+    },
+
+    /// Discord invalidated the session.
+    SessionInvalidated {
+        /// Shard ID.
+        shard_id: u16,
+        /// Whether the session can still be resumed.
+        resumable: bool,
+    },
+
+    /// The connection was closed.
+    Disconnected {
+        /// Shard ID.
+        shard_id: u16,
+        /// Raw close code, `0` if the connection dropped without one.
+        code: u16,
+        /// Discord close code, if the code is one.
+        close_code: Option<CloseCode>,
+        /// Close reason.
+        reason: String,
+    },
+
+    /// The shard waits before connecting again.
+    Reconnecting {
+        /// Shard ID.
+        shard_id: u16,
+        /// Reconnect attempt, starting at 1.
+        attempt: u32,
+        /// Time until the attempt.
+        backoff: Duration,
+    },
+
+    /// The shard stopped and will not reconnect by itself.
+    Stopped {
+        /// Shard ID.
+        shard_id: u16,
+        /// Why the shard stopped.
+        reason: ShardStopReason,
+    },
+}
+
+impl ShardEvent {
+    /// The shard the event is about.
+    pub const fn shard_id(&self) -> u16 {
+        match self {
+            Self::StateChanged { shard_id, .. }
+            | Self::Identified { shard_id, .. }
+            | Self::Resumed { shard_id }
+            | Self::SessionInvalidated { shard_id, .. }
+            | Self::Disconnected { shard_id, .. }
+            | Self::Reconnecting { shard_id, .. }
+            | Self::Stopped { shard_id, .. } => *shard_id,
+        }
+    }
+}
+
+/// Why a shard stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ShardStopReason {
+    /// Shutdown was requested.
+    Shutdown,
+
+    /// Discord closed the connection with a code that forbids reconnecting,
+    /// e.g. 4004 (authentication failed) or 4014 (disallowed intents).
+    FatalClose {
+        /// Discord close code.
+        code: CloseCode,
+        /// Close reason.
+        reason: String,
+    },
+
+    /// `max_reconnect_attempts` reconnects failed in a row.
+    ReconnectsExhausted {
+        /// Number of failed attempts.
+        attempts: u32,
+    },
+}
+
+/// Something that happened to a cluster.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ClusterEvent {
+    /// Lifecycle event of one of the cluster's shards.
+    Shard(ShardEvent),
+
+    /// A reshard started bringing up new shards.
+    ReshardStarted {
+        /// Previous total shard count.
+        from: u16,
+        /// New total shard count.
+        to: u16,
+    },
+
+    /// The new shards took over and the old ones were shut down.
+    ReshardCompleted {
+        /// New total shard count.
+        total: u16,
+    },
+
+    /// A reshard was aborted; the old shards keep running.
+    ReshardFailed {
+        /// Total shard count that was attempted.
+        to: u16,
+        /// Why the reshard failed.
+        reason: String,
+    },
+}
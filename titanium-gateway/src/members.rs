@@ -0,0 +1,204 @@
+//! Request Guild Members (op 8) response collection.
+//!
+//! Discord answers a member request with one or more `GUILD_MEMBERS_CHUNK`
+//! dispatches tagged with the request's nonce. Each shard keeps a registry of
+//! in-flight nonces and tees matching chunks to the requester, while still
+//! forwarding them as regular events.
+
+use crate::event::GuildMembersChunkEvent;
+use futures_util::Stream;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use titanium_model::{GuildMember, Snowflake};
+
+/// A chunk as dispatched by the shard.
+type Chunk = Arc<GuildMembersChunkEvent<'static>>;
+
+/// In-flight member requests of a shard, keyed by nonce.
+#[derive(Debug, Default)]
+pub(crate) struct MemberRequests {
+    pending: Mutex<HashMap<String, flume::Sender<Chunk>>>,
+    counter: AtomicU64,
+}
+
+impl MemberRequests {
+    /// Generate a nonce unique to this shard (Discord allows up to 32 bytes).
+    pub(crate) fn next_nonce(&self, shard_id: u16) -> String {
+        let n = self.counter.fetch_add(1, Ordering::Relaxed);
+        format!("{shard_id}-{n}")
+    }
+
+    /// Start collecting chunks for `nonce`.
+    pub(crate) fn register(&self, guild_id: Snowflake, nonce: String) -> MemberChunkStream {
+        let (tx, rx) = flume::unbounded();
+        self.pending.lock().insert(nonce.clone(), tx);
+
+        MemberChunkStream {
+            guild_id,
+            nonce,
+            inner: rx.into_stream(),
+        }
+    }
+
+    /// Stop collecting chunks for `nonce`.
+    pub(crate) fn cancel(&self, nonce: &str) {
+        self.pending.lock().remove(nonce);
+    }
+
+    /// End every request, as their chunks will not arrive in a new session.
+    pub(crate) fn clear(&self) {
+        self.pending.lock().clear();
+    }
+
+    /// Whether any request still waits for chunks.
+    pub(crate) fn has_pending(&self) -> bool {
+        !self.pending.lock().is_empty()
+    }
+
+    /// Route a received chunk to its requester, if any.
+    pub(crate) fn dispatch(&self, chunk: &Chunk) {
+        let Some(nonce) = chunk.nonce.as_deref() else {
+            return;
+        };
+
+        let mut pending = self.pending.lock();
+        // Forget requests whose stream was dropped
+        pending.retain(|_, tx| !tx.is_disconnected());
+
+        if chunk.chunk_index + 1 >= chunk.chunk_count {
+            // Last chunk: dropping the sender ends the stream
+            if let Some(tx) = pending.remove(nonce) {
+                let _ = tx.send(chunk.clone());
+            }
+        } else if let Some(tx) = pending.get(nonce) {
+            let _ = tx.send(chunk.clone());
+        }
+    }
+}
+
+/// Stream of the `GUILD_MEMBERS_CHUNK` events answering one member request.
+///
+/// The stream ends after the last chunk (`chunk_index == chunk_count - 1`).
+/// Chunks are still delivered as regular [`Event::GuildMembersChunk`](crate::Event)
+/// events as well. If the session is lost before the last chunk arrives,
+/// the stream ends early with the chunks received so far.
+///
+/// # Example
+///
+/// ```ignore
+/// use titanium_gateway::RequestGuildMembersPayload;
+///
+/// let request = RequestGuildMembersPayload::user_ids(guild_id, [user_a, user_b]);
+/// let members = cluster.request_guild_members(request)?.collect_members().await;
+///
+/// println!("{} found, {} missing", members.members.len(), members.not_found.len());
+/// ```
+#[derive(Debug)]
+pub struct MemberChunkStream {
+    guild_id: Snowflake,
+    nonce: String,
+    inner: flume::r#async::RecvStream<'static, Chunk>,
+}
+
+impl MemberChunkStream {
+    /// The guild the members were requested from.
+    #[must_use]
+    pub const fn guild_id(&self) -> Snowflake {
+        self.guild_id
+    }
+
+    /// Nonce identifying the response chunks.
+    #[must_use]
+    pub fn nonce(&self) -> &str {
+        &self.nonce
+    }
+
+    /// Wait for every chunk and merge them.
+    pub async fn collect_members(mut self) -> CollectedMembers {
+        use futures_util::StreamExt;
+
+        let mut collected = CollectedMembers {
+            guild_id: self.guild_id,
+            members: Vec::new(),
+            not_found: Vec::new(),
+            presences: Vec::new(),
+        };
+
+        while let Some(chunk) = self.next().await {
+            let chunk = Arc::unwrap_or_clone(chunk);
+            collected.members.extend(chunk.members);
+            collected.not_found.extend(chunk.not_found);
+            collected.presences.extend(chunk.presences);
+        }
+
+        collected
+    }
+}
+
+impl Stream for MemberChunkStream {
+    type Item = Chunk;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.inner).poll_next(cx)
+    }
+}
+
+/// All members returned for one member request.
+#[derive(Debug, Clone)]
+pub struct CollectedMembers {
+    /// The guild the members belong to.
+    pub guild_id: Snowflake,
+
+    /// Members matching the request.
+    pub members: Vec<GuildMember<'static>>,
+
+    /// Requested user IDs that are not members of the guild.
+    pub not_found: Vec<Snowflake>,
+
+    /// Presences of the members (if requested).
+    pub presences: Vec<titanium_model::json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(index: u32, count: u32, nonce: &str, not_found: &[u64]) -> Chunk {
+        let json = serde_json::json!({
+            "guild_id": "1",
+            "members": [{ "user": { "id": "10", "username": "a", "discriminator": "0" }, "joined_at": "2024-01-01T00:00:00Z" }],
+            "chunk_index": index,
+            "chunk_count": count,
+            "not_found": not_found.iter().map(u64::to_string).collect::<Vec<_>>(),
+            "nonce": nonce,
+        });
+        Arc::new(serde_json::from_value(json).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_collects_chunks_by_nonce() {
+        let requests = MemberRequests::default();
+        let nonce = requests.next_nonce(3);
+        assert_eq!(nonce, "3-0");
+
+        let stream = requests.register(Snowflake::new(1), nonce.clone());
+        requests.dispatch(&chunk(0, 2, &nonce, &[]));
+        requests.dispatch(&chunk(0, 1, "other", &[]));
+        requests.dispatch(&chunk(1, 2, &nonce, &[42]));
+
+        let collected = stream.collect_members().await;
+        assert_eq!(collected.members.len(), 2);
+        assert_eq!(collected.not_found, vec![Snowflake::new(42)]);
+        assert!(requests.pending.lock().is_empty());
+
+        // Requests of a lost session end without their chunks
+        let stream = requests.register(Snowflake::new(1), requests.next_nonce(3));
+        requests.dispatch(&chunk(0, 2, "3-1", &[]));
+        requests.clear();
+        assert_eq!(stream.collect_members().await.members.len(), 1);
+    }
+}
@@ -3,9 +3,11 @@
 //! Provides observable metrics for monitoring shard health,
 //! event throughput, and connection stability.
 
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
+use titanium_model::Snowflake;
 
 /// Metrics for the entire Gateway cluster.
 #[derive(Debug, Default)]
@@ -14,10 +16,14 @@ pub struct GatewayMetrics {
     pub events_received: AtomicU64,
     /// Total events dispatched to handlers.
     pub events_dispatched: AtomicU64,
+    /// Total events dropped because the event channel was full.
+    pub events_dropped: AtomicU64,
     /// Total WebSocket messages received.
     pub ws_messages_received: AtomicU64,
     /// Total bytes received.
     pub bytes_received: AtomicU64,
+    /// Total bytes after decompression.
+    pub bytes_decompressed: AtomicU64,
     /// Total heartbeats sent.
     pub heartbeats_sent: AtomicU64,
     /// Total heartbeats acknowledged.
@@ -46,6 +52,11 @@ impl GatewayMetrics {
         self.events_dispatched.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Increment events dropped counter.
+    pub fn inc_events_dropped(&self) {
+        self.events_dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
     /// Increment WebSocket messages received counter.
     pub fn inc_ws_messages(&self) {
         self.ws_messages_received.fetch_add(1, Ordering::Relaxed);
@@ -56,6 +67,11 @@ impl GatewayMetrics {
         self.bytes_received.fetch_add(bytes, Ordering::Relaxed);
     }
 
+    /// Add bytes to decompressed counter.
+    pub fn add_bytes_decompressed(&self, bytes: u64) {
+        self.bytes_decompressed.fetch_add(bytes, Ordering::Relaxed);
+    }
+
     /// Increment heartbeats sent counter.
     pub fn inc_heartbeats_sent(&self) {
         self.heartbeats_sent.fetch_add(1, Ordering::Relaxed);
@@ -86,8 +102,10 @@ impl GatewayMetrics {
         MetricsSnapshot {
             events_received: self.events_received.load(Ordering::Relaxed),
             events_dispatched: self.events_dispatched.load(Ordering::Relaxed),
+            events_dropped: self.events_dropped.load(Ordering::Relaxed),
             ws_messages_received: self.ws_messages_received.load(Ordering::Relaxed),
             bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            bytes_decompressed: self.bytes_decompressed.load(Ordering::Relaxed),
             heartbeats_sent: self.heartbeats_sent.load(Ordering::Relaxed),
             heartbeats_acked: self.heartbeats_acked.load(Ordering::Relaxed),
             reconnections: self.reconnections.load(Ordering::Relaxed),
@@ -102,8 +120,10 @@ impl GatewayMetrics {
 pub struct MetricsSnapshot {
     pub events_received: u64,
     pub events_dispatched: u64,
+    pub events_dropped: u64,
     pub ws_messages_received: u64,
     pub bytes_received: u64,
+    pub bytes_decompressed: u64,
     pub heartbeats_sent: u64,
     pub heartbeats_acked: u64,
     pub reconnections: u64,
@@ -122,8 +142,30 @@ pub struct ShardMetrics {
     connected_at: RwLock<Option<Instant>>,
     /// Events received on this shard.
     pub events_received: AtomicU64,
+    /// Events of this shard dropped because the event channel was full.
+    pub events_dropped: AtomicU64,
     /// Guilds on this shard.
     pub guild_count: AtomicU64,
+    /// WebSocket messages received on this shard.
+    pub ws_messages_received: AtomicU64,
+    /// Bytes received on this shard.
+    pub bytes_received: AtomicU64,
+    /// Bytes after decompression on this shard.
+    pub bytes_decompressed: AtomicU64,
+    /// Heartbeats sent on this shard.
+    pub heartbeats_sent: AtomicU64,
+    /// Heartbeats acknowledged on this shard.
+    pub heartbeats_acked: AtomicU64,
+    /// Reconnections of this shard.
+    pub reconnections: AtomicU64,
+    /// Sessions resumed by this shard.
+    pub session_resumes: AtomicU64,
+    /// Identifies sent by this shard.
+    pub identifies_sent: AtomicU64,
+    /// Events received per dispatch name (e.g. `MESSAGE_CREATE`).
+    events_by_type: Mutex<HashMap<String, u64>>,
+    /// IDs of the guilds on this shard.
+    guilds: Mutex<HashSet<Snowflake>>,
 }
 
 impl ShardMetrics {
@@ -134,7 +176,18 @@ impl ShardMetrics {
             last_heartbeat_latency: RwLock::new(Duration::ZERO),
             connected_at: RwLock::new(None),
             events_received: AtomicU64::new(0),
+            events_dropped: AtomicU64::new(0),
             guild_count: AtomicU64::new(0),
+            ws_messages_received: AtomicU64::new(0),
+            bytes_received: AtomicU64::new(0),
+            bytes_decompressed: AtomicU64::new(0),
+            heartbeats_sent: AtomicU64::new(0),
+            heartbeats_acked: AtomicU64::new(0),
+            reconnections: AtomicU64::new(0),
+            session_resumes: AtomicU64::new(0),
+            identifies_sent: AtomicU64::new(0),
+            events_by_type: Mutex::new(HashMap::new()),
+            guilds: Mutex::new(HashSet::new()),
         }
     }
 
@@ -182,6 +235,46 @@ impl ShardMetrics {
     pub fn dec_guild_count(&self) {
         self.guild_count.fetch_sub(1, Ordering::Relaxed);
     }
+
+    /// Increment the counter of a dispatch event type.
+    pub fn inc_event_type(&self, name: &str) {
+        let mut counts = self.events_by_type.lock();
+        match counts.get_mut(name) {
+            Some(count) => *count += 1,
+            None => {
+                counts.insert(name.to_string(), 1);
+            }
+        }
+    }
+
+    /// Get the number of events received per dispatch name.
+    pub fn events_by_type(&self) -> HashMap<String, u64> {
+        self.events_by_type.lock().clone()
+    }
+
+    /// Replace the tracked guilds with the ones listed in Ready.
+    pub(crate) fn reset_guilds(&self, ids: impl IntoIterator<Item = Snowflake>) {
+        let mut guilds = self.guilds.lock();
+        guilds.clear();
+        guilds.extend(ids);
+        self.set_guild_count(guilds.len() as u64);
+    }
+
+    /// Track a guild that became available or was joined.
+    pub(crate) fn add_guild(&self, id: Snowflake) {
+        let mut guilds = self.guilds.lock();
+        if guilds.insert(id) {
+            self.set_guild_count(guilds.len() as u64);
+        }
+    }
+
+    /// Stop tracking a guild the bot left.
+    pub(crate) fn remove_guild(&self, id: Snowflake) {
+        let mut guilds = self.guilds.lock();
+        if guilds.remove(&id) {
+            self.set_guild_count(guilds.len() as u64);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -209,5 +302,16 @@ mod tests {
 
         assert!(metrics.uptime().is_some());
         assert_eq!(metrics.heartbeat_latency(), Duration::from_millis(50));
+
+        metrics.inc_event_type("MESSAGE_CREATE");
+        metrics.inc_event_type("MESSAGE_CREATE");
+        assert_eq!(metrics.events_by_type()["MESSAGE_CREATE"], 2);
+
+        // Guilds from Ready are counted once, even when they arrive later
+        metrics.reset_guilds([Snowflake::new(1), Snowflake::new(2)]);
+        metrics.add_guild(Snowflake::new(1));
+        metrics.add_guild(Snowflake::new(3));
+        metrics.remove_guild(Snowflake::new(2));
+        assert_eq!(metrics.guild_count.load(Ordering::Relaxed), 2);
     }
 }
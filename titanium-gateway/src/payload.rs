@@ -5,7 +5,7 @@
 
 use crate::opcode::OpCode;
 use serde::{Deserialize, Serialize};
-use titanium_model::{Application, Intents, UnavailableGuild, User};
+use titanium_model::{Application, Intents, Snowflake, UnavailableGuild, User};
 
 /// A raw Gateway payload for initial parsing.
 ///
@@ -171,10 +171,19 @@ impl<'a> Default for ConnectionProperties<'a> {
 }
 
 /// Presence update payload.
+///
+/// Sent with the Presence Update opcode (op 3) or as the initial presence in
+/// Identify.
+///
+/// ```
+/// use titanium_gateway::{Activity, PresenceUpdate, Status};
+///
+/// let presence = PresenceUpdate::new(Status::Online)
+///     .with_activity(Activity::watching("1,024 servers"));
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PresenceUpdate {
     /// Unix timestamp (milliseconds) of when the client went idle.
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub since: Option<u64>,
 
     /// User's activities.
@@ -187,7 +196,41 @@ pub struct PresenceUpdate {
     pub afk: bool,
 }
 
+impl PresenceUpdate {
+    /// Create a presence with the given status and no activities.
+    pub fn new(status: Status) -> Self {
+        Self {
+            since: None,
+            activities: Vec::new(),
+            status,
+            afk: false,
+        }
+    }
+
+    /// Add an activity.
+    pub fn with_activity(mut self, activity: Activity) -> Self {
+        self.activities.push(activity);
+        self
+    }
+
+    /// Mark the client as AFK since the given Unix timestamp (milliseconds).
+    pub fn with_afk_since(mut self, since: u64) -> Self {
+        self.since = Some(since);
+        self.afk = true;
+        self
+    }
+}
+
+impl Default for PresenceUpdate {
+    fn default() -> Self {
+        Self::new(Status::Online)
+    }
+}
+
 /// Activity for presence.
+///
+/// Bots can only set `name`, `type`, `url` and `state`; Discord ignores the
+/// other fields for bot accounts.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Activity {
     /// Activity name.
@@ -198,8 +241,155 @@ pub struct Activity {
     pub activity_type: ActivityType,
 
     /// Stream URL (only for Streaming type).
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
+
+    /// Current party status, or the text of a custom status.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+
+    /// Emoji of a custom status.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<ActivityEmoji>,
+
+    /// Start and end of the activity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamps: Option<ActivityTimestamps>,
+
+    /// Custom buttons shown on the activity (max 2).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub buttons: Vec<ActivityButton>,
+}
+
+impl Activity {
+    /// Create an activity of the given type.
+    pub fn new(activity_type: ActivityType, name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            activity_type,
+            url: None,
+            state: None,
+            emoji: None,
+            timestamps: None,
+            buttons: Vec::new(),
+        }
+    }
+
+    /// "Playing {name}".
+    pub fn playing(name: impl Into<String>) -> Self {
+        Self::new(ActivityType::Playing, name)
+    }
+
+    /// "Streaming {name}", linking to a Twitch or YouTube `url`.
+    pub fn streaming(name: impl Into<String>, url: impl Into<String>) -> Self {
+        let mut activity = Self::new(ActivityType::Streaming, name);
+        activity.url = Some(url.into());
+        activity
+    }
+
+    /// "Listening to {name}".
+    pub fn listening(name: impl Into<String>) -> Self {
+        Self::new(ActivityType::Listening, name)
+    }
+
+    /// "Watching {name}".
+    pub fn watching(name: impl Into<String>) -> Self {
+        Self::new(ActivityType::Watching, name)
+    }
+
+    /// "Competing in {name}".
+    pub fn competing(name: impl Into<String>) -> Self {
+        Self::new(ActivityType::Competing, name)
+    }
+
+    /// Custom status showing `state` as the text.
+    pub fn custom(state: impl Into<String>) -> Self {
+        Self::new(ActivityType::Custom, "Custom Status").with_state(state)
+    }
+
+    /// Set the state.
+    pub fn with_state(mut self, state: impl Into<String>) -> Self {
+        self.state = Some(state.into());
+        self
+    }
+
+    /// Set the custom status emoji.
+    pub fn with_emoji(mut self, emoji: ActivityEmoji) -> Self {
+        self.emoji = Some(emoji);
+        self
+    }
+
+    /// Set the start and end timestamps.
+    pub fn with_timestamps(mut self, timestamps: ActivityTimestamps) -> Self {
+        self.timestamps = Some(timestamps);
+        self
+    }
+
+    /// Add a button linking to `url`.
+    pub fn with_button(mut self, label: impl Into<String>, url: impl Into<String>) -> Self {
+        self.buttons.push(ActivityButton {
+            label: label.into(),
+            url: url.into(),
+        });
+        self
+    }
+}
+
+/// Emoji of a custom status.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityEmoji {
+    /// Emoji name (or the unicode character).
+    pub name: String,
+
+    /// ID of a custom emoji.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Snowflake>,
+
+    /// Whether the custom emoji is animated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub animated: Option<bool>,
+}
+
+impl ActivityEmoji {
+    /// A unicode emoji.
+    pub fn unicode(emoji: impl Into<String>) -> Self {
+        Self {
+            name: emoji.into(),
+            id: None,
+            animated: None,
+        }
+    }
+
+    /// A custom emoji.
+    pub fn custom(name: impl Into<String>, id: Snowflake, animated: bool) -> Self {
+        Self {
+            name: name.into(),
+            id: Some(id),
+            animated: Some(animated),
+        }
+    }
+}
+
+/// Start and end of an activity, as Unix timestamps in milliseconds.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ActivityTimestamps {
+    /// When the activity started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<u64>,
+
+    /// When the activity ends.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<u64>,
+}
+
+/// Button shown on an activity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityButton {
+    /// Text shown on the button (1-32 characters).
+    pub label: String,
+
+    /// URL opened when the button is clicked (1-512 characters).
+    pub url: String,
 }
 
 /// Activity type.
@@ -281,6 +471,118 @@ pub struct ResumePayload<'a> {
     pub seq: u64,
 }
 
+// ============================================================================
+// Update Voice State Payload (Sent)
+// ============================================================================
+
+/// Payload for the Voice State Update opcode (op 4).
+///
+/// Joins, moves between or leaves (`channel_id: None`) voice channels.
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateVoiceStatePayload {
+    /// ID of the guild.
+    pub guild_id: Snowflake,
+
+    /// ID of the voice channel to join, or `None` to disconnect.
+    pub channel_id: Option<Snowflake>,
+
+    /// Whether the client is muted.
+    pub self_mute: bool,
+
+    /// Whether the client is deafened.
+    pub self_deaf: bool,
+}
+
+// ============================================================================
+// Request Guild Members Payload (Sent)
+// ============================================================================
+
+/// Payload for the Request Guild Members opcode (op 8).
+///
+/// Discord answers with one or more `GUILD_MEMBERS_CHUNK` dispatches carrying
+/// the same `nonce`. Requires the `GUILD_MEMBERS` intent unless querying by
+/// `user_ids`; `presences` requires `GUILD_PRESENCES`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestGuildMembersPayload {
+    /// ID of the guild to get members for.
+    pub guild_id: Snowflake,
+
+    /// Username prefix to match, or an empty string for all members.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+
+    /// Maximum number of members to return (0 for no limit with an empty query).
+    pub limit: u32,
+
+    /// Whether to include presences of the matched members.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub presences: bool,
+
+    /// Specific users to fetch (up to 100).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_ids: Option<Vec<Snowflake>>,
+
+    /// Nonce echoed in the response chunks (up to 32 bytes).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+}
+
+impl RequestGuildMembersPayload {
+    /// Request members whose username starts with `query`.
+    pub fn query(guild_id: Snowflake, query: impl Into<String>, limit: u32) -> Self {
+        Self {
+            guild_id,
+            query: Some(query.into()),
+            limit,
+            presences: false,
+            user_ids: None,
+            nonce: None,
+        }
+    }
+
+    /// Request every member of the guild.
+    pub fn all(guild_id: Snowflake) -> Self {
+        Self::query(guild_id, "", 0)
+    }
+
+    /// Request specific members by user ID.
+    pub fn user_ids(guild_id: Snowflake, user_ids: impl IntoIterator<Item = Snowflake>) -> Self {
+        Self {
+            guild_id,
+            query: None,
+            limit: 0,
+            presences: false,
+            user_ids: Some(user_ids.into_iter().collect()),
+            nonce: None,
+        }
+    }
+
+    /// Include presences of the matched members.
+    pub fn with_presences(mut self, presences: bool) -> Self {
+        self.presences = presences;
+        self
+    }
+
+    /// Set the nonce used to identify the response chunks.
+    pub fn with_nonce(mut self, nonce: impl Into<String>) -> Self {
+        self.nonce = Some(nonce.into());
+        self
+    }
+}
+
+// ============================================================================
+// Request Soundboard Sounds Payload (Sent)
+// ============================================================================
+
+/// Payload for the Request Soundboard Sounds opcode (op 31).
+///
+/// Discord answers with one `SOUNDBOARD_SOUNDS` dispatch per guild.
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestSoundboardSoundsPayload {
+    /// IDs of the guilds to get soundboard sounds for.
+    pub guild_ids: Vec<Snowflake>,
+}
+
 // ============================================================================
 // Ready Event (Received after successful Identify)
 // ============================================================================
@@ -356,4 +658,22 @@ mod tests {
         let payload_null = create_heartbeat_payload(None);
         assert_eq!(payload_null, r#"{"op":1,"d":null}"#);
     }
+
+    #[test]
+    fn test_presence_serialization() {
+        let presence = PresenceUpdate::new(Status::Dnd).with_activity(
+            Activity::custom("Shipping")
+                .with_emoji(ActivityEmoji::unicode("🚀"))
+                .with_button("Docs", "https://docs.rs"),
+        );
+
+        let json = serde_json::to_value(&presence).unwrap();
+        assert_eq!(json["since"], serde_json::Value::Null);
+        assert_eq!(json["status"], "dnd");
+        assert_eq!(json["activities"][0]["type"], 4);
+        assert_eq!(json["activities"][0]["state"], "Shipping");
+        assert_eq!(json["activities"][0]["emoji"]["name"], "🚀");
+        assert_eq!(json["activities"][0]["buttons"][0]["label"], "Docs");
+        assert!(json["activities"][0].get("timestamps").is_none());
+    }
 }
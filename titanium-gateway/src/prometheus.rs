@@ -0,0 +1,316 @@
+//! Prometheus exporter for gateway metrics.
+//!
+//! Renders [`GatewayMetrics`] and [`ShardMetrics`] in the Prometheus text
+//! exposition format and serves them over HTTP/1.1.
+
+use crate::cluster::Cluster;
+use crate::error::GatewayError;
+use crate::metrics::{GatewayMetrics, ShardMetrics};
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::body::Incoming;
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::{TokioIo, TokioTimer};
+use std::convert::Infallible;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tracing::{debug, info, warn};
+
+/// How long a client may take to send its request head.
+const HEADER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Pause after a failed accept, e.g. while out of file descriptors.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Accessor of a per-shard counter.
+type ShardCounter = fn(&ShardMetrics) -> &AtomicU64;
+
+/// Render metrics in the Prometheus text exposition format.
+///
+/// Cluster-wide counters are named `titanium_gateway_*`, per-shard series
+/// `titanium_shard_*` with a `shard` label.
+pub fn render(gateway: &GatewayMetrics, shards: &[Arc<ShardMetrics>]) -> String {
+    let mut out = String::with_capacity(4096);
+    let snapshot = gateway.snapshot();
+
+    for (name, help, value) in [
+        (
+            "events_received_total",
+            "Dispatch events received.",
+            snapshot.events_received,
+        ),
+        (
+            "events_dispatched_total",
+            "Dispatch events delivered to the event channel.",
+            snapshot.events_dispatched,
+        ),
+        (
+            "events_dropped_total",
+            "Dispatch events dropped because the event channel was full.",
+            snapshot.events_dropped,
+        ),
+        (
+            "ws_messages_received_total",
+            "WebSocket messages received.",
+            snapshot.ws_messages_received,
+        ),
+        (
+            "bytes_received_total",
+            "Bytes received.",
+            snapshot.bytes_received,
+        ),
+        (
+            "bytes_decompressed_total",
+            "Bytes after decompression.",
+            snapshot.bytes_decompressed,
+        ),
+        (
+            "heartbeats_sent_total",
+            "Heartbeats sent.",
+            snapshot.heartbeats_sent,
+        ),
+        (
+            "heartbeats_acked_total",
+            "Heartbeats acknowledged.",
+            snapshot.heartbeats_acked,
+        ),
+        (
+            "reconnections_total",
+            "Reconnections.",
+            snapshot.reconnections,
+        ),
+        (
+            "session_resumes_total",
+            "Sessions resumed.",
+            snapshot.session_resumes,
+        ),
+        (
+            "identifies_sent_total",
+            "Identifies sent.",
+            snapshot.identifies_sent,
+        ),
+    ] {
+        header(
+            &mut out,
+            &format!("titanium_gateway_{name}"),
+            help,
+            "counter",
+        );
+        let _ = writeln!(out, "titanium_gateway_{name} {value}");
+    }
+
+    let counters: [(&str, &str, ShardCounter); 10] = [
+        ("events_received_total", "Dispatch events received.", |m| {
+            &m.events_received
+        }),
+        (
+            "events_dropped_total",
+            "Dispatch events dropped because the event channel was full.",
+            |m| &m.events_dropped,
+        ),
+        (
+            "ws_messages_received_total",
+            "WebSocket messages received.",
+            |m| &m.ws_messages_received,
+        ),
+        ("bytes_received_total", "Bytes received.", |m| {
+            &m.bytes_received
+        }),
+        (
+            "bytes_decompressed_total",
+            "Bytes after decompression.",
+            |m| &m.bytes_decompressed,
+        ),
+        ("heartbeats_sent_total", "Heartbeats sent.", |m| {
+            &m.heartbeats_sent
+        }),
+        ("heartbeats_acked_total", "Heartbeats acknowledged.", |m| {
+            &m.heartbeats_acked
+        }),
+        ("reconnections_total", "Reconnections.", |m| {
+            &m.reconnections
+        }),
+        ("session_resumes_total", "Sessions resumed.", |m| {
+            &m.session_resumes
+        }),
+        ("identifies_sent_total", "Identifies sent.", |m| {
+            &m.identifies_sent
+        }),
+    ];
+    for (name, help, counter) in counters {
+        header(&mut out, &format!("titanium_shard_{name}"), help, "counter");
+        for shard in shards {
+            let value = counter(shard).load(Ordering::Relaxed);
+            let _ = writeln!(
+                out,
+                "titanium_shard_{name}{{shard=\"{}\"}} {value}",
+                shard.shard_id
+            );
+        }
+    }
+
+    header(
+        &mut out,
+        "titanium_shard_events_total",
+        "Dispatch events received per type.",
+        "counter",
+    );
+    for shard in shards {
+        let mut counts: Vec<_> = shard.events_by_type().into_iter().collect();
+        counts.sort_unstable();
+        for (event, count) in counts {
+            let _ = writeln!(
+                out,
+                "titanium_shard_events_total{{shard=\"{}\",event=\"{}\"}} {count}",
+                shard.shard_id,
+                escape(&event)
+            );
+        }
+    }
+
+    header(
+        &mut out,
+        "titanium_shard_guilds",
+        "Guilds on the shard.",
+        "gauge",
+    );
+    for shard in shards {
+        let guilds = shard.guild_count.load(Ordering::Relaxed);
+        let _ = writeln!(
+            out,
+            "titanium_shard_guilds{{shard=\"{}\"}} {guilds}",
+            shard.shard_id
+        );
+    }
+
+    header(
+        &mut out,
+        "titanium_shard_heartbeat_latency_seconds",
+        "Last heartbeat round trip.",
+        "gauge",
+    );
+    for shard in shards {
+        let latency = shard.heartbeat_latency().as_secs_f64();
+        let _ = writeln!(
+            out,
+            "titanium_shard_heartbeat_latency_seconds{{shard=\"{}\"}} {latency}",
+            shard.shard_id
+        );
+    }
+
+    header(
+        &mut out,
+        "titanium_shard_uptime_seconds",
+        "Time since the shard connected, 0 if disconnected.",
+        "gauge",
+    );
+    for shard in shards {
+        let uptime = shard.uptime().unwrap_or_default().as_secs_f64();
+        let _ = writeln!(
+            out,
+            "titanium_shard_uptime_seconds{{shard=\"{}\"}} {uptime}",
+            shard.shard_id
+        );
+    }
+
+    out
+}
+
+/// Serve a cluster's metrics over HTTP.
+///
+/// Every `GET` request is answered with the rendered metrics, so point the
+/// Prometheus scraper at `http://<addr>/metrics`. Failed accepts are logged
+/// and do not stop the exporter.
+///
+/// # Errors
+/// Returns `GatewayError::Io` if binding fails.
+pub async fn serve(cluster: Arc<Cluster>, addr: impl ToSocketAddrs) -> Result<(), GatewayError> {
+    let listener = TcpListener::bind(addr).await?;
+    info!(addr = %listener.local_addr()?, "Serving Prometheus metrics");
+
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                warn!(error = %e, "Failed to accept metrics connection");
+                tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                continue;
+            }
+        };
+        let cluster = cluster.clone();
+
+        tokio::spawn(async move {
+            let service = service_fn(move |req| {
+                let response = respond(&cluster, &req);
+                async move { Ok::<_, Infallible>(response) }
+            });
+
+            if let Err(e) = http1::Builder::new()
+                .timer(TokioTimer::new())
+                .header_read_timeout(HEADER_TIMEOUT)
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                debug!(peer = %peer, error = %e, "Metrics connection closed with error");
+            }
+        });
+    }
+}
+
+/// Answer one HTTP request.
+fn respond(cluster: &Cluster, req: &Request<Incoming>) -> Response<Full<Bytes>> {
+    if req.method() != Method::GET {
+        let mut response = Response::new(Full::default());
+        *response.status_mut() = StatusCode::METHOD_NOT_ALLOWED;
+        return response;
+    }
+
+    let body = render(cluster.metrics(), &cluster.shard_metrics());
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("text/plain; version=0.0.4"),
+    );
+    response
+}
+
+fn header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+/// Escape a label value.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let gateway = GatewayMetrics::new();
+        gateway.inc_events_received();
+        let shard = Arc::new(ShardMetrics::new(2));
+        shard.inc_event_type("MESSAGE_CREATE");
+        shard.set_guild_count(5);
+
+        let text = render(&gateway, &[shard]);
+        assert!(text.contains("# TYPE titanium_gateway_events_received_total counter"));
+        assert!(text.contains("titanium_gateway_events_received_total 1\n"));
+        assert!(
+            text.contains("titanium_shard_events_total{shard=\"2\",event=\"MESSAGE_CREATE\"} 1\n")
+        );
+        assert!(text.contains("titanium_shard_guilds{shard=\"2\"} 5\n"));
+    }
+}
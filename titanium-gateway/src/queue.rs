@@ -0,0 +1,224 @@
+//! Event delivery with backpressure.
+//!
+//! Shards send their events straight into the channel returned by
+//! [`Cluster::new`](crate::Cluster::new), and their raw dispatches into the
+//! cluster's raw channel. When a channel is bounded and full, the
+//! [`OverflowPolicy`] decides whether the shard waits for the consumer or
+//! events are dropped.
+
+use crate::error::GatewayError;
+use crate::event::Event;
+use crate::event_flags::EventTypeFlags;
+use crate::reshard::EventRouter;
+use flume::{Receiver, Sender, TrySendError};
+use std::sync::Arc;
+
+/// What to do when the event channel is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Wait for the consumer, pausing the shard's reader.
+    ///
+    /// No event is lost, but Discord may close connections whose messages
+    /// are not read for too long.
+    #[default]
+    Block,
+
+    /// Drop the oldest queued event to make room.
+    DropOldest,
+
+    /// Drop new events of the given types, and wait for the consumer for
+    /// all others.
+    DropTypes(EventTypeFlags),
+}
+
+/// Outcome of sending an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Delivery {
+    /// The event was queued.
+    Sent,
+    /// The event was queued after dropping the oldest one.
+    SentDroppingOldest,
+    /// The channel was full and the event was dropped.
+    Dropped,
+    /// The event belongs to a shard set whose events are not delivered.
+    Filtered,
+}
+
+/// Sending side of a channel, applying an overflow policy.
+#[derive(Debug)]
+pub(crate) struct OverflowSender<T> {
+    tx: Sender<T>,
+
+    /// Receiver to drop the oldest items with, for `DropOldest`.
+    rx: Option<Receiver<T>>,
+
+    policy: OverflowPolicy,
+}
+
+impl<T> OverflowSender<T> {
+    /// Create the sender and the receiver handed to the application.
+    pub(crate) fn new(capacity: Option<usize>, policy: OverflowPolicy) -> (Self, Receiver<T>) {
+        let (tx, rx) = match capacity {
+            Some(capacity) => flume::bounded(capacity),
+            None => flume::unbounded(),
+        };
+        let sender = Self {
+            tx,
+            rx: (policy == OverflowPolicy::DropOldest).then(|| rx.clone()),
+            policy,
+        };
+        (sender, rx)
+    }
+
+    /// Send an item of type `kind`, applying the overflow policy.
+    pub(crate) async fn send(
+        &self,
+        item: T,
+        kind: EventTypeFlags,
+    ) -> Result<Delivery, GatewayError> {
+        let mut item = match self.tx.try_send(item) {
+            Ok(()) => return Ok(Delivery::Sent),
+            Err(TrySendError::Disconnected(_)) => return Err(Self::closed()),
+            Err(TrySendError::Full(item)) => item,
+        };
+
+        match self.policy {
+            OverflowPolicy::Block => {}
+            OverflowPolicy::DropTypes(types) if types.intersects(kind) => {
+                return Ok(Delivery::Dropped);
+            }
+            OverflowPolicy::DropTypes(_) => {}
+            OverflowPolicy::DropOldest => {
+                // Our own receiver keeps the channel open, so check for others
+                if self.tx.receiver_count() <= 1 {
+                    return Err(Self::closed());
+                }
+                let Some(rx) = &self.rx else {
+                    return Ok(Delivery::Dropped);
+                };
+                let mut dropped = false;
+                loop {
+                    dropped |= rx.try_recv().is_ok();
+                    match self.tx.try_send(item) {
+                        Ok(()) if dropped => return Ok(Delivery::SentDroppingOldest),
+                        Ok(()) => return Ok(Delivery::Sent),
+                        Err(TrySendError::Full(back)) => item = back,
+                        Err(TrySendError::Disconnected(_)) => return Err(Self::closed()),
+                    }
+                }
+            }
+        }
+
+        self.tx.send_async(item).await?;
+        Ok(Delivery::Sent)
+    }
+
+    fn closed() -> GatewayError {
+        GatewayError::ChannelSend("Event receiver dropped".to_string())
+    }
+}
+
+/// Cluster event channel shared by all shards.
+#[derive(Debug)]
+pub(crate) struct EventQueue {
+    sender: OverflowSender<(u16, Event<'static>)>,
+
+    /// Filters events while two shard sets overlap.
+    router: Arc<EventRouter>,
+}
+
+impl EventQueue {
+    /// Create the queue and the receiver handed to the application.
+    pub(crate) fn new(
+        capacity: Option<usize>,
+        policy: OverflowPolicy,
+        router: Arc<EventRouter>,
+    ) -> (Self, Receiver<(u16, Event<'static>)>) {
+        let (sender, rx) = OverflowSender::new(capacity, policy);
+        (Self { sender, router }, rx)
+    }
+
+    /// Route an event of shard set `generation` and send it.
+    pub(crate) async fn route(
+        &self,
+        generation: u64,
+        shard_id: u16,
+        event: Event<'static>,
+        kind: EventTypeFlags,
+    ) -> Result<Delivery, GatewayError> {
+        match self.router.route(generation, shard_id, event) {
+            Some(event) => self.send(shard_id, event, kind).await,
+            None => Ok(Delivery::Filtered),
+        }
+    }
+
+    /// Send an event, applying the overflow policy.
+    pub(crate) async fn send(
+        &self,
+        shard_id: u16,
+        event: Event<'static>,
+        kind: EventTypeFlags,
+    ) -> Result<Delivery, GatewayError> {
+        self.sender.send((shard_id, event), kind).await
+    }
+}
+
+/// Where a shard delivers its events.
+#[derive(Debug)]
+pub(crate) enum EventSender {
+    /// Channel passed to [`Shard::run`](crate::Shard::run).
+    Direct(Sender<Event<'static>>),
+
+    /// Cluster channel, as part of shard set `generation`.
+    Cluster {
+        queue: Arc<EventQueue>,
+        generation: u64,
+    },
+}
+
+impl EventSender {
+    /// Deliver an event of type `kind` from shard `shard_id`.
+    pub(crate) async fn send(
+        &self,
+        shard_id: u16,
+        event: Event<'static>,
+        kind: EventTypeFlags,
+    ) -> Result<Delivery, GatewayError> {
+        match self {
+            Self::Direct(tx) => {
+                tx.send_async(event).await?;
+                Ok(Delivery::Sent)
+            }
+            Self::Cluster { queue, generation } => {
+                queue.route(*generation, shard_id, event, kind).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_overflow_policies() {
+        let router = Arc::new(EventRouter::default());
+
+        let (queue, rx) = EventQueue::new(Some(2), OverflowPolicy::DropOldest, router.clone());
+        for _ in 0..2 {
+            let delivery = queue.send(0, Event::Resumed, EventTypeFlags::RESUMED);
+            assert_eq!(delivery.await.unwrap(), Delivery::Sent);
+        }
+        let delivery = queue.send(1, Event::Resumed, EventTypeFlags::RESUMED);
+        assert_eq!(delivery.await.unwrap(), Delivery::SentDroppingOldest);
+        let shards: Vec<u16> = rx.drain().map(|(shard_id, _)| shard_id).collect();
+        assert_eq!(shards, [0, 1]);
+
+        let policy = OverflowPolicy::DropTypes(EventTypeFlags::TYPING_START);
+        let (queue, _rx) = EventQueue::new(Some(1), policy, router);
+        let delivery = queue.send(0, Event::Resumed, EventTypeFlags::RESUMED);
+        assert_eq!(delivery.await.unwrap(), Delivery::Sent);
+        let delivery = queue.send(0, Event::Resumed, EventTypeFlags::TYPING_START);
+        assert_eq!(delivery.await.unwrap(), Delivery::Dropped);
+    }
+}
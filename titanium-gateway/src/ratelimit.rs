@@ -2,16 +2,25 @@
 //!
 //! Discord limits how quickly bots can identify on the Gateway.
 //! Large bots (150k+ guilds) get higher `max_concurrency` values.
+//!
+//! Each connection may also send at most 120 commands per 60 seconds, or
+//! Discord closes it with code 4008.
 
+use parking_lot::Mutex;
+use std::collections::VecDeque;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::Semaphore;
 use tokio::time::sleep;
 
 /// Rate limiter for Gateway identify operations.
 ///
 /// Discord allows `max_concurrency` identify operations every 5 seconds.
-/// This rate limiter ensures we don't exceed this limit.
+/// This rate limiter ensures we don't exceed this limit within one process.
+/// It ignores which bucket a shard belongs to; prefer
+/// [`InMemoryIdentifyQueue`](crate::InMemoryIdentifyQueue), which follows
+/// the bucket rule.
+#[derive(Debug)]
 pub struct IdentifyRateLimiter {
     /// Semaphore with max_concurrency permits.
     semaphore: Arc<Semaphore>,
@@ -73,6 +82,184 @@ impl Default for IdentifyRateLimiter {
     }
 }
 
+/// Pause before checking again while only queued commands fill the bucket.
+const QUEUE_POLL: Duration = Duration::from_millis(250);
+
+/// Rate limiter for outgoing Gateway commands of one connection.
+///
+/// A token bucket of `limit` tokens where each spent token returns one
+/// `period` later, so no window of `period` ever sees more than `limit`
+/// commands. Part of the bucket is reserved for heartbeats, Identify and
+/// Resume, which the shard sends itself and never have to wait.
+///
+/// The shard takes a token right before writing a command to the socket.
+/// Callers queueing commands use [`check`](Self::check) with the number
+/// of commands still queued, so the queue never outgrows the bucket.
+#[derive(Debug)]
+pub struct CommandRateLimiter {
+    /// Commands allowed per period.
+    limit: u32,
+
+    /// Length of the rate limit window.
+    period: Duration,
+
+    /// Tokens kept back for heartbeats, Identify and Resume.
+    reserved: Mutex<u32>,
+
+    /// When each token in use was spent, oldest first.
+    spent: Mutex<VecDeque<Instant>>,
+}
+
+impl CommandRateLimiter {
+    /// Create a command rate limiter allowing `limit` commands per `period`.
+    #[must_use]
+    pub fn new(limit: u32, period: Duration) -> Self {
+        let limiter = Self {
+            limit,
+            period,
+            reserved: Mutex::new(0),
+            spent: Mutex::new(VecDeque::with_capacity(limit as usize)),
+        };
+        // Discord's usual heartbeat interval until Hello says otherwise
+        limiter.set_heartbeat_interval(Duration::from_millis(41_250));
+        limiter
+    }
+
+    /// Reserve headroom for the heartbeats sent at `interval`.
+    ///
+    /// Keeps one token per heartbeat in a period, plus one for a heartbeat
+    /// requested by Discord and one for Identify or Resume.
+    pub fn set_heartbeat_interval(&self, interval: Duration) {
+        let per_period = self
+            .period
+            .as_millis()
+            .div_ceil(interval.as_millis().max(1));
+        let reserved = u32::try_from(per_period)
+            .unwrap_or(u32::MAX)
+            .saturating_add(2);
+        *self.reserved.lock() = reserved.min(self.limit);
+    }
+
+    /// Take a token without waiting.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::RateLimited` with the time until the next token
+    /// frees up if none is available.
+    pub fn try_acquire(&self) -> Result<(), crate::GatewayError> {
+        self.try_take(Instant::now())
+            .map_err(|wait| crate::GatewayError::RateLimited {
+                retry_after_ms: u64::try_from(wait.as_millis()).unwrap_or(u64::MAX),
+            })
+    }
+
+    /// Take a token, waiting until one is available.
+    pub async fn acquire(&self) {
+        while let Err(wait) = self.try_take(Instant::now()) {
+            sleep(wait).await;
+        }
+    }
+
+    /// Check whether a command fits behind `queued` commands not sent yet.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::RateLimited` with an estimate of the time
+    /// until it fits otherwise.
+    pub fn check(&self, queued: u32) -> Result<(), crate::GatewayError> {
+        self.room(queued, Instant::now())
+            .map_err(|wait| crate::GatewayError::RateLimited {
+                retry_after_ms: u64::try_from(wait.as_millis()).unwrap_or(u64::MAX),
+            })
+    }
+
+    /// Wait until a command fits behind the commands `queued` returns.
+    pub async fn wait_for_room(&self, queued: impl Fn() -> u32) {
+        while let Err(wait) = self.room(queued(), Instant::now()) {
+            sleep(wait).await;
+        }
+    }
+
+    /// Wait until a token is available, without taking it.
+    pub async fn ready(&self) {
+        self.wait_for_room(|| 0).await;
+    }
+
+    /// Get the number of commands that can be sent right now.
+    #[must_use]
+    pub fn available(&self) -> u32 {
+        let mut spent = self.spent.lock();
+        self.release_expired(&mut spent, Instant::now());
+        self.capacity().saturating_sub(Self::in_use(&spent))
+    }
+
+    /// Return all tokens, e.g. after a new connection was opened.
+    pub fn reset(&self) {
+        self.spent.lock().clear();
+    }
+
+    /// Take a token at `now`, or return how long until one frees up.
+    fn try_take(&self, now: Instant) -> Result<(), Duration> {
+        let mut spent = self.spent.lock();
+        self.release_expired(&mut spent, now);
+        self.wait_time(&spent, 0, now)?;
+        spent.push_back(now);
+        Ok(())
+    }
+
+    /// Check at `now` whether a token is left after `queued` more are
+    /// taken, or return how long until one might be.
+    fn room(&self, queued: u32, now: Instant) -> Result<(), Duration> {
+        let mut spent = self.spent.lock();
+        self.release_expired(&mut spent, now);
+        self.wait_time(&spent, queued, now)
+    }
+
+    fn wait_time(
+        &self,
+        spent: &VecDeque<Instant>,
+        queued: u32,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let in_use = Self::in_use(spent).saturating_add(queued);
+        if in_use < self.capacity() {
+            return Ok(());
+        }
+
+        // The reserve may have grown since tokens were spent, so skip past
+        // every token that has to return before one is free again. Queued
+        // commands leave as soon as the connection allows, so poll for them.
+        let excess = in_use - self.capacity();
+        let wait = spent.get(excess as usize).map_or(QUEUE_POLL, |at| {
+            (*at + self.period).saturating_duration_since(now)
+        });
+        Err(wait.max(Duration::from_millis(1)))
+    }
+
+    fn release_expired(&self, spent: &mut VecDeque<Instant>, now: Instant) {
+        while spent
+            .front()
+            .is_some_and(|at| now.saturating_duration_since(*at) >= self.period)
+        {
+            spent.pop_front();
+        }
+    }
+
+    /// Tokens available to callers, after the reserve.
+    fn capacity(&self) -> u32 {
+        self.limit.saturating_sub(*self.reserved.lock())
+    }
+
+    fn in_use(spent: &VecDeque<Instant>) -> u32 {
+        u32::try_from(spent.len()).unwrap_or(u32::MAX)
+    }
+}
+
+impl Default for CommandRateLimiter {
+    fn default() -> Self {
+        // 120 commands per 60 seconds, per Discord docs
+        Self::new(120, Duration::from_secs(60))
+    }
+}
+
 /// Calculate backoff duration with exponential increase.
 ///
 /// # Arguments
@@ -148,4 +335,35 @@ mod tests {
         // But we check immediately so might still show 3 or 2 depending on timing
         assert!(limiter.available_permits() <= 3);
     }
+
+    #[test]
+    fn test_command_rate_limiter() {
+        let limiter = CommandRateLimiter::new(10, Duration::from_secs(60));
+        limiter.set_heartbeat_interval(Duration::from_secs(30));
+        // 2 heartbeats per minute + 2 spare
+        assert_eq!(limiter.available(), 6);
+
+        let start = Instant::now();
+        for _ in 0..6 {
+            limiter.try_take(start).unwrap();
+        }
+        let wait = limiter.try_take(start).unwrap_err();
+        assert_eq!(wait, Duration::from_secs(60));
+        assert!(matches!(
+            limiter.try_acquire(),
+            Err(crate::GatewayError::RateLimited { .. })
+        ));
+
+        // Tokens return one period after they were spent
+        limiter.try_take(start + Duration::from_secs(60)).unwrap();
+
+        limiter.reset();
+        assert_eq!(limiter.available(), 6);
+
+        // Queued commands count against the bucket before they are sent
+        limiter.try_take(start).unwrap();
+        assert!(limiter.room(4, start).is_ok());
+        assert_eq!(limiter.room(5, start), Err(Duration::from_secs(60)));
+        assert_eq!(limiter.room(6, start), Err(QUEUE_POLL));
+    }
 }
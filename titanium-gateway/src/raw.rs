@@ -0,0 +1,131 @@
+//! Raw dispatch passthrough.
+//!
+//! Services that only forward gateway traffic (e.g. to a message queue) gain
+//! nothing from deserializing every event and serializing it again. In raw
+//! mode, shards hand out each dispatch as the exact payload received, and
+//! consumers parse it later with [`Event::from_raw`] if at all.
+
+use crate::error::GatewayError;
+use crate::etf::{EtfDecoder, EtfTerm, GatewayEncoding};
+use crate::event::{parse_event, parse_event_etf, Event};
+use crate::shard::EtfPayload;
+
+/// How a shard hands out dispatches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DispatchMode {
+    /// Deliver parsed [`Event`]s only.
+    #[default]
+    Parsed,
+
+    /// Deliver [`RawDispatch`]es only.
+    ///
+    /// `READY` and `RESUMED` are still parsed and delivered as events, as
+    /// the shard needs them itself. Resharding does not wait for guilds in
+    /// this mode.
+    Raw,
+
+    /// Deliver both parsed events and raw dispatches.
+    Both,
+}
+
+impl DispatchMode {
+    /// Whether raw dispatches are delivered.
+    pub const fn is_raw(self) -> bool {
+        matches!(self, Self::Raw | Self::Both)
+    }
+
+    /// Whether parsed events are delivered.
+    pub const fn is_parsed(self) -> bool {
+        matches!(self, Self::Parsed | Self::Both)
+    }
+}
+
+/// A dispatch payload exactly as received, after decompression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawDispatch {
+    /// Shard that received the dispatch.
+    pub shard_id: u16,
+
+    /// Sequence number.
+    pub seq: u64,
+
+    /// Dispatch name (`t` field).
+    pub event_name: String,
+
+    /// Encoding of `bytes`.
+    pub encoding: GatewayEncoding,
+
+    /// The whole gateway payload, `op`, `s`, `t` and `d` included.
+    pub bytes: Vec<u8>,
+}
+
+impl Event<'static> {
+    /// Parse a raw dispatch into an event.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::JsonDecode` (or `GatewayError::Etf`) if the
+    /// payload is malformed or does not match the event type.
+    pub fn from_raw(raw: &RawDispatch) -> Result<Self, GatewayError> {
+        match raw.encoding {
+            GatewayEncoding::Json => parse_json(&raw.event_name, &raw.bytes),
+            GatewayEncoding::Etf => {
+                let term = EtfDecoder::decode(&raw.bytes)?;
+                let payload = EtfPayload::from_term(&term)?;
+                let null = EtfTerm::Atom("nil".to_string());
+                parse_event_etf(&raw.event_name, payload.d.unwrap_or(&null))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "simd")]
+fn parse_json(event_name: &str, bytes: &[u8]) -> Result<Event<'static>, GatewayError> {
+    // Parsing happens in place, so work on a copy
+    let mut bytes = bytes.to_vec();
+    let mut json = titanium_model::json::to_borrowed_value(&mut bytes)
+        .map_err(|e| GatewayError::JsonDecode(e.to_string()))?;
+
+    let titanium_model::json::BorrowedValue::Object(ref mut map) = json else {
+        return Err(GatewayError::JsonDecode(
+            "Payload is not an object".to_string(),
+        ));
+    };
+    let d = map
+        .remove("d")
+        .unwrap_or_else(|| titanium_model::json::BorrowedValue::from(()));
+    parse_event(event_name, d)
+}
+
+#[cfg(not(feature = "simd"))]
+fn parse_json(event_name: &str, bytes: &[u8]) -> Result<Event<'static>, GatewayError> {
+    let payload: crate::payload::RawGatewayPayload = serde_json::from_slice(bytes)?;
+    match payload.d {
+        Some(d) => parse_event(event_name, d),
+        None => parse_event(event_name, &serde_json::value::RawValue::NULL),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_raw() {
+        let bytes = br#"{"op":0,"s":3,"t":"MESSAGE_DELETE","d":{"id":"1","channel_id":"2"}}"#;
+        let raw = RawDispatch {
+            shard_id: 0,
+            seq: 3,
+            event_name: "MESSAGE_DELETE".to_string(),
+            encoding: GatewayEncoding::Json,
+            bytes: bytes.to_vec(),
+        };
+
+        let Event::MessageDelete(event) = Event::from_raw(&raw).unwrap() else {
+            panic!("expected MESSAGE_DELETE");
+        };
+        assert_eq!(event.id.get(), 1);
+        assert_eq!(event.channel_id.get(), 2);
+        // The raw bytes are left untouched
+        assert_eq!(raw.bytes, bytes);
+    }
+}
@@ -0,0 +1,532 @@
+//! Gateway session recording and replay.
+//!
+//! A [`SessionRecorder`] writes every frame the shards receive, after
+//! decompression, to a compact binary file. A [`SessionReplay`] reads it
+//! back and turns the dispatches into the same `(shard_id, Event)` stream a
+//! [`Cluster`](crate::Cluster) delivers, so bug reports can ship recordings
+//! and tests can run without Discord.
+//!
+//! Recordings contain everything Discord sent, including message contents
+//! and the session IDs of the recorded sessions. Treat them like logs.
+//!
+//! # Format
+//!
+//! The file starts with the magic bytes `TGREC\x01`, followed by one record
+//! per frame, all integers little endian:
+//!
+//! | Field        | Size     | Content                                   |
+//! |--------------|----------|-------------------------------------------|
+//! | shard ID     | 2        |                                           |
+//! | timestamp    | 8        | Microseconds since recording started      |
+//! | opcode       | 1        |                                           |
+//! | flags        | 1        | Bit 0: has sequence, bit 1: ETF payload   |
+//! | sequence     | 8        | `0` without one                           |
+//! | name length  | 1        | Length of the dispatch name, `0` for none |
+//! | name         | variable |                                           |
+//! | payload size | 4        |                                           |
+//! | payload      | variable | The frame as received                     |
+
+use crate::error::GatewayError;
+use crate::etf::{EtfDecoder, GatewayEncoding};
+use crate::event::Event;
+use crate::event_flags::peek_payload;
+use crate::raw::RawDispatch;
+use crate::shard::EtfPayload;
+use flume::Sender;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Magic bytes at the start of a recording.
+const MAGIC: &[u8; 6] = b"TGREC\x01";
+
+/// Largest frame a recording holds.
+const MAX_FRAME_LEN: usize = 128 * 1024 * 1024;
+
+const FLAG_SEQUENCE: u8 = 1 << 0;
+const FLAG_ETF: u8 = 1 << 1;
+
+/// A frame received by a shard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedFrame {
+    /// Shard that received the frame.
+    pub shard_id: u16,
+
+    /// Time since the recording started.
+    pub timestamp: Duration,
+
+    /// Gateway opcode.
+    pub opcode: u8,
+
+    /// Sequence number, for dispatches.
+    pub seq: Option<u64>,
+
+    /// Dispatch name, for dispatches.
+    pub event_name: Option<String>,
+
+    /// Encoding of `payload`.
+    pub encoding: GatewayEncoding,
+
+    /// The frame as received, after decompression.
+    pub payload: Vec<u8>,
+}
+
+impl RecordedFrame {
+    /// Parse the frame into an event, if it is a dispatch.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::JsonDecode` (or `GatewayError::Etf`) if the
+    /// payload does not match the event type.
+    pub fn event(&self) -> Result<Option<Event<'static>>, GatewayError> {
+        let (0, Some(event_name)) = (self.opcode, &self.event_name) else {
+            return Ok(None);
+        };
+        let raw = RawDispatch {
+            shard_id: self.shard_id,
+            seq: self.seq.unwrap_or_default(),
+            event_name: event_name.clone(),
+            encoding: self.encoding,
+            bytes: self.payload.clone(),
+        };
+        Event::from_raw(&raw).map(Some)
+    }
+}
+
+/// Writes the frames received by shards to a recording.
+///
+/// Pass it to [`ShardConfig::with_recorder`](crate::ShardConfig::with_recorder)
+/// or [`ClusterConfig::with_recorder`](crate::ClusterConfig::with_recorder).
+/// Frames are written by a dedicated thread, so recording never blocks the
+/// shards; call [`flush`](Self::flush) before reading the recording while
+/// shards still run. Dropping the recorder waits for pending frames.
+pub struct SessionRecorder {
+    started: Instant,
+    tx: Option<Sender<WriterCommand>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+/// Work for the writer thread.
+enum WriterCommand {
+    Frame(PendingFrame),
+    Flush(Sender<io::Result<()>>),
+}
+
+/// A received frame waiting to be written.
+struct PendingFrame {
+    shard_id: u16,
+    timestamp: u64,
+    encoding: GatewayEncoding,
+    payload: Vec<u8>,
+}
+
+impl PendingFrame {
+    /// Encode the frame as a record of the file format.
+    ///
+    /// Runs on the writer thread, so reading the envelope does not add a
+    /// second decode to the shard's event loop.
+    fn to_record(&self) -> Vec<u8> {
+        let (opcode, seq, name) = envelope(self.encoding, &self.payload);
+        let name = name.as_deref().unwrap_or_default().as_bytes();
+        let name = &name[..name.len().min(usize::from(u8::MAX))];
+
+        let mut flags = 0;
+        if seq.is_some() {
+            flags |= FLAG_SEQUENCE;
+        }
+        if self.encoding == GatewayEncoding::Etf {
+            flags |= FLAG_ETF;
+        }
+
+        // `record` keeps payloads below `MAX_FRAME_LEN`
+        let len = self.payload.len() as u32;
+        let mut record = Vec::with_capacity(25 + name.len() + self.payload.len());
+        record.extend_from_slice(&self.shard_id.to_le_bytes());
+        record.extend_from_slice(&self.timestamp.to_le_bytes());
+        record.push(opcode);
+        record.push(flags);
+        record.extend_from_slice(&seq.unwrap_or_default().to_le_bytes());
+        record.push(name.len() as u8);
+        record.extend_from_slice(name);
+        record.extend_from_slice(&len.to_le_bytes());
+        record.extend_from_slice(&self.payload);
+        record
+    }
+}
+
+impl SessionRecorder {
+    /// Record to `writer`.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::Io` if writing the file header or starting the
+    /// writer thread fails.
+    pub fn new(writer: impl Write + Send + 'static) -> Result<Self, GatewayError> {
+        let mut writer: Box<dyn Write + Send> = Box::new(writer);
+        writer.write_all(MAGIC)?;
+        let (tx, rx) = flume::unbounded();
+        let writer = std::thread::Builder::new()
+            .name("titanium-recorder".to_string())
+            .spawn(move || write_frames(writer, &rx))?;
+        Ok(Self {
+            started: Instant::now(),
+            tx: Some(tx),
+            writer: Some(writer),
+        })
+    }
+
+    /// Record to a new file at `path`, replacing any existing file.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::Io` if the file cannot be created.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, GatewayError> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+
+    /// Record a frame received by shard `shard_id`.
+    ///
+    /// The frame is queued for the writer thread, which logs write errors.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::Io` if the frame is too large or the writer
+    /// thread stopped.
+    pub fn record(
+        &self,
+        shard_id: u16,
+        encoding: GatewayEncoding,
+        payload: &[u8],
+    ) -> Result<(), GatewayError> {
+        if payload.len() > MAX_FRAME_LEN {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Frame too large").into());
+        }
+        self.send(WriterCommand::Frame(PendingFrame {
+            shard_id,
+            timestamp: u64::try_from(self.started.elapsed().as_micros()).unwrap_or(u64::MAX),
+            encoding,
+            payload: payload.to_vec(),
+        }))
+    }
+
+    /// Write all recorded frames and flush the writer.
+    ///
+    /// Blocks until the writer thread caught up, so avoid calling it from
+    /// async code.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::Io` if writing failed or the writer thread
+    /// stopped.
+    pub fn flush(&self) -> Result<(), GatewayError> {
+        let (tx, rx) = flume::bounded(1);
+        self.send(WriterCommand::Flush(tx))?;
+        rx.recv().map_err(|_| writer_stopped())??;
+        Ok(())
+    }
+
+    fn send(&self, command: WriterCommand) -> Result<(), GatewayError> {
+        self.tx
+            .as_ref()
+            .and_then(|tx| tx.send(command).ok())
+            .ok_or_else(|| writer_stopped().into())
+    }
+}
+
+/// Writer thread: write frames until the recorder is dropped.
+///
+/// After the first failed write, frames are discarded and every flush
+/// reports the failure.
+fn write_frames(mut writer: Box<dyn Write + Send>, rx: &flume::Receiver<WriterCommand>) {
+    let mut failed: Option<(io::ErrorKind, String)> = None;
+    for command in rx.iter() {
+        let result = match (command, &failed) {
+            (WriterCommand::Frame(frame), None) => writer.write_all(&frame.to_record()),
+            (WriterCommand::Frame(_), Some(_)) => Ok(()),
+            (WriterCommand::Flush(reply), None) => {
+                let result = writer.flush();
+                let _ = reply.send(result.as_ref().copied().map_err(clone_error));
+                result
+            }
+            (WriterCommand::Flush(reply), Some((kind, message))) => {
+                let _ = reply.send(Err(io::Error::new(*kind, message.clone())));
+                Ok(())
+            }
+        };
+        if let Err(e) = result {
+            warn!(error = %e, "Failed to write recording, discarding further frames");
+            failed = Some((e.kind(), e.to_string()));
+        }
+    }
+    if failed.is_none() {
+        if let Err(e) = writer.flush() {
+            warn!(error = %e, "Failed to flush recording");
+        }
+    }
+}
+
+fn clone_error(e: &io::Error) -> io::Error {
+    io::Error::new(e.kind(), e.to_string())
+}
+
+fn writer_stopped() -> io::Error {
+    io::Error::new(io::ErrorKind::BrokenPipe, "Recorder writer thread stopped")
+}
+
+impl std::fmt::Debug for SessionRecorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionRecorder")
+            .field("started", &self.started)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for SessionRecorder {
+    fn drop(&mut self) {
+        // Closing the channel lets the writer thread finish
+        drop(self.tx.take());
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+/// Read opcode, sequence and dispatch name of a frame.
+fn envelope(encoding: GatewayEncoding, payload: &[u8]) -> (u8, Option<u64>, Option<String>) {
+    match encoding {
+        GatewayEncoding::Json => peek_payload(payload).map_or((u8::MAX, None, None), |header| {
+            (
+                u8::try_from(header.op).unwrap_or(u8::MAX),
+                header.sequence,
+                header.name.map(str::to_owned),
+            )
+        }),
+        GatewayEncoding::Etf => EtfDecoder::decode(payload)
+            .ok()
+            .and_then(|term| {
+                let payload = EtfPayload::from_term(&term).ok()?;
+                Some((payload.op as u8, payload.s, payload.t))
+            })
+            .unwrap_or((u8::MAX, None, None)),
+    }
+}
+
+/// How fast a recording is replayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplayPacing {
+    /// Deliver events as fast as they are consumed.
+    #[default]
+    Immediate,
+
+    /// Deliver events with the delays they were received with.
+    Recorded,
+}
+
+/// Reads a recording made by a [`SessionRecorder`].
+///
+/// Iterate it for the recorded frames, or use [`events`](Self::events) and
+/// [`play`](Self::play) for the dispatches, parsed like a cluster would.
+///
+/// # Example
+///
+/// ```no_run
+/// use titanium_gateway::SessionReplay;
+///
+/// # fn run() -> Result<(), titanium_gateway::GatewayError> {
+/// for event in SessionReplay::open("bug-report.tgrec")?.events() {
+///     let (shard_id, event) = event?;
+///     println!("[{shard_id}] {event:?}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SessionReplay<R> {
+    reader: R,
+}
+
+impl SessionReplay<BufReader<File>> {
+    /// Open the recording at `path`.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::Io` if the file cannot be read or is not a
+    /// recording.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, GatewayError> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> SessionReplay<R> {
+    /// Read a recording from `reader`.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::Io` if `reader` does not start with a
+    /// recording header.
+    pub fn new(mut reader: R) -> Result<Self, GatewayError> {
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("Not a gateway recording").into());
+        }
+        Ok(Self { reader })
+    }
+
+    /// The recorded dispatches as `(shard_id, Event)`, in recording order.
+    pub fn events(self) -> impl Iterator<Item = Result<(u16, Event<'static>), GatewayError>> {
+        self.filter_map(|frame| match frame {
+            Ok(frame) => frame
+                .event()
+                .transpose()
+                .map(|event| event.map(|event| (frame.shard_id, event))),
+            Err(e) => Some(Err(e)),
+        })
+    }
+
+    /// Send the recorded dispatches to `tx`, like a cluster's event channel.
+    ///
+    /// # Errors
+    /// Returns the first error reading or parsing the recording, or
+    /// `GatewayError::ChannelSend` if the receiver was dropped.
+    pub async fn play(
+        self,
+        tx: Sender<(u16, Event<'static>)>,
+        pacing: ReplayPacing,
+    ) -> Result<(), GatewayError> {
+        let started = tokio::time::Instant::now();
+        for frame in self {
+            let frame = frame?;
+            let Some(event) = frame.event()? else {
+                continue;
+            };
+            if pacing == ReplayPacing::Recorded {
+                tokio::time::sleep_until(started + frame.timestamp).await;
+            }
+            tx.send_async((frame.shard_id, event)).await?;
+        }
+        Ok(())
+    }
+
+    fn read_frame(&mut self) -> io::Result<Option<RecordedFrame>> {
+        let mut shard_id = [0; 2];
+        match self.reader.read_exact(&mut shard_id) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+
+        let mut header = [0; 19];
+        self.reader.read_exact(&mut header)?;
+        let timestamp = u64::from_le_bytes(header[0..8].try_into().unwrap_or_default());
+        let opcode = header[8];
+        let flags = header[9];
+        let seq = u64::from_le_bytes(header[10..18].try_into().unwrap_or_default());
+
+        let mut name = vec![0; usize::from(header[18])];
+        self.reader.read_exact(&mut name)?;
+        let name = String::from_utf8(name).map_err(|_| invalid_data("Invalid event name"))?;
+
+        let mut len = [0; 4];
+        self.reader.read_exact(&mut len)?;
+        let len = u32::from_le_bytes(len) as usize;
+        if len > MAX_FRAME_LEN {
+            return Err(invalid_data("Frame too large"));
+        }
+        // Grow with the data read, a truncated file must not allocate `len`
+        let mut payload = Vec::new();
+        (&mut self.reader)
+            .take(len as u64)
+            .read_to_end(&mut payload)?;
+        if payload.len() != len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        Ok(Some(RecordedFrame {
+            shard_id: u16::from_le_bytes(shard_id),
+            timestamp: Duration::from_micros(timestamp),
+            opcode,
+            seq: (flags & FLAG_SEQUENCE != 0).then_some(seq),
+            event_name: (!name.is_empty()).then_some(name),
+            encoding: if flags & FLAG_ETF != 0 {
+                GatewayEncoding::Etf
+            } else {
+                GatewayEncoding::Json
+            },
+            payload,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for SessionReplay<R> {
+    type Item = Result<RecordedFrame, GatewayError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_frame().map_err(GatewayError::from).transpose()
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    /// Writer whose contents stay readable after the recorder took it.
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let file = Shared::default();
+        let recorder = SessionRecorder::new(file.clone()).unwrap();
+        let hello = br#"{"op":10,"d":{"heartbeat_interval":41250},"s":null,"t":null}"#;
+        let delete = br#"{"op":0,"s":2,"t":"MESSAGE_DELETE","d":{"id":"1","channel_id":"2"}}"#;
+        recorder.record(0, GatewayEncoding::Json, hello).unwrap();
+        recorder.record(3, GatewayEncoding::Json, delete).unwrap();
+        drop(recorder);
+
+        let bytes = file.0.lock().clone();
+        let frames: Vec<_> = SessionReplay::new(bytes.as_slice())
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].opcode, 10);
+        assert_eq!(frames[0].seq, None);
+        assert_eq!(frames[1].seq, Some(2));
+        assert_eq!(frames[1].event_name.as_deref(), Some("MESSAGE_DELETE"));
+        assert_eq!(frames[1].payload, delete);
+
+        let events: Vec<_> = SessionReplay::new(bytes.as_slice())
+            .unwrap()
+            .events()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert!(matches!(events.as_slice(), [(3, Event::MessageDelete(_))]));
+    }
+
+    #[test]
+    fn test_replay_rejects_oversized_frame() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&[0; 21]);
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+
+        let error = SessionReplay::new(bytes.as_slice())
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert!(matches!(error, GatewayError::Io(e) if e.kind() == io::ErrorKind::InvalidData));
+    }
+}
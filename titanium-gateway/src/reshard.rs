@@ -0,0 +1,444 @@
+//! Event routing while resharding.
+//!
+//! During a reshard two shard sets are connected at once and both receive
+//! every dispatch. The router forwards events of the active set only, while
+//! the new set catches up. At the swap, create events that only the new set
+//! has seen so far are replayed, holding back the new set's later events
+//! until the replay is delivered. For a short while afterwards the new set's
+//! copies of events already forwarded by the old set are discarded.
+//!
+//! Only events carrying a unique ID can be matched across the two sets.
+//! Other events (e.g. presence or typing updates) arriving right at the swap
+//! may be delivered twice or not at all.
+
+use crate::event::Event;
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use titanium_model::Snowflake;
+use tokio::sync::futures::Notified;
+use tokio::sync::Notify;
+
+/// How long events are remembered to match them across shard sets.
+const OVERLAP_WINDOW: Duration = Duration::from_secs(30);
+
+/// Routes shard events to the cluster channel by shard set generation.
+#[derive(Debug, Default)]
+pub(crate) struct EventRouter {
+    /// Generation of the shard set whose events are forwarded.
+    active: AtomicU64,
+
+    /// Reshard in progress, or its dedup window after the swap.
+    overlap: Mutex<Option<Overlap>>,
+
+    /// Whether `overlap` is set, so routing skips the lock without one.
+    overlapping: AtomicBool,
+
+    /// Woken whenever a shard of the next set makes progress.
+    progress: Notify,
+}
+
+#[derive(Debug)]
+struct Overlap {
+    /// Generation of the set being brought up.
+    next: u64,
+
+    /// When the sets were swapped, if they were.
+    swapped_at: Option<Instant>,
+
+    /// Keys of events forwarded from the old set.
+    seen: HashSet<Snowflake>,
+
+    /// The same keys, oldest first, for pruning.
+    seen_order: VecDeque<(Instant, Snowflake)>,
+
+    /// Keyed events of the next set, kept for replay at the swap.
+    buffered: VecDeque<(Instant, Snowflake, u16, Event<'static>)>,
+
+    /// Events to deliver after the swap before any live event of the next
+    /// set, while the replay is in progress.
+    replay: Option<VecDeque<(u16, Event<'static>)>>,
+
+    /// Startup progress of each shard of the next set.
+    shards: HashMap<u16, ShardProgress>,
+
+    /// Whether to wait for every guild of a shard to arrive.
+    wait_for_guilds: bool,
+}
+
+#[derive(Debug, Default)]
+struct ShardProgress {
+    ready: bool,
+    pending_guilds: HashSet<Snowflake>,
+}
+
+impl EventRouter {
+    /// Decide whether an event of shard set `generation` is delivered.
+    pub(crate) fn route(
+        &self,
+        generation: u64,
+        shard_id: u16,
+        event: Event<'static>,
+    ) -> Option<Event<'static>> {
+        if !self.overlapping.load(Ordering::Acquire) {
+            return (generation == self.active.load(Ordering::Acquire)).then_some(event);
+        }
+
+        let mut guard = self.overlap.lock();
+        // Read under the lock so a concurrent swap is seen
+        let active = self.active.load(Ordering::Acquire);
+
+        let Some(overlap) = guard.as_mut() else {
+            return (generation == active).then_some(event);
+        };
+
+        let now = Instant::now();
+        overlap.prune(now);
+        if overlap.replay.is_none()
+            && overlap
+                .swapped_at
+                .is_some_and(|at| now.duration_since(at) > OVERLAP_WINDOW)
+        {
+            *guard = None;
+            self.overlapping.store(false, Ordering::Release);
+            return (generation == active).then_some(event);
+        }
+
+        let key = dedup_key(&event);
+        if generation == overlap.next {
+            if overlap.swapped_at.is_some() {
+                if key.is_some_and(|key| overlap.seen.contains(&key)) {
+                    // Already forwarded by the old set
+                    return None;
+                }
+                if let Some(replay) = overlap.replay.as_mut() {
+                    // Keep it behind the events being replayed
+                    replay.push_back((shard_id, event));
+                    return None;
+                }
+                return Some(event);
+            }
+
+            if overlap.track(shard_id, &event) {
+                self.progress.notify_waiters();
+            }
+            if let Some(key) = key {
+                overlap.buffered.push_back((now, key, shard_id, event));
+            }
+            return None;
+        }
+
+        if generation != active {
+            return None;
+        }
+        if let Some(key) = key {
+            if overlap.seen.insert(key) {
+                overlap.seen_order.push_back((now, key));
+            }
+        }
+        Some(event)
+    }
+
+    /// Whether shard set `generation` is the active one.
+    pub(crate) fn is_active(&self, generation: u64) -> bool {
+        self.active.load(Ordering::Acquire) == generation
+    }
+
+    /// Start bringing up shard set `generation` with the given shards.
+    ///
+    /// Returns `false` if a reshard is already in progress.
+    pub(crate) fn begin(&self, generation: u64, shard_ids: &[u16], wait_for_guilds: bool) -> bool {
+        let mut guard = self.overlap.lock();
+        if guard
+            .as_ref()
+            .is_some_and(|o| o.swapped_at.is_none() || o.replay.is_some())
+        {
+            return false;
+        }
+
+        *guard = Some(Overlap {
+            next: generation,
+            swapped_at: None,
+            seen: HashSet::new(),
+            seen_order: VecDeque::new(),
+            buffered: VecDeque::new(),
+            replay: None,
+            shards: shard_ids
+                .iter()
+                .map(|id| (*id, ShardProgress::default()))
+                .collect(),
+            wait_for_guilds,
+        });
+        self.overlapping.store(true, Ordering::Release);
+        true
+    }
+
+    /// Whether every shard of the next set is ready with all of its guilds.
+    pub(crate) fn is_ready(&self) -> bool {
+        self.overlap.lock().as_ref().is_some_and(|o| {
+            o.shards
+                .values()
+                .all(|s| s.ready && s.pending_guilds.is_empty())
+        })
+    }
+
+    /// Future completing once a shard of the next set makes progress.
+    ///
+    /// Create it before checking [`is_ready`](Self::is_ready) so no progress
+    /// is missed in between.
+    pub(crate) fn progressed(&self) -> Notified<'_> {
+        self.progress.notified()
+    }
+
+    /// Make the next set active.
+    ///
+    /// The events only the next set has received so far are replayed with
+    /// [`next_replay`](Self::next_replay). Until the replay is done, the next
+    /// set's events are queued behind them instead of being forwarded.
+    pub(crate) fn swap(&self) {
+        let mut guard = self.overlap.lock();
+        let Some(overlap) = guard.as_mut() else {
+            return;
+        };
+
+        overlap.prune(Instant::now());
+        overlap.swapped_at = Some(Instant::now());
+        self.active.store(overlap.next, Ordering::Release);
+
+        let replay = std::mem::take(&mut overlap.buffered)
+            .into_iter()
+            .filter(|(_, key, _, _)| !overlap.seen.contains(key))
+            .map(|(_, _, shard_id, event)| (shard_id, event))
+            .collect();
+        overlap.replay = Some(replay);
+    }
+
+    /// Take the next event to replay after the swap.
+    ///
+    /// Deliver each event before taking the next one. Returns `None` once the
+    /// replay is done, after which the next set's events are forwarded again.
+    pub(crate) fn next_replay(&self) -> Option<(u16, Event<'static>)> {
+        let mut guard = self.overlap.lock();
+        let overlap = guard.as_mut()?;
+        let next = overlap.replay.as_mut()?.pop_front();
+        if next.is_none() {
+            overlap.replay = None;
+        }
+        next
+    }
+
+    /// Give up bringing up the next set.
+    pub(crate) fn abort(&self) {
+        let mut guard = self.overlap.lock();
+        if guard.as_ref().is_some_and(|o| o.swapped_at.is_none()) {
+            *guard = None;
+            self.overlapping.store(false, Ordering::Release);
+        }
+    }
+}
+
+impl Overlap {
+    /// Update startup progress, returning whether anything changed.
+    fn track(&mut self, shard_id: u16, event: &Event<'static>) -> bool {
+        let wait_for_guilds = self.wait_for_guilds;
+        let Some(progress) = self.shards.get_mut(&shard_id) else {
+            return false;
+        };
+
+        match event {
+            Event::Ready(ready) => {
+                progress.ready = true;
+                if wait_for_guilds {
+                    progress.pending_guilds = ready.guilds.iter().map(|g| g.id).collect();
+                }
+                true
+            }
+            Event::GuildCreate(guild) => progress.pending_guilds.remove(&guild.id),
+            Event::GuildDelete(guild) => progress.pending_guilds.remove(&guild.id),
+            _ => false,
+        }
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while let Some((at, key)) = self.seen_order.front() {
+            if now.duration_since(*at) <= OVERLAP_WINDOW {
+                break;
+            }
+            self.seen.remove(key);
+            self.seen_order.pop_front();
+        }
+        while self
+            .buffered
+            .front()
+            .is_some_and(|(at, ..)| now.duration_since(*at) > OVERLAP_WINDOW)
+        {
+            self.buffered.pop_front();
+        }
+    }
+}
+
+/// ID identifying one occurrence of an event across shard sets.
+///
+/// Only create events qualify: their ID is new, so two events with the same
+/// key are the same event.
+fn dedup_key(event: &Event<'_>) -> Option<Snowflake> {
+    match event {
+        Event::MessageCreate(message) => Some(message.id),
+        Event::InteractionCreate(interaction) => Some(interaction.id),
+        Event::ChannelCreate(channel) | Event::ThreadCreate(channel) => Some(channel.id),
+        Event::GuildRoleCreate(event) => Some(event.role.id),
+        Event::GuildScheduledEventCreate(event) => Some(event.id),
+        Event::AutoModerationRuleCreate(rule) => Some(rule.id),
+        Event::EntitlementCreate(entitlement) => Some(entitlement.id),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn message(id: u64) -> Event<'static> {
+        let json = serde_json::json!({
+            "id": id.to_string(),
+            "channel_id": "1",
+            "author": { "id": "2", "username": "a", "discriminator": "0" },
+            "content": "hi",
+            "timestamp": "2024-01-01T00:00:00Z",
+            "tts": false,
+            "mention_everyone": false,
+            "pinned": false,
+            "type": 0,
+        });
+        Event::MessageCreate(Arc::new(serde_json::from_value(json).unwrap()))
+    }
+
+    fn ready(guild_ids: &[u64]) -> Event<'static> {
+        let guilds: Vec<_> = guild_ids
+            .iter()
+            .map(|id| serde_json::json!({ "id": id.to_string(), "unavailable": true }))
+            .collect();
+        let json = serde_json::json!({
+            "v": 10,
+            "user": { "id": "1", "username": "bot", "discriminator": "0", "bot": true },
+            "guilds": guilds,
+            "session_id": "abc",
+            "resume_gateway_url": "wss://gateway.discord.gg",
+            "application": { "id": "1", "flags": 0 },
+        });
+        Event::Ready(Arc::new(serde_json::from_value(json).unwrap()))
+    }
+
+    fn guild_create(id: u64) -> Event<'static> {
+        let json = serde_json::json!({
+            "id": id.to_string(),
+            "name": "guild",
+            "owner_id": "1",
+        });
+        Event::GuildCreate(Arc::new(serde_json::from_value(json).unwrap()))
+    }
+
+    fn id_of(event: Option<Event<'static>>) -> Option<Snowflake> {
+        event.as_ref().and_then(dedup_key)
+    }
+
+    #[test]
+    fn test_swap_without_duplicates() {
+        let router = EventRouter::default();
+        assert!(router.route(0, 0, message(1)).is_some());
+
+        assert!(router.begin(1, &[0, 1], true));
+        assert!(!router.begin(2, &[0], true));
+
+        // The old set keeps delivering, the new set is held back
+        assert_eq!(
+            id_of(router.route(0, 0, message(2))),
+            Some(Snowflake::new(2))
+        );
+        assert!(router.route(1, 0, message(2)).is_none());
+        assert!(router.route(1, 1, message(3)).is_none());
+        assert!(!router.is_ready());
+
+        // Message 3 only reached the new set before the swap, and message 4
+        // arriving during the replay is held back behind it
+        router.swap();
+        assert!(router.route(1, 0, message(4)).is_none());
+        let replay: Vec<_> = std::iter::from_fn(|| router.next_replay())
+            .map(|(_, event)| dedup_key(&event))
+            .collect();
+        assert_eq!(replay, [Some(Snowflake::new(3)), Some(Snowflake::new(4))]);
+
+        // Late copies from the old set and duplicates from the new set are dropped
+        assert!(router.route(0, 0, message(3)).is_none());
+        assert!(router.route(1, 0, message(2)).is_none());
+        assert_eq!(
+            id_of(router.route(1, 0, message(5))),
+            Some(Snowflake::new(5))
+        );
+    }
+
+    #[test]
+    fn test_ready_after_guilds() {
+        let router = EventRouter::default();
+        assert!(!router.is_ready());
+        assert!(router.begin(1, &[0, 1], true));
+
+        router.route(1, 0, ready(&[10, 11]));
+        router.route(1, 1, ready(&[]));
+        router.route(1, 0, guild_create(10));
+        assert!(!router.is_ready());
+
+        // Events of shards outside the set do not count
+        router.route(1, 2, guild_create(11));
+        assert!(!router.is_ready());
+        router.route(1, 0, guild_create(11));
+        assert!(router.is_ready());
+    }
+
+    #[test]
+    fn test_ready_without_waiting_for_guilds() {
+        let router = EventRouter::default();
+        assert!(router.begin(1, &[0], false));
+        router.route(1, 0, ready(&[10]));
+        assert!(router.is_ready());
+    }
+
+    #[test]
+    fn test_abort() {
+        let router = EventRouter::default();
+        assert!(router.begin(1, &[0], true));
+        router.route(1, 0, ready(&[]));
+        router.abort();
+
+        // The old set stays active and the failed set is ignored
+        assert!(!router.overlapping.load(Ordering::Acquire));
+        assert!(router.is_active(0));
+        assert!(!router.is_ready());
+        assert!(router.route(0, 0, message(1)).is_some());
+        assert!(router.route(1, 0, message(2)).is_none());
+
+        // A new reshard may start, and aborting after the swap does nothing
+        assert!(router.begin(2, &[0], true));
+        router.swap();
+        router.abort();
+        assert!(router.is_active(2));
+    }
+
+    #[test]
+    fn test_overlap_expires() {
+        let router = EventRouter::default();
+        assert!(router.begin(1, &[0], true));
+        router.swap();
+        while router.next_replay().is_some() {}
+
+        if let Some(overlap) = router.overlap.lock().as_mut() {
+            overlap.swapped_at = Some(Instant::now() - OVERLAP_WINDOW - Duration::from_secs(1));
+        }
+        assert!(router.route(1, 0, message(1)).is_some());
+        assert!(router.overlap.lock().is_none());
+        assert!(!router.overlapping.load(Ordering::Acquire));
+        assert!(router.route(0, 0, message(1)).is_none());
+    }
+}
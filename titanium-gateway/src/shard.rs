@@ -3,51 +3,130 @@
 //! A Shard represents a single WebSocket connection to Discord's Gateway.
 //! For large bots, multiple shards are used to distribute guild events.
 
-use crate::compression::ZlibDecompressor;
+use crate::compression::{TransportCompression, TransportDecompressor};
 use crate::error::{CloseCode, GatewayError};
-use crate::event::{parse_event, Event, ReadyEventData};
+use crate::etf::{self, EtfDecoder, EtfEncoder, EtfTerm, GatewayEncoding};
+use crate::event::{parse_event, parse_event_etf, Event, ReadyEventData};
+use crate::event_flags::{peek_dispatch, EventTypeFlags};
 use crate::heartbeat::HeartbeatHandler;
+use crate::identify::{IdentifyQueue, InMemoryIdentifyQueue};
+use crate::lifecycle::{ClusterEvent, ShardEvent, ShardStopReason, LIFECYCLE_CAPACITY};
+use crate::members::{MemberChunkStream, MemberRequests};
+use crate::metrics::{GatewayMetrics, ShardMetrics};
 use crate::opcode::OpCode;
 use crate::payload::{
-    create_heartbeat_payload, GatewayPayload, HelloPayload, IdentifyPayload, RawGatewayPayload,
-    ResumePayload,
+    create_heartbeat_payload, GatewayPayload, HelloPayload, IdentifyPayload, PresenceUpdate,
+    RawGatewayPayload, RequestGuildMembersPayload, RequestSoundboardSoundsPayload, ResumePayload,
+    UpdateVoiceStatePayload,
 };
-use crate::ratelimit::{exponential_backoff, with_jitter, IdentifyRateLimiter};
+use crate::proxy::ProxyConfig;
+use crate::queue::{Delivery, EventSender, OverflowPolicy, OverflowSender};
+use crate::ratelimit::{exponential_backoff, with_jitter, CommandRateLimiter, IdentifyRateLimiter};
+use crate::raw::{DispatchMode, RawDispatch};
+use crate::recording::SessionRecorder;
+use crate::reshard::EventRouter;
+use crate::voice::{VoiceConnectionFuture, VoiceRequests};
 use crate::{DEFAULT_GATEWAY_URL, GATEWAY_VERSION};
 
 use flume::Sender;
 use futures_util::{SinkExt, StreamExt};
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
 #[cfg(feature = "simd")]
 use simd_json::prelude::*;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
+use titanium_model::Snowflake;
 use tokio::net::TcpStream;
+use tokio::sync::broadcast;
 use tokio::time::{sleep, timeout};
 use tokio_tungstenite::tungstenite::client::IntoClientRequest;
 use tokio_tungstenite::tungstenite::protocol::CloseFrame;
 use tokio_tungstenite::tungstenite::Message as WsMessage;
-use tokio_tungstenite::{client_async, MaybeTlsStream, WebSocketStream};
+use tokio_tungstenite::{client_async_tls_with_config, MaybeTlsStream, WebSocketStream};
 use tracing::{debug, error, info, trace, warn};
 use url::Url;
 
 /// Command sent to the shard from the application.
 #[derive(Debug)]
 enum ShardCommand {
-    /// Send a raw JSON payload.
-    Send(String),
+    /// Send an already encoded payload.
+    Send(WsMessage),
 }
 
 /// Internal action to take after parsing a frame.
 enum GatewayAction {
-    Dispatch(Event<'static>),
+    /// A dispatch, with its type if it is delivered rather than only
+    /// handled internally.
+    Dispatch(Event<'static>, Option<EventTypeFlags>),
     Heartbeat,
     Reconnect,
     InvalidSession(bool),
     None,
 }
 
+/// What to do with a dispatch, given the configured event types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DispatchFilter {
+    /// Deserialize and deliver.
+    Deliver,
+    /// Deserialize for a pending request, but do not deliver.
+    Internal,
+    /// Drop without deserializing.
+    Skip,
+}
+
+impl DispatchFilter {
+    /// Type of the dispatch, if it is delivered.
+    fn delivered(self, event_name: &str) -> Option<EventTypeFlags> {
+        (self == Self::Deliver).then(|| EventTypeFlags::from_event_name(event_name))
+    }
+}
+
+/// Envelope of a decoded ETF payload, borrowing `d` from the term.
+pub(crate) struct EtfPayload<'a> {
+    pub(crate) op: OpCode,
+    pub(crate) d: Option<&'a EtfTerm>,
+    pub(crate) s: Option<u64>,
+    pub(crate) t: Option<String>,
+}
+
+impl<'a> EtfPayload<'a> {
+    pub(crate) fn from_term(term: &'a EtfTerm) -> Result<Self, GatewayError> {
+        let EtfTerm::Map(pairs) = term else {
+            return Err(GatewayError::Etf("ETF payload is not a map".to_string()));
+        };
+
+        let mut op = None;
+        let mut payload = Self {
+            op: OpCode::Dispatch,
+            d: None,
+            s: None,
+            t: None,
+        };
+
+        for (key, value) in pairs {
+            let key = match key {
+                EtfTerm::Atom(key) | EtfTerm::String(key) => key.as_bytes(),
+                EtfTerm::Binary(key) => key.as_slice(),
+                _ => continue,
+            };
+            match key {
+                b"op" => op = Some(etf::from_term::<OpCode>(value)?),
+                b"d" => payload.d = Some(value),
+                b"s" => payload.s = etf::from_term(value)?,
+                b"t" => payload.t = etf::from_term(value)?,
+                _ => {}
+            }
+        }
+
+        payload.op =
+            op.ok_or_else(|| GatewayError::Etf("ETF payload has no opcode".to_string()))?;
+        Ok(payload)
+    }
+}
+
 /// Type alias for the WebSocket stream.
 type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
@@ -87,9 +166,19 @@ pub struct ShardConfig {
     /// Large guild threshold (50-250).
     pub large_threshold: u8,
 
+    /// Transport compression.
+    pub compression: TransportCompression,
+
     /// Enable zlib compression.
+    ///
+    /// Shards use `zlib-stream` if this is set and `compression` is
+    /// [`TransportCompression::None`].
+    #[deprecated(since = "0.1.7", note = "use `compression` instead")]
     pub compress: bool,
 
+    /// Payload encoding (JSON or ETF).
+    pub encoding: GatewayEncoding,
+
     /// Maximum reconnection attempts before giving up.
     pub max_reconnect_attempts: u32,
 
@@ -98,21 +187,48 @@ pub struct ShardConfig {
 
     /// Maximum reconnect delay in milliseconds.
     pub reconnect_max_delay_ms: u64,
+
+    /// Proxy to tunnel the connection through.
+    pub proxy: Option<ProxyConfig>,
+
+    /// Presence sent with Identify.
+    pub presence: Option<PresenceUpdate>,
+
+    /// Session to resume on the first connection instead of identifying.
+    pub session: Option<ShardSession>,
+
+    /// Dispatch types to deserialize and deliver.
+    pub event_types: EventTypeFlags,
+
+    /// Whether dispatches are delivered parsed, raw or both.
+    pub dispatch_mode: DispatchMode,
+
+    /// Recorder of received frames.
+    pub recorder: Option<Arc<SessionRecorder>>,
 }
 
 impl ShardConfig {
     /// Create a new shard configuration with required fields.
     #[must_use]
+    #[allow(deprecated)]
     pub fn new(token: impl Into<String>, intents: titanium_model::Intents) -> Self {
         Self {
             token: token.into(),
             intents,
             gateway_url: DEFAULT_GATEWAY_URL.to_string(),
             large_threshold: 250,
+            compression: TransportCompression::default(),
             compress: false,
+            encoding: GatewayEncoding::default(),
             max_reconnect_attempts: 10,
             reconnect_base_delay_ms: 1000,
             reconnect_max_delay_ms: 60000,
+            proxy: None,
+            presence: None,
+            session: None,
+            event_types: EventTypeFlags::all(),
+            dispatch_mode: DispatchMode::default(),
+            recorder: None,
         }
     }
 
@@ -122,6 +238,107 @@ impl ShardConfig {
         self.gateway_url = url.into();
         self
     }
+
+    /// Set the transport compression.
+    #[must_use]
+    pub fn with_compression(mut self, compression: TransportCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Transport compression, taking the deprecated `compress` flag into account.
+    #[allow(deprecated)]
+    fn transport_compression(&self) -> TransportCompression {
+        match self.compression {
+            TransportCompression::None if self.compress => TransportCompression::ZlibStream,
+            compression => compression,
+        }
+    }
+
+    /// Set the payload encoding.
+    ///
+    /// ETF payloads are smaller than JSON and are decoded straight into
+    /// event types.
+    #[must_use]
+    pub fn with_encoding(mut self, encoding: GatewayEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Connect through an HTTP `CONNECT` or SOCKS5 proxy.
+    #[must_use]
+    pub fn with_proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Set the presence sent with Identify.
+    #[must_use]
+    pub fn with_presence(mut self, presence: PresenceUpdate) -> Self {
+        self.presence = Some(presence);
+        self
+    }
+
+    /// Resume a session saved by a previous process.
+    ///
+    /// Ignored if it was made for another shard. If Discord rejects the
+    /// resume, the shard identifies as usual.
+    #[must_use]
+    pub fn with_session(mut self, session: ShardSession) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// Only deserialize and deliver the given dispatch types.
+    ///
+    /// Other dispatches are dropped right after reading their type, skipping
+    /// most of the parsing work. Sequence numbers are still tracked.
+    #[must_use]
+    pub fn with_event_types(mut self, event_types: EventTypeFlags) -> Self {
+        self.event_types = event_types;
+        self
+    }
+
+    /// Choose between parsed events and raw dispatches.
+    ///
+    /// Raw dispatches are received through [`Shard::raw_dispatches`].
+    #[must_use]
+    pub fn with_dispatch_mode(mut self, dispatch_mode: DispatchMode) -> Self {
+        self.dispatch_mode = dispatch_mode;
+        self
+    }
+
+    /// Record every received frame, e.g. to attach to a bug report.
+    #[must_use]
+    pub fn with_recorder(mut self, recorder: Arc<SessionRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+}
+
+/// Snapshot of a shard's session, for resuming after a process restart.
+///
+/// Take it with [`Shard::session`] after [`Shard::shutdown_resumable`] (or from
+/// [`Cluster::shutdown_resumable`](crate::Cluster::shutdown_resumable)),
+/// store it anywhere serde can write to, and pass it back through
+/// [`ShardConfig::with_session`]. Discord keeps sessions alive for a short
+/// while only, so this is meant for restarts and deploys.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardSession {
+    /// Shard ID the session belongs to.
+    pub shard_id: u16,
+
+    /// Total number of shards when the session was created.
+    pub total_shards: u16,
+
+    /// Session ID from the Ready event.
+    pub session_id: String,
+
+    /// Resume URL from the Ready event.
+    pub resume_url: String,
+
+    /// Last sequence number received.
+    pub sequence: u64,
 }
 
 /// Session data for resuming connections.
@@ -153,8 +370,8 @@ pub struct Shard {
     /// Shard configuration.
     config: ShardConfig,
 
-    /// Rate limiter for identify (shared across cluster).
-    rate_limiter: Arc<IdentifyRateLimiter>,
+    /// Identify queue (shared across cluster).
+    identify_queue: Arc<dyn IdentifyQueue>,
 
     // =========================================================================
     // State
@@ -171,17 +388,57 @@ pub struct Shard {
     /// Heartbeat handler.
     heartbeat: HeartbeatHandler,
 
-    /// Zlib-stream decompressor.
-    decompressor: RwLock<ZlibDecompressor>,
+    /// Transport decompressor (`None` without compression).
+    decompressor: RwLock<Option<TransportDecompressor>>,
+
+    /// In-flight Request Guild Members calls.
+    member_requests: MemberRequests,
+
+    /// Current presence, re-sent when identifying again.
+    presence: RwLock<Option<PresenceUpdate>>,
+
+    /// In-flight Voice State Update calls.
+    voice_requests: VoiceRequests,
+
+    /// ID of the bot user, known after Ready.
+    user_id: RwLock<Option<Snowflake>>,
+
+    /// Pacing of outgoing commands on the current connection.
+    command_limiter: CommandRateLimiter,
 
     /// Whether shutdown has been requested.
     shutdown: AtomicBool,
 
+    /// Whether shutting down keeps the session resumable.
+    keep_session: AtomicBool,
+
+    /// Metrics of this shard.
+    metrics: Arc<ShardMetrics>,
+
+    /// Metrics shared with the other shards of a cluster.
+    gateway_metrics: Arc<GatewayMetrics>,
+
+    /// Lifecycle event subscribers.
+    lifecycle_tx: broadcast::Sender<ShardEvent>,
+
+    /// Lifecycle events of the owning cluster, if any.
+    cluster_events: Option<broadcast::Sender<ClusterEvent>>,
+
     /// Channel for sending commands to the shard loop.
     command_tx: Sender<ShardCommand>,
 
     /// Channel for receiving commands in the shard loop.
     command_rx: flume::Receiver<ShardCommand>,
+
+    /// Channel for raw dispatches.
+    raw_tx: Arc<OverflowSender<RawDispatch>>,
+
+    /// Receiving end of the raw dispatch channel, handed out to consumers.
+    raw_rx: flume::Receiver<RawDispatch>,
+
+    /// Router and shard set generation deciding whether raw dispatches are
+    /// delivered, when run by a cluster.
+    raw_route: Option<(Arc<EventRouter>, u64)>,
 }
 
 impl Shard {
@@ -193,11 +450,11 @@ impl Shard {
     /// * `config` - Shard configuration.
     #[must_use]
     pub fn new(shard_id: u16, total_shards: u16, config: ShardConfig) -> Self {
-        Self::with_rate_limiter(
+        Self::with_identify_queue(
             shard_id,
             total_shards,
             config,
-            Arc::new(IdentifyRateLimiter::default()),
+            Arc::new(InMemoryIdentifyQueue::default()),
         )
     }
 
@@ -208,25 +465,108 @@ impl Shard {
         total_shards: u16,
         config: ShardConfig,
         rate_limiter: Arc<IdentifyRateLimiter>,
+    ) -> Self {
+        Self::with_identify_queue(shard_id, total_shards, config, rate_limiter)
+    }
+
+    /// Create a new shard with a shared identify queue.
+    #[must_use]
+    pub fn with_identify_queue(
+        shard_id: u16,
+        total_shards: u16,
+        mut config: ShardConfig,
+        identify_queue: Arc<dyn IdentifyQueue>,
     ) -> Self {
         let (tx, rx) = flume::unbounded();
+        let (raw_tx, raw_rx) = OverflowSender::new(None, OverflowPolicy::Block);
+        config.compression = config.transport_compression();
+        let decompressor = TransportDecompressor::new(config.compression);
+        let presence = config.presence.clone();
+
+        let mut sequence = 0;
+        let session = config.session.as_ref().and_then(|saved| {
+            if saved.shard_id != shard_id || saved.total_shards != total_shards {
+                warn!(
+                    shard_id = shard_id,
+                    saved_shard = saved.shard_id,
+                    saved_total = saved.total_shards,
+                    "Ignoring session saved for another shard"
+                );
+                return None;
+            }
+            sequence = saved.sequence;
+            Some(SessionData {
+                session_id: saved.session_id.clone(),
+                resume_url: saved.resume_url.clone(),
+            })
+        });
 
         Self {
             shard_id,
             total_shards,
             config,
-            rate_limiter,
+            identify_queue,
             state: RwLock::new(ShardState::Disconnected),
-            session: RwLock::new(None),
-            sequence: AtomicU64::new(0),
+            session: RwLock::new(session),
+            sequence: AtomicU64::new(sequence),
             heartbeat: HeartbeatHandler::default(),
-            decompressor: RwLock::new(ZlibDecompressor::new()),
+            decompressor: RwLock::new(decompressor),
+            member_requests: MemberRequests::default(),
+            presence: RwLock::new(presence),
+            voice_requests: VoiceRequests::default(),
+            user_id: RwLock::new(None),
+            command_limiter: CommandRateLimiter::default(),
+            metrics: Arc::new(ShardMetrics::new(shard_id)),
+            gateway_metrics: Arc::new(GatewayMetrics::new()),
+            lifecycle_tx: broadcast::channel(LIFECYCLE_CAPACITY).0,
+            cluster_events: None,
             shutdown: AtomicBool::new(false),
+            keep_session: AtomicBool::new(false),
             command_tx: tx,
             command_rx: rx,
+            raw_tx: Arc::new(raw_tx),
+            raw_rx,
+            raw_route: None,
         }
     }
 
+    /// Also publish lifecycle events to a cluster's channel.
+    pub(crate) fn with_cluster_events(mut self, tx: broadcast::Sender<ClusterEvent>) -> Self {
+        self.cluster_events = Some(tx);
+        self
+    }
+
+    /// Send raw dispatches to a cluster's channel while shard set
+    /// `generation` is active.
+    pub(crate) fn with_raw_dispatches(
+        mut self,
+        tx: Arc<OverflowSender<RawDispatch>>,
+        router: Arc<EventRouter>,
+        generation: u64,
+    ) -> Self {
+        self.raw_tx = tx;
+        self.raw_route = Some((router, generation));
+        self
+    }
+
+    /// Count into metrics shared with other shards.
+    pub(crate) fn with_gateway_metrics(mut self, metrics: Arc<GatewayMetrics>) -> Self {
+        self.gateway_metrics = metrics;
+        self
+    }
+
+    /// Get the metrics of this shard.
+    pub fn metrics(&self) -> &Arc<ShardMetrics> {
+        &self.metrics
+    }
+
+    /// Get the gateway-wide metrics this shard counts into.
+    ///
+    /// Shared by every shard of a [`Cluster`](crate::Cluster).
+    pub fn gateway_metrics(&self) -> &Arc<GatewayMetrics> {
+        &self.gateway_metrics
+    }
+
     /// Get the shard ID.
     #[must_use]
     pub const fn shard_id(&self) -> u16 {
@@ -244,51 +584,373 @@ impl Shard {
         *self.state.read()
     }
 
+    /// Subscribe to the shard's lifecycle events.
+    ///
+    /// Only events after subscribing are received. Subscribers that fall
+    /// more than 256 events behind skip the oldest ones.
+    pub fn lifecycle_events(&self) -> broadcast::Receiver<ShardEvent> {
+        self.lifecycle_tx.subscribe()
+    }
+
+    /// Receiver of raw dispatches, if the dispatch mode includes them.
+    ///
+    /// All receivers share one queue, so each dispatch goes to one of them.
+    /// The queue is unbounded: keep consuming it while the shard runs.
+    /// Shards run by a cluster deliver to
+    /// [`Cluster::raw_dispatches`](crate::Cluster::raw_dispatches) instead.
+    pub fn raw_dispatches(&self) -> flume::Receiver<RawDispatch> {
+        self.raw_rx.clone()
+    }
+
+    /// Move to `to`, announcing the transition.
+    fn set_state(&self, to: ShardState) {
+        let from = std::mem::replace(&mut *self.state.write(), to);
+        if from != to {
+            self.emit(ShardEvent::StateChanged {
+                shard_id: self.shard_id,
+                from,
+                to,
+            });
+        }
+    }
+
+    /// Publish a lifecycle event.
+    fn emit(&self, event: ShardEvent) {
+        if let Some(tx) = &self.cluster_events {
+            let _ = tx.send(ClusterEvent::Shard(event.clone()));
+        }
+        // Fails only without subscribers
+        let _ = self.lifecycle_tx.send(event);
+    }
+
+    /// Stop for good, announcing why.
+    fn stop(&self, reason: ShardStopReason) {
+        self.member_requests.clear();
+        self.metrics.mark_disconnected();
+        self.set_state(ShardState::Disconnected);
+        self.emit(ShardEvent::Stopped {
+            shard_id: self.shard_id,
+            reason,
+        });
+    }
+
     /// Get the last sequence number.
     pub fn sequence(&self) -> u64 {
         self.sequence.load(Ordering::SeqCst)
     }
 
+    /// Get a snapshot of the current session, if the shard has one.
+    ///
+    /// See [`ShardSession`].
+    pub fn session(&self) -> Option<ShardSession> {
+        self.session.read().as_ref().map(|session| ShardSession {
+            shard_id: self.shard_id,
+            total_shards: self.total_shards,
+            session_id: session.session_id.clone(),
+            resume_url: session.resume_url.clone(),
+            sequence: self.sequence(),
+        })
+    }
+
     /// Request a graceful shutdown.
+    ///
+    /// The connection is closed normally, which ends the session and takes
+    /// the bot offline right away.
     pub fn shutdown(&self) {
         self.shutdown.store(true, Ordering::SeqCst);
     }
 
+    /// Request a graceful shutdown that keeps the session resumable.
+    ///
+    /// Take the session with [`session`](Self::session) once the shard
+    /// stopped, to resume it from another process. Until it is resumed or
+    /// times out, Discord keeps the bot online.
+    pub fn shutdown_resumable(&self) {
+        self.keep_session.store(true, Ordering::SeqCst);
+        self.shutdown.store(true, Ordering::SeqCst);
+    }
+
     /// Get the last measured latency.
     pub fn latency(&self) -> Option<Duration> {
         self.heartbeat.latency()
     }
 
+    /// Get the presence used for Identify.
+    pub(crate) fn presence(&self) -> Option<PresenceUpdate> {
+        self.presence.read().clone()
+    }
+
+    /// Get the ID of the bot user, once Ready was received.
+    pub fn current_user_id(&self) -> Option<Snowflake> {
+        *self.user_id.read()
+    }
+
+    /// Get the number of commands that can be sent right now.
+    ///
+    /// Discord allows 120 commands per 60 seconds on a connection, part of
+    /// which is reserved for heartbeats. Commands still queued are taken
+    /// off.
+    pub fn available_commands(&self) -> u32 {
+        self.command_limiter
+            .available()
+            .saturating_sub(self.queued_commands())
+    }
+
     /// Send a raw payload to the gateway.
     ///
-    /// This is useful for sending voice state updates (Op 4) or presence updates (Op 3).
+    /// This is useful for opcodes without a typed method.
     /// Accepts any type that implements `serde::Serialize`.
     ///
     /// # Errors
-    /// Returns `GatewayError::JsonDecode` if serialization fails, or `GatewayError::Closed` if the command channel is closed.
+    /// Returns `GatewayError::JsonDecode` (or `GatewayError::Etf`) if serialization fails, `GatewayError::RateLimited`
+    /// if the command rate limit is exhausted, or `GatewayError::Closed` if the command channel is closed.
     pub fn send_payload<T: serde::Serialize>(&self, payload: &T) -> Result<(), GatewayError> {
-        let json = self.serialize_payload(payload)?;
+        let message = self.encode_payload(payload)?;
+        self.command_limiter.check(self.queued_commands())?;
+        self.queue_command(message)
+    }
+
+    /// Send a raw payload to the gateway, waiting for the command rate limit.
+    ///
+    /// Like [`send_payload`](Self::send_payload), but waits for a free slot
+    /// instead of failing with `GatewayError::RateLimited`.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::JsonDecode` (or `GatewayError::Etf`) if serialization fails, or `GatewayError::Closed` if the command channel is closed.
+    pub async fn send_payload_async<T: serde::Serialize>(
+        &self,
+        payload: &T,
+    ) -> Result<(), GatewayError> {
+        let message = self.encode_payload(payload)?;
+        self.command_limiter
+            .wait_for_room(|| self.queued_commands())
+            .await;
+        self.queue_command(message)
+    }
 
+    /// Number of commands waiting for the event loop to send them.
+    fn queued_commands(&self) -> u32 {
+        u32::try_from(self.command_rx.len()).unwrap_or(u32::MAX)
+    }
+
+    fn queue_command(&self, message: WsMessage) -> Result<(), GatewayError> {
         self.command_tx
-            .send(ShardCommand::Send(json))
+            .send(ShardCommand::Send(message))
             .map_err(|_| GatewayError::Closed {
                 code: 0,
                 reason: "Shard command channel closed".to_string(),
             })
     }
 
-    /// Helper to serialize payload based on features
-    #[allow(clippy::unused_self)]
-    fn serialize_payload<T: serde::Serialize>(&self, payload: &T) -> Result<String, GatewayError> {
-        #[cfg(feature = "simd")]
-        {
-            Ok(simd_json::to_string(payload)?)
+    /// Update the bot's presence (Op 3).
+    ///
+    /// The presence is also kept for later Identify calls, so it survives
+    /// reconnects that start a new session.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::RateLimited` if the command rate limit is
+    /// exhausted, or `GatewayError::Closed` if the command channel is closed.
+    pub fn update_presence(&self, presence: PresenceUpdate) -> Result<(), GatewayError> {
+        let payload = GatewayPayload::new(OpCode::PresenceUpdate, &presence);
+        self.send_payload(&payload)?;
+        *self.presence.write() = Some(presence);
+        Ok(())
+    }
+
+    /// Update the bot's presence (Op 3), waiting for the command rate limit.
+    ///
+    /// See [`update_presence`](Self::update_presence).
+    ///
+    /// # Errors
+    /// Returns `GatewayError::Closed` if the command channel is closed.
+    pub async fn update_presence_async(
+        &self,
+        presence: PresenceUpdate,
+    ) -> Result<(), GatewayError> {
+        let payload = GatewayPayload::new(OpCode::PresenceUpdate, &presence);
+        self.send_payload_async(&payload).await?;
+        *self.presence.write() = Some(presence);
+        Ok(())
+    }
+
+    /// Join, move between or leave voice channels (Op 4).
+    ///
+    /// Pass `None` as `channel_id` to disconnect. When joining, the returned
+    /// future resolves with the bot's `VOICE_STATE_UPDATE` and the matching
+    /// `VOICE_SERVER_UPDATE`, which together build a
+    /// `titanium_voice::VoiceConfig`. When leaving, the future fails and can
+    /// be dropped. The guild must belong to this shard.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::RateLimited` if the command rate limit is
+    /// exhausted, or `GatewayError::Closed` if the command channel is closed.
+    pub fn update_voice_state(
+        &self,
+        guild_id: Snowflake,
+        channel_id: Option<Snowflake>,
+        self_mute: bool,
+        self_deaf: bool,
+    ) -> Result<VoiceConnectionFuture, GatewayError> {
+        let state = UpdateVoiceStatePayload {
+            guild_id,
+            channel_id,
+            self_mute,
+            self_deaf,
+        };
+        // On failure, dropping the future cancels the registration
+        let future = self.register_voice_state(&state);
+        self.send_payload(&GatewayPayload::new(OpCode::VoiceStateUpdate, state))?;
+        Ok(future)
+    }
+
+    /// Join, move between or leave voice channels (Op 4), waiting for the
+    /// command rate limit.
+    ///
+    /// See [`update_voice_state`](Self::update_voice_state).
+    ///
+    /// # Errors
+    /// Returns `GatewayError::Closed` if the command channel is closed.
+    pub async fn update_voice_state_async(
+        &self,
+        guild_id: Snowflake,
+        channel_id: Option<Snowflake>,
+        self_mute: bool,
+        self_deaf: bool,
+    ) -> Result<VoiceConnectionFuture, GatewayError> {
+        let state = UpdateVoiceStatePayload {
+            guild_id,
+            channel_id,
+            self_mute,
+            self_deaf,
+        };
+        let future = self.register_voice_state(&state);
+        self.send_payload_async(&GatewayPayload::new(OpCode::VoiceStateUpdate, state))
+            .await?;
+        Ok(future)
+    }
+
+    /// Start waiting for the answer to a voice state update.
+    fn register_voice_state(&self, state: &UpdateVoiceStatePayload) -> VoiceConnectionFuture {
+        let future = self.voice_requests.register(state.guild_id);
+        if state.channel_id.is_none() {
+            // Nothing to pair when leaving
+            self.voice_requests.cancel(state.guild_id);
         }
+        future
+    }
 
-        #[cfg(not(feature = "simd"))]
-        {
-            Ok(serde_json::to_string(payload)?)
+    /// Request members of a guild (Op 8).
+    ///
+    /// A nonce is generated unless the request already has one. The returned
+    /// stream yields every `GUILD_MEMBERS_CHUNK` carrying that nonce; use
+    /// [`MemberChunkStream::collect_members`] to merge them. The guild must
+    /// belong to this shard.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::RateLimited` if the command rate limit is
+    /// exhausted, or `GatewayError::Closed` if the command channel is closed.
+    pub fn request_guild_members(
+        &self,
+        mut request: RequestGuildMembersPayload,
+    ) -> Result<MemberChunkStream, GatewayError> {
+        let stream = self.register_member_request(&mut request);
+
+        let payload = GatewayPayload::new(OpCode::RequestGuildMembers, request);
+        if let Err(e) = self.send_payload(&payload) {
+            self.member_requests.cancel(stream.nonce());
+            return Err(e);
         }
+
+        Ok(stream)
+    }
+
+    /// Request members of a guild (Op 8), waiting for the command rate limit.
+    ///
+    /// See [`request_guild_members`](Self::request_guild_members).
+    ///
+    /// # Errors
+    /// Returns `GatewayError::Closed` if the command channel is closed.
+    pub async fn request_guild_members_async(
+        &self,
+        mut request: RequestGuildMembersPayload,
+    ) -> Result<MemberChunkStream, GatewayError> {
+        let stream = self.register_member_request(&mut request);
+
+        let payload = GatewayPayload::new(OpCode::RequestGuildMembers, request);
+        if let Err(e) = self.send_payload_async(&payload).await {
+            self.member_requests.cancel(stream.nonce());
+            return Err(e);
+        }
+
+        Ok(stream)
+    }
+
+    /// Assign a nonce to a member request and start collecting its chunks.
+    fn register_member_request(
+        &self,
+        request: &mut RequestGuildMembersPayload,
+    ) -> MemberChunkStream {
+        let nonce = request
+            .nonce
+            .get_or_insert_with(|| self.member_requests.next_nonce(self.shard_id))
+            .clone();
+        self.member_requests.register(request.guild_id, nonce)
+    }
+
+    /// Request the soundboard sounds of the given guilds (Op 31).
+    ///
+    /// Discord responds with a [`Event::SoundboardSounds`] dispatch per guild.
+    /// All guilds must belong to this shard.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::RateLimited` if the command rate limit is
+    /// exhausted, or `GatewayError::Closed` if the command channel is closed.
+    pub fn request_soundboard_sounds(
+        &self,
+        guild_ids: impl IntoIterator<Item = Snowflake>,
+    ) -> Result<(), GatewayError> {
+        let payload = GatewayPayload::new(
+            OpCode::RequestSoundboardSounds,
+            RequestSoundboardSoundsPayload {
+                guild_ids: guild_ids.into_iter().collect(),
+            },
+        );
+        self.send_payload(&payload)
+    }
+
+    /// Request the soundboard sounds of the given guilds (Op 31), waiting for
+    /// the command rate limit.
+    ///
+    /// See [`request_soundboard_sounds`](Self::request_soundboard_sounds).
+    ///
+    /// # Errors
+    /// Returns `GatewayError::Closed` if the command channel is closed.
+    pub async fn request_soundboard_sounds_async(
+        &self,
+        guild_ids: impl IntoIterator<Item = Snowflake>,
+    ) -> Result<(), GatewayError> {
+        let payload = GatewayPayload::new(
+            OpCode::RequestSoundboardSounds,
+            RequestSoundboardSoundsPayload {
+                guild_ids: guild_ids.into_iter().collect(),
+            },
+        );
+        self.send_payload_async(&payload).await
+    }
+
+    /// Encode a payload into a WebSocket message for the configured encoding.
+    fn encode_payload<T: serde::Serialize>(&self, payload: &T) -> Result<WsMessage, GatewayError> {
+        if self.config.encoding == GatewayEncoding::Etf {
+            return Ok(WsMessage::Binary(EtfEncoder::encode(payload)?.into()));
+        }
+
+        #[cfg(feature = "simd")]
+        let json = simd_json::to_string(payload)?;
+
+        #[cfg(not(feature = "simd"))]
+        let json = serde_json::to_string(payload)?;
+
+        Ok(WsMessage::Text(json.into()))
     }
 
     /// Run the shard event loop.
@@ -305,6 +967,11 @@ impl Shard {
     /// # Errors
     /// Returns `GatewayError` if the connection fails or closes unexpectedly.
     pub async fn run(&self, event_tx: Sender<Event<'static>>) -> Result<(), GatewayError> {
+        self.run_with(EventSender::Direct(event_tx)).await
+    }
+
+    /// Run the shard event loop, delivering events to `events`.
+    pub(crate) async fn run_with(&self, events: EventSender) -> Result<(), GatewayError> {
         let mut reconnect_attempts = 0u32;
         let mut read_buffer = Vec::with_capacity(32 * 1024);
 
@@ -312,14 +979,22 @@ impl Shard {
             // Check for shutdown
             if self.shutdown.load(Ordering::SeqCst) {
                 info!(shard_id = self.shard_id, "Shard shutdown requested");
-                *self.state.write() = ShardState::Disconnecting;
+                self.set_state(ShardState::Disconnecting);
+                self.stop(ShardStopReason::Shutdown);
                 return Ok(());
             }
 
             // Connect and run
-            match self.connect_and_run(&event_tx, &mut read_buffer).await {
+            let result = self.connect_and_run(&events, &mut read_buffer).await;
+            if result.is_err() && self.state() == ShardState::Connected {
+                // The connection was healthy, so this is a fresh failure
+                reconnect_attempts = 0;
+            }
+
+            match result {
                 Ok(()) => {
                     // Graceful disconnect (shutdown requested)
+                    self.stop(ShardStopReason::Shutdown);
                     return Ok(());
                 }
                 Err(GatewayError::HeartbeatTimeout) => {
@@ -327,13 +1002,24 @@ impl Shard {
                         shard_id = self.shard_id,
                         "Heartbeat timeout, reconnecting..."
                     );
+                    self.emit(ShardEvent::Disconnected {
+                        shard_id: self.shard_id,
+                        code: 0,
+                        close_code: None,
+                        reason: GatewayError::HeartbeatTimeout.to_string(),
+                    });
                     reconnect_attempts += 1;
                 }
                 Err(GatewayError::InvalidSession { resumable }) => {
+                    self.emit(ShardEvent::SessionInvalidated {
+                        shard_id: self.shard_id,
+                        resumable,
+                    });
                     if !resumable {
                         // Clear session to force new identify
                         *self.session.write() = None;
                         self.sequence.store(0, Ordering::SeqCst);
+                        self.member_requests.clear();
                     }
                     warn!(
                         shard_id = self.shard_id,
@@ -344,8 +1030,21 @@ impl Shard {
                 }
                 Err(GatewayError::Closed { code, reason }) => {
                     let close_code = CloseCode::from_code(code);
+                    self.emit(ShardEvent::Disconnected {
+                        shard_id: self.shard_id,
+                        code,
+                        close_code,
+                        reason: reason.clone(),
+                    });
 
                     if let Some(cc) = close_code {
+                        if matches!(cc, CloseCode::InvalidSeq | CloseCode::SessionTimedOut) {
+                            // The session cannot be resumed, identify instead
+                            *self.session.write() = None;
+                            self.sequence.store(0, Ordering::SeqCst);
+                            self.member_requests.clear();
+                        }
+
                         if !cc.can_reconnect() {
                             error!(
                                 shard_id = self.shard_id,
@@ -353,6 +1052,10 @@ impl Shard {
                                 reason = %reason,
                                 "Fatal close code, cannot reconnect"
                             );
+                            self.stop(ShardStopReason::FatalClose {
+                                code: cc,
+                                reason: reason.clone(),
+                            });
                             return Err(GatewayError::Closed { code, reason });
                         }
                     }
@@ -367,6 +1070,12 @@ impl Shard {
                 }
                 Err(e) => {
                     error!(shard_id = self.shard_id, error = %e, "Shard error");
+                    self.emit(ShardEvent::Disconnected {
+                        shard_id: self.shard_id,
+                        code: 0,
+                        close_code: None,
+                        reason: e.to_string(),
+                    });
                     reconnect_attempts += 1;
                 }
             }
@@ -378,6 +1087,9 @@ impl Shard {
                     attempts = reconnect_attempts,
                     "Max reconnect attempts exceeded"
                 );
+                self.stop(ShardStopReason::ReconnectsExhausted {
+                    attempts: reconnect_attempts,
+                });
                 return Err(GatewayError::Closed {
                     code: 0,
                     reason: "Max reconnect attempts exceeded".to_string(),
@@ -399,7 +1111,16 @@ impl Shard {
                 "Waiting before reconnect"
             );
 
-            *self.state.write() = ShardState::Reconnecting;
+            self.metrics.mark_disconnected();
+            self.metrics.reconnections.fetch_add(1, Ordering::Relaxed);
+            self.gateway_metrics.inc_reconnections();
+
+            self.set_state(ShardState::Reconnecting);
+            self.emit(ShardEvent::Reconnecting {
+                shard_id: self.shard_id,
+                attempt: reconnect_attempts,
+                backoff: backoff_with_jitter,
+            });
             sleep(backoff_with_jitter).await;
         }
     }
@@ -407,51 +1128,78 @@ impl Shard {
     /// Connect and run the event loop once.
     async fn connect_and_run(
         &self,
-        event_tx: &Sender<Event<'static>>,
+        events: &EventSender,
         buffer: &mut Vec<u8>,
     ) -> Result<(), GatewayError> {
         // Build connection URL
         let gateway_url = self.build_gateway_url()?;
 
         info!(shard_id = self.shard_id, url = %gateway_url, "Connecting to Gateway");
-        *self.state.write() = ShardState::Connecting;
+        self.set_state(ShardState::Connecting);
 
-        // Connect WebSocket
-        // Connect with TCP_NODELAY
+        // Connect with TCP_NODELAY, directly or through the proxy tunnel
         let request = gateway_url.as_str().into_client_request()?;
         let host = request.uri().host().ok_or_else(|| GatewayError::Closed {
             code: 0,
             reason: "Invalid Gateway URL: missing host".to_string(),
         })?;
-        let port = request.uri().port_u16().unwrap_or(443);
+        let default_port = if gateway_url.scheme() == "ws" {
+            80
+        } else {
+            443
+        };
+        let port = request.uri().port_u16().unwrap_or(default_port);
 
-        let stream = TcpStream::connect((host, port)).await?;
-        stream.set_nodelay(true)?;
+        let stream = match &self.config.proxy {
+            Some(proxy) => {
+                debug!(shard_id = self.shard_id, proxy = %proxy.addr, "Connecting through proxy");
+                proxy.connect(host, port).await?
+            }
+            None => {
+                let stream = TcpStream::connect((host, port)).await?;
+                stream.set_nodelay(true)?;
+                stream
+            }
+        };
 
-        let (ws_stream, _response) = client_async(request, MaybeTlsStream::Plain(stream)).await?;
+        // Performs the rustls handshake for wss:// URLs
+        let (ws_stream, _response) =
+            client_async_tls_with_config(request, stream, None, None).await?;
         let (mut sink, mut stream) = ws_stream.split();
 
         info!(shard_id = self.shard_id, "WebSocket connected");
-        *self.state.write() = ShardState::Handshaking;
+        self.set_state(ShardState::Handshaking);
+
+        // Every connection starts a new compression stream
+        if let Some(decompressor) = self.decompressor.write().as_mut() {
+            decompressor.reset();
+        }
 
         // Wait for Hello
         let hello = self.wait_for_hello(&mut stream).await?;
         let heartbeat_interval = Duration::from_millis(hello.heartbeat_interval);
         self.heartbeat.set_interval(heartbeat_interval);
 
+        // The command rate limit is per connection, queued commands take
+        // their tokens from the new bucket as they are sent
+        self.command_limiter
+            .set_heartbeat_interval(heartbeat_interval);
+        self.command_limiter.reset();
+
         debug!(
             shard_id = self.shard_id,
             interval_ms = hello.heartbeat_interval,
             "Received Hello"
         );
 
-        // Send Identify or Resume
-        self.rate_limiter.acquire().await?;
-
+        // Send Resume, or Identify once the identify queue lets us. The
+        // queue may take longer than the heartbeat interval, so the event
+        // loop keeps heartbeating while waiting.
+        let mut identify = None;
         let session = self.session.read().clone();
         if let Some(ref session_data) = session {
             // Try to resume
-            *self.state.write() = ShardState::Resuming;
+            self.set_state(ShardState::Resuming);
             info!(
                 shard_id = self.shard_id,
                 session_id = %session_data.session_id,
@@ -460,9 +1208,9 @@ impl Shard {
             self.send_resume(&mut sink, session_data).await?;
         } else {
             // Fresh identify
-            *self.state.write() = ShardState::Identifying;
-            info!(shard_id = self.shard_id, "Sending Identify");
-            self.send_identify(&mut sink).await?;
+            self.set_state(ShardState::Identifying);
+            debug!(shard_id = self.shard_id, "Waiting for the identify queue");
+            identify = Some(self.identify_queue.request(self.shard_id));
         }
 
         // Reset heartbeat ACK
@@ -479,17 +1227,38 @@ impl Shard {
         loop {
             // Check shutdown
             if self.shutdown.load(Ordering::SeqCst) {
-                // Send close frame
+                // Close codes 1000 and 1001 invalidate the session, so use a
+                // library code to allow resuming from another process
+                let code = if self.keep_session.load(Ordering::SeqCst) {
+                    4000
+                } else {
+                    *self.session.write() = None;
+                    1000
+                };
+                let _ = sink
+                    .send(WsMessage::Close(Some(CloseFrame {
+                        code: code.into(),
+                        reason: "Shutting down".into(),
+                    })))
+                    .await;
                 let _ = sink.close().await;
                 return Ok(());
             }
 
             tokio::select! {
+                // Identify queue slot granted
+                Some(result) = async { Some(identify.as_mut()?.await) }, if identify.is_some() => {
+                    identify = None;
+                    result?;
+                    info!(shard_id = self.shard_id, "Sending Identify");
+                    self.send_identify(&mut sink).await?;
+                }
+
                 // WebSocket message received
                 message = stream.next() => {
                     match message {
                         Some(Ok(msg)) => {
-                            self.handle_message(msg, event_tx, &mut sink, buffer).await?;
+                            self.handle_message(msg, events, &mut sink, buffer).await?;
                         }
                         Some(Err(e)) => {
                             return Err(GatewayError::WebSocket(e));
@@ -520,12 +1289,18 @@ impl Shard {
                     next_heartbeat = Instant::now() + self.heartbeat.interval();
                 }
 
-                // Command channel
-                command = self.command_rx.recv_async() => {
+                // Command channel, held back until identified and a token
+                // is free
+                command = async {
+                    self.command_limiter.ready().await;
+                    self.command_rx.recv_async().await
+                }, if identify.is_none() => {
                     match command {
-                        Ok(ShardCommand::Send(json)) => {
+                        Ok(ShardCommand::Send(message)) => {
                             trace!(shard_id = self.shard_id, "Sending custom payload");
-                            sink.send(WsMessage::Text(json.into())).await?;
+                            // Only this loop takes tokens, so one is still free
+                            self.command_limiter.acquire().await;
+                            sink.send(message).await?;
                         }
                         Err(_) => {
                             // Channel closed, connection likely dropping
@@ -557,10 +1332,10 @@ impl Shard {
         // Add query parameters
         url.query_pairs_mut()
             .append_pair("v", &GATEWAY_VERSION.to_string())
-            .append_pair("encoding", "json");
+            .append_pair("encoding", self.config.encoding.as_str());
 
-        if self.config.compress {
-            url.query_pairs_mut().append_pair("compress", "zlib-stream");
+        if let Some(compress) = self.config.compression.as_str() {
+            url.query_pairs_mut().append_pair("compress", compress);
         }
 
         Ok(url)
@@ -585,43 +1360,84 @@ impl Shard {
                 reason: "Connection closed before Hello".to_string(),
             })??;
 
-        if let WsMessage::Text(text) = message {
-            let payload: RawGatewayPayload = serde_json::from_str(&text)?;
-
-            if payload.op == OpCode::Hello {
-                if let Some(data) = payload.d {
-                    #[cfg(feature = "simd")]
-                    let hello: HelloPayload = titanium_model::json::from_value(data)?;
-                    #[cfg(not(feature = "simd"))]
-                    let hello: HelloPayload = serde_json::from_str(data.get())?;
-                    return Ok(hello);
+        let hello = match message {
+            WsMessage::Text(text) => Self::parse_json_hello(text.as_str().as_bytes())?,
+            WsMessage::Binary(data) => {
+                let mut decompressor = self.decompressor.write();
+                let frame: &[u8] = match decompressor.as_mut() {
+                    Some(decompressor) => decompressor
+                        .push(&data)
+                        .map_err(|e| GatewayError::JsonDecode(format!("Decompression error: {e}")))?
+                        .map_or(&[], |frame| &*frame),
+                    None => &data,
+                };
+
+                match self.config.encoding {
+                    GatewayEncoding::Json => Self::parse_json_hello(frame)?,
+                    GatewayEncoding::Etf => Self::parse_etf_hello(frame)?,
                 }
             }
-        }
+            _ => None,
+        };
 
-        Err(GatewayError::Closed {
+        hello.ok_or_else(|| GatewayError::Closed {
             code: 0,
             reason: "Expected Hello payload".to_string(),
         })
     }
 
+    /// Parse a JSON Hello frame, returning `None` for any other opcode.
+    fn parse_json_hello(frame: &[u8]) -> Result<Option<HelloPayload>, GatewayError> {
+        let payload: RawGatewayPayload = serde_json::from_slice(frame)?;
+
+        if payload.op != OpCode::Hello {
+            return Ok(None);
+        }
+
+        let Some(data) = payload.d else {
+            return Ok(None);
+        };
+
+        #[cfg(feature = "simd")]
+        let hello: HelloPayload = titanium_model::json::from_value(data)?;
+        #[cfg(not(feature = "simd"))]
+        let hello: HelloPayload = serde_json::from_str(data.get())?;
+
+        Ok(Some(hello))
+    }
+
+    /// Parse an ETF Hello frame, returning `None` for any other opcode.
+    fn parse_etf_hello(frame: &[u8]) -> Result<Option<HelloPayload>, GatewayError> {
+        let term = EtfDecoder::decode(frame)?;
+        let payload = EtfPayload::from_term(&term)?;
+
+        match (payload.op, payload.d) {
+            (OpCode::Hello, Some(data)) => Ok(Some(etf::from_term(data)?)),
+            _ => Ok(None),
+        }
+    }
+
     /// Send an Identify payload.
     async fn send_identify(
         &self,
         sink: &mut futures_util::stream::SplitSink<WsStream, WsMessage>,
     ) -> Result<(), GatewayError> {
-        let identify = IdentifyPayload::new(
+        let mut identify = IdentifyPayload::new(
             std::borrow::Cow::Borrowed(self.config.token.as_str()),
             self.config.intents,
         )
         .with_shard(self.shard_id, self.total_shards);
+        identify.presence = self.presence.read().clone();
 
         let payload = GatewayPayload::new(OpCode::Identify, identify);
 
-        let json = self.serialize_payload(&payload)?;
+        let message = self.encode_payload(&payload)?;
 
         trace!(shard_id = self.shard_id, "Sending Identify payload");
-        sink.send(WsMessage::Text(json.into())).await?;
+        sink.send(message).await?;
+
+        self.metrics.identifies_sent.fetch_add(1, Ordering::Relaxed);
+        self.gateway_metrics.inc_identifies();
 
         Ok(())
     }
@@ -640,10 +1456,10 @@ impl Shard {
 
         let payload = GatewayPayload::new(OpCode::Resume, resume);
 
-        let json = self.serialize_payload(&payload)?;
+        let message = self.encode_payload(&payload)?;
 
         trace!(shard_id = self.shard_id, "Sending Resume payload");
-        sink.send(WsMessage::Text(json.into())).await?;
+        sink.send(message).await?;
 
         Ok(())
     }
@@ -656,10 +1472,18 @@ impl Shard {
         let seq = self.sequence.load(Ordering::SeqCst);
         let seq_opt = if seq > 0 { Some(seq) } else { None };
 
-        let json = create_heartbeat_payload(seq_opt);
+        let message = match self.config.encoding {
+            GatewayEncoding::Json => WsMessage::Text(create_heartbeat_payload(seq_opt).into()),
+            GatewayEncoding::Etf => {
+                self.encode_payload(&GatewayPayload::new(OpCode::Heartbeat, seq_opt))?
+            }
+        };
 
         trace!(shard_id = self.shard_id, seq = seq, "Sending Heartbeat");
-        sink.send(WsMessage::Text(json.into())).await?;
+        sink.send(message).await?;
+
+        self.metrics.heartbeats_sent.fetch_add(1, Ordering::Relaxed);
+        self.gateway_metrics.inc_heartbeats_sent();
 
         Ok(())
     }
@@ -668,29 +1492,64 @@ impl Shard {
     async fn handle_message(
         &self,
         message: WsMessage,
-        event_tx: &Sender<Event<'static>>,
+        events: &EventSender,
         sink: &mut futures_util::stream::SplitSink<WsStream, WsMessage>,
         buffer: &mut Vec<u8>,
     ) -> Result<(), GatewayError> {
+        self.metrics
+            .ws_messages_received
+            .fetch_add(1, Ordering::Relaxed);
+        self.gateway_metrics.inc_ws_messages();
+
+        let mut raw = None;
         let action = match message {
             WsMessage::Text(text) => {
+                self.record_bytes(text.len(), text.len());
+
                 // Reuse scratch buffer to avoid allocation
                 buffer.clear();
                 buffer.extend_from_slice(text.as_str().as_bytes());
-                self.process_frame(buffer)?
+                self.record_frame(GatewayEncoding::Json, buffer);
+                self.process_frame(buffer, &mut raw)?
             }
             WsMessage::Binary(data) => {
-                // Binary messages are zlib-compressed
+                // Binary messages are compressed and/or ETF-encoded
                 // We use scopes to drop locks quickly
                 let mut decompressor = self.decompressor.write();
-                match decompressor.push(&data) {
-                    Ok(Some(msg)) => self.process_frame(msg)?,
-                    Ok(None) => GatewayAction::None, // Incomplete
-                    Err(e) => {
+                let frame = decompressor.as_mut().map(|d| d.push(&data));
+                let decompressed = match &frame {
+                    Some(Ok(Some(msg))) => msg.len(),
+                    Some(_) => 0,
+                    None => data.len(),
+                };
+                self.record_bytes(data.len(), decompressed);
+
+                match frame {
+                    Some(Ok(Some(msg))) => {
+                        self.record_frame(self.config.encoding, msg);
+                        match self.config.encoding {
+                            GatewayEncoding::Json => self.process_frame(msg, &mut raw)?,
+                            GatewayEncoding::Etf => self.process_etf_frame(msg, &mut raw)?,
+                        }
+                    }
+                    Some(Ok(None)) => GatewayAction::None, // Incomplete
+                    Some(Err(e)) => {
                         return Err(GatewayError::JsonDecode(format!(
                             "Decompression error: {e}",
                         )))
                     }
+                    None => match self.config.encoding {
+                        GatewayEncoding::Etf => {
+                            self.record_frame(GatewayEncoding::Etf, &data);
+                            self.process_etf_frame(&data, &mut raw)?
+                        }
+                        GatewayEncoding::Json => {
+                            buffer.clear();
+                            buffer.extend_from_slice(&data);
+                            self.record_frame(GatewayEncoding::Json, buffer);
+                            self.process_frame(buffer, &mut raw)?
+                        }
+                    },
                 }
             }
             WsMessage::Close(frame) => {
@@ -707,9 +1566,45 @@ impl Shard {
             WsMessage::Pong(_) | WsMessage::Frame(_) => return Ok(()),
         };
 
+        if let Some(raw) = raw {
+            self.send_raw(raw).await;
+        }
+
         match action {
-            GatewayAction::Dispatch(event) => {
-                event_tx.send_async(event).await?;
+            GatewayAction::Dispatch(event, kind) => {
+                match &event {
+                    Event::GuildMembersChunk(chunk) => self.member_requests.dispatch(chunk),
+                    Event::VoiceStateUpdate(state) => self
+                        .voice_requests
+                        .state_update(state, self.current_user_id()),
+                    Event::VoiceServerUpdate(server) => self.voice_requests.server_update(server),
+                    Event::GuildCreate(guild) => self.metrics.add_guild(guild.id),
+                    Event::GuildDelete(guild) if !guild.unavailable => {
+                        self.metrics.remove_guild(guild.id);
+                    }
+                    Event::Resumed => {
+                        info!(shard_id = self.shard_id, "Session resumed");
+                        self.metrics.mark_connected();
+                        self.metrics.session_resumes.fetch_add(1, Ordering::Relaxed);
+                        self.gateway_metrics.inc_session_resumes();
+                        self.set_state(ShardState::Connected);
+                        self.emit(ShardEvent::Resumed {
+                            shard_id: self.shard_id,
+                        });
+                    }
+                    _ => {}
+                }
+                if let Some(kind) = kind {
+                    match events.send(self.shard_id, event, kind).await? {
+                        Delivery::Sent => self.gateway_metrics.inc_events_dispatched(),
+                        Delivery::SentDroppingOldest => {
+                            self.gateway_metrics.inc_events_dispatched();
+                            self.record_dropped();
+                        }
+                        Delivery::Dropped => self.record_dropped(),
+                        Delivery::Filtered => {}
+                    }
+                }
             }
             GatewayAction::Heartbeat => {
                 debug!(shard_id = self.shard_id, "Received Heartbeat request");
@@ -741,7 +1636,35 @@ impl Shard {
     /// # optimization
     /// Accepts `&mut [u8]` to allow in-place SIMD parsing.
     /// This function is synchronous and does NOT hold locks across awaits.
-    fn process_frame(&self, text: &mut [u8]) -> Result<GatewayAction, GatewayError> {
+    /// A raw dispatch to hand out is stored in `raw`.
+    fn process_frame(
+        &self,
+        text: &mut [u8],
+        raw: &mut Option<RawDispatch>,
+    ) -> Result<GatewayAction, GatewayError> {
+        // Hand out raw dispatches and drop filtered ones before parsing the
+        // whole payload
+        let mode = self.config.dispatch_mode;
+        if mode.is_raw() || !self.config.event_types.is_all() {
+            if let Some(header) = peek_dispatch(text) {
+                if mode.is_raw() && self.config.event_types.wants(header.name) {
+                    *raw = Some(self.raw_dispatch(
+                        header.sequence.unwrap_or_default(),
+                        header.name,
+                        GatewayEncoding::Json,
+                        text,
+                    ));
+                }
+                if self.filter_dispatch(header.name) == DispatchFilter::Skip {
+                    if let Some(seq) = header.sequence {
+                        self.sequence.store(seq, Ordering::SeqCst);
+                    }
+                    self.record_dispatch(header.name);
+                    return Ok(GatewayAction::None);
+                }
+            }
+        }
+
         #[cfg(feature = "simd")]
         {
             // Zero-Copy parse whole buffer
@@ -791,13 +1714,21 @@ impl Shard {
                         return Ok(GatewayAction::None);
                     };
 
+                    let filter = self.filter_dispatch(event_name);
+                    self.record_dispatch(event_name);
+                    if filter == DispatchFilter::Skip {
+                        return Ok(GatewayAction::None);
+                    }
                     let event_result = parse_event(event_name, d)?;
 
                     if let Event::Ready(ref ready) = event_result {
                         self.handle_ready(ready);
                     }
 
-                    return Ok(GatewayAction::Dispatch(event_result));
+                    return Ok(GatewayAction::Dispatch(
+                        event_result,
+                        filter.delivered(event_name),
+                    ));
                 }
 
                 OpCode::Heartbeat => return Ok(GatewayAction::Heartbeat),
@@ -809,7 +1740,7 @@ impl Shard {
                 }
 
                 OpCode::HeartbeatAck => {
-                    self.heartbeat.mark_acked();
+                    self.on_heartbeat_ack();
                     let rtt = self.heartbeat.latency().unwrap_or_default();
                     trace!(
                         shard_id = self.shard_id,
@@ -841,6 +1772,11 @@ impl Shard {
                 OpCode::Dispatch => {
                     if let (Some(event_name), Some(data)) = (payload.t.as_deref(), payload.d) {
                         let event_name = event_name.to_string();
+                        let filter = self.filter_dispatch(&event_name);
+                        self.record_dispatch(&event_name);
+                        if filter == DispatchFilter::Skip {
+                            return Ok(GatewayAction::None);
+                        }
                         // For non-simd, we clone data to avoid holding the buffer, effectively similar logic
                         // but here we just process synchronously.
                         let json_string = data.get().to_string();
@@ -852,7 +1788,10 @@ impl Shard {
                         if let Event::Ready(ref ready) = event_result {
                             self.handle_ready(ready);
                         }
-                        return Ok(GatewayAction::Dispatch(event_result));
+                        return Ok(GatewayAction::Dispatch(
+                            event_result,
+                            filter.delivered(&event_name),
+                        ));
                     }
                 }
                 OpCode::Heartbeat => return Ok(GatewayAction::Heartbeat),
@@ -865,7 +1804,7 @@ impl Shard {
                     return Ok(GatewayAction::InvalidSession(resumable));
                 }
                 OpCode::HeartbeatAck => {
-                    self.heartbeat.mark_acked();
+                    self.on_heartbeat_ack();
                 }
                 _ => {}
             }
@@ -874,13 +1813,185 @@ impl Shard {
         Ok(GatewayAction::None)
     }
 
+    /// Process a binary ETF frame and determine the action.
+    fn process_etf_frame(
+        &self,
+        data: &[u8],
+        raw: &mut Option<RawDispatch>,
+    ) -> Result<GatewayAction, GatewayError> {
+        let term = EtfDecoder::decode(data)?;
+        let payload = EtfPayload::from_term(&term)?;
+
+        if let Some(seq) = payload.s {
+            self.sequence.store(seq, Ordering::SeqCst);
+        }
+
+        match payload.op {
+            OpCode::Dispatch => {
+                let Some(event_name) = payload.t else {
+                    return Ok(GatewayAction::None);
+                };
+                if self.config.dispatch_mode.is_raw() && self.config.event_types.wants(&event_name)
+                {
+                    *raw = Some(self.raw_dispatch(
+                        payload.s.unwrap_or_default(),
+                        &event_name,
+                        GatewayEncoding::Etf,
+                        data,
+                    ));
+                }
+                let filter = self.filter_dispatch(&event_name);
+                self.record_dispatch(&event_name);
+                if filter == DispatchFilter::Skip {
+                    return Ok(GatewayAction::None);
+                }
+                let null = EtfTerm::Atom("nil".to_string());
+                let event_result = parse_event_etf(&event_name, payload.d.unwrap_or(&null))?;
+
+                if let Event::Ready(ref ready) = event_result {
+                    self.handle_ready(ready);
+                }
+                Ok(GatewayAction::Dispatch(
+                    event_result,
+                    filter.delivered(&event_name),
+                ))
+            }
+            OpCode::Heartbeat => Ok(GatewayAction::Heartbeat),
+            OpCode::Reconnect => Ok(GatewayAction::Reconnect),
+            OpCode::InvalidSession => {
+                let resumable = payload
+                    .d
+                    .and_then(|d| etf::from_term::<bool>(d).ok())
+                    .unwrap_or(false);
+                Ok(GatewayAction::InvalidSession(resumable))
+            }
+            OpCode::HeartbeatAck => {
+                self.on_heartbeat_ack();
+                Ok(GatewayAction::None)
+            }
+            _ => Ok(GatewayAction::None),
+        }
+    }
+
+    /// Decide how to handle a dispatch named `event_name`.
+    fn filter_dispatch(&self, event_name: &str) -> DispatchFilter {
+        let parsed = if self.config.dispatch_mode.is_parsed() {
+            self.config.event_types
+        } else {
+            EventTypeFlags::empty()
+        };
+        if parsed.wants(event_name) {
+            return DispatchFilter::Deliver;
+        }
+        let pending = match event_name {
+            "GUILD_MEMBERS_CHUNK" => self.member_requests.has_pending(),
+            "VOICE_STATE_UPDATE" | "VOICE_SERVER_UPDATE" => self.voice_requests.has_pending(),
+            _ => false,
+        };
+        if pending {
+            DispatchFilter::Internal
+        } else {
+            DispatchFilter::Skip
+        }
+    }
+
+    /// Copy a dispatch payload as received.
+    fn raw_dispatch(
+        &self,
+        seq: u64,
+        event_name: &str,
+        encoding: GatewayEncoding,
+        bytes: &[u8],
+    ) -> RawDispatch {
+        RawDispatch {
+            shard_id: self.shard_id,
+            seq,
+            event_name: event_name.to_owned(),
+            encoding,
+            bytes: bytes.to_vec(),
+        }
+    }
+
+    /// Hand out a raw dispatch, applying the overflow policy.
+    async fn send_raw(&self, raw: RawDispatch) {
+        if let Some((router, generation)) = &self.raw_route {
+            if !router.is_active(*generation) {
+                return;
+            }
+        }
+        let kind = EventTypeFlags::from_event_name(&raw.event_name);
+        // The shard or its cluster holds a receiver, so this cannot fail
+        if let Ok(Delivery::SentDroppingOldest | Delivery::Dropped) =
+            self.raw_tx.send(raw, kind).await
+        {
+            self.record_dropped();
+        }
+    }
+
+    /// Write a received frame to the recorder, if any.
+    fn record_frame(&self, encoding: GatewayEncoding, frame: &[u8]) {
+        if let Some(recorder) = &self.config.recorder {
+            if let Err(e) = recorder.record(self.shard_id, encoding, frame) {
+                warn!(shard_id = self.shard_id, error = %e, "Failed to record frame");
+            }
+        }
+    }
+
+    /// Count an event dropped because the event channel was full.
+    fn record_dropped(&self) {
+        self.metrics.events_dropped.fetch_add(1, Ordering::Relaxed);
+        self.gateway_metrics.inc_events_dropped();
+    }
+
+    /// Count a received dispatch.
+    fn record_dispatch(&self, event_name: &str) {
+        self.metrics.inc_events();
+        self.metrics.inc_event_type(event_name);
+        self.gateway_metrics.inc_events_received();
+    }
+
+    /// Count a received message, before and after decompression.
+    fn record_bytes(&self, received: usize, decompressed: usize) {
+        let (received, decompressed) = (received as u64, decompressed as u64);
+        self.metrics
+            .bytes_received
+            .fetch_add(received, Ordering::Relaxed);
+        self.metrics
+            .bytes_decompressed
+            .fetch_add(decompressed, Ordering::Relaxed);
+        self.gateway_metrics.add_bytes_received(received);
+        self.gateway_metrics.add_bytes_decompressed(decompressed);
+    }
+
+    /// Handle a Heartbeat ACK.
+    fn on_heartbeat_ack(&self) {
+        self.heartbeat.mark_acked();
+        if let Some(latency) = self.heartbeat.latency() {
+            self.metrics.record_heartbeat_latency(latency);
+        }
+        self.metrics
+            .heartbeats_acked
+            .fetch_add(1, Ordering::Relaxed);
+        self.gateway_metrics.inc_heartbeats_acked();
+    }
+
     /// Handle the Ready event to store session data.
     fn handle_ready(&self, ready: &ReadyEventData) {
         *self.session.write() = Some(SessionData {
             session_id: ready.session_id.clone(),
             resume_url: ready.resume_gateway_url.clone(),
         });
-        *self.state.write() = ShardState::Connected;
+        *self.user_id.write() = Some(ready.user.id);
+        // Chunks requested in an earlier session will not arrive
+        self.member_requests.clear();
+        self.metrics
+            .reset_guilds(ready.guilds.iter().map(|guild| guild.id));
+        self.metrics.mark_connected();
+        self.set_state(ShardState::Connected);
+        self.emit(ShardEvent::Identified {
+            shard_id: self.shard_id,
+            session_id: ready.session_id.clone(),
+        });
 
         info!(
             shard_id = self.shard_id,
@@ -903,6 +2014,127 @@ mod tests {
         assert!(config.intents.contains(Intents::GUILDS));
     }
 
+    #[test]
+    fn test_lifecycle_events() {
+        let (cluster_tx, mut cluster_rx) = broadcast::channel(16);
+        let shard = Shard::new(3, 4, ShardConfig::new("token", Intents::GUILDS))
+            .with_cluster_events(cluster_tx);
+        let mut rx = shard.lifecycle_events();
+
+        shard.set_state(ShardState::Connecting);
+        shard.set_state(ShardState::Connecting);
+        shard.stop(ShardStopReason::ReconnectsExhausted { attempts: 10 });
+
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            ShardEvent::StateChanged {
+                shard_id: 3,
+                from: ShardState::Disconnected,
+                to: ShardState::Connecting,
+            }
+        );
+        assert!(matches!(
+            rx.try_recv().unwrap(),
+            ShardEvent::StateChanged {
+                to: ShardState::Disconnected,
+                ..
+            }
+        ));
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            ShardEvent::Stopped {
+                shard_id: 3,
+                reason: ShardStopReason::ReconnectsExhausted { attempts: 10 },
+            }
+        );
+        assert!(rx.try_recv().is_err());
+
+        assert!(matches!(
+            cluster_rx.try_recv().unwrap(),
+            ClusterEvent::Shard(ShardEvent::StateChanged { shard_id: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_filtered_dispatch() {
+        let config = ShardConfig::new("token", Intents::GUILDS)
+            .with_event_types(EventTypeFlags::MESSAGE_DELETE);
+        let shard = Shard::new(0, 1, config);
+
+        let mut typing =
+            br#"{"op":0,"s":5,"t":"TYPING_START","d":{"channel_id":"1","user_id":"2","timestamp":3}}"#
+                .to_vec();
+        assert!(matches!(
+            shard.process_frame(&mut typing, &mut None).unwrap(),
+            GatewayAction::None
+        ));
+        assert_eq!(shard.sequence(), 5);
+        assert_eq!(
+            shard.metrics().events_by_type().get("TYPING_START"),
+            Some(&1)
+        );
+
+        let mut delete =
+            br#"{"op":0,"s":6,"t":"MESSAGE_DELETE","d":{"id":"1","channel_id":"2"}}"#.to_vec();
+        assert!(matches!(
+            shard.process_frame(&mut delete, &mut None).unwrap(),
+            GatewayAction::Dispatch(Event::MessageDelete(_), Some(_))
+        ));
+        assert_eq!(shard.sequence(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_raw_dispatch() {
+        let config =
+            ShardConfig::new("token", Intents::GUILDS).with_dispatch_mode(DispatchMode::Raw);
+        let shard = Shard::new(1, 2, config);
+        let raw = shard.raw_dispatches();
+
+        let payload = br#"{"op":0,"s":9,"t":"MESSAGE_DELETE","d":{"id":"1","channel_id":"2"}}"#;
+        let mut dispatch = None;
+        assert!(matches!(
+            shard
+                .process_frame(&mut payload.to_vec(), &mut dispatch)
+                .unwrap(),
+            GatewayAction::None
+        ));
+        assert_eq!(shard.sequence(), 9);
+        shard.send_raw(dispatch.unwrap()).await;
+
+        let dispatch = raw.try_recv().unwrap();
+        assert_eq!(dispatch.shard_id, 1);
+        assert_eq!(dispatch.seq, 9);
+        assert_eq!(dispatch.event_name, "MESSAGE_DELETE");
+        assert_eq!(dispatch.bytes, payload);
+        assert!(matches!(
+            Event::from_raw(&dispatch).unwrap(),
+            Event::MessageDelete(_)
+        ));
+    }
+
+    #[test]
+    fn test_restore_session() {
+        let saved = ShardSession {
+            shard_id: 1,
+            total_shards: 2,
+            session_id: "abc".to_string(),
+            resume_url: "wss://resume.discord.gg".to_string(),
+            sequence: 42,
+        };
+        let json = serde_json::to_string(&saved).unwrap();
+        let saved: ShardSession = serde_json::from_str(&json).unwrap();
+
+        let config = ShardConfig::new("token", Intents::GUILDS).with_session(saved.clone());
+        let shard = Shard::new(1, 2, config.clone());
+        assert_eq!(shard.sequence(), 42);
+        assert_eq!(shard.session(), Some(saved));
+
+        // A session of another shard layout is not resumed
+        let shard = Shard::new(1, 4, config);
+        assert_eq!(shard.sequence(), 0);
+        assert!(shard.session().is_none());
+    }
+
     #[test]
     fn test_shard_creation() {
         let config = ShardConfig::new("test_token", Intents::default());
@@ -921,5 +2153,58 @@ mod tests {
         let url = shard.build_gateway_url().expect("Failed to build URL");
         assert!(url.as_str().contains("v=10"));
         assert!(url.as_str().contains("encoding=json"));
+
+        let config =
+            ShardConfig::new("test", Intents::default()).with_encoding(GatewayEncoding::Etf);
+        let shard = Shard::new(0, 1, config);
+
+        let url = shard.build_gateway_url().expect("Failed to build URL");
+        assert!(url.as_str().contains("encoding=etf"));
+        assert!(!url.as_str().contains("compress="));
+
+        let config = ShardConfig::new("test", Intents::default())
+            .with_compression(TransportCompression::ZlibStream);
+        let shard = Shard::new(0, 1, config);
+
+        let url = shard.build_gateway_url().expect("Failed to build URL");
+        assert!(url.as_str().contains("compress=zlib-stream"));
+
+        #[allow(deprecated)]
+        let config = ShardConfig {
+            compress: true,
+            ..ShardConfig::new("test", Intents::default())
+        };
+        let shard = Shard::new(0, 1, config);
+
+        let url = shard.build_gateway_url().expect("Failed to build URL");
+        assert!(url.as_str().contains("compress=zlib-stream"));
+    }
+
+    #[test]
+    fn test_process_etf_dispatch() {
+        let config =
+            ShardConfig::new("test", Intents::default()).with_encoding(GatewayEncoding::Etf);
+        let shard = Shard::new(0, 1, config);
+
+        let payload = GatewayPayload {
+            op: OpCode::Dispatch,
+            d: Some(serde_json::json!({
+                "id": 1_234_567_890_123_456_789u64,
+                "channel_id": 456,
+                "guild_id": 789,
+            })),
+            s: Some(7),
+            t: Some("MESSAGE_DELETE".to_string()),
+        };
+        let frame = EtfEncoder::encode(&payload).unwrap();
+
+        let GatewayAction::Dispatch(Event::MessageDelete(event), Some(_)) =
+            shard.process_etf_frame(&frame, &mut None).unwrap()
+        else {
+            panic!("expected MESSAGE_DELETE dispatch");
+        };
+        assert_eq!(event.id.get(), 1_234_567_890_123_456_789);
+        assert_eq!(event.channel_id.get(), 456);
+        assert_eq!(shard.sequence(), 7);
     }
 }
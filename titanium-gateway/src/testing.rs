@@ -0,0 +1,617 @@
+//! Mock gateway server for integration tests.
+//!
+//! [`MockGateway`] is an in-process WebSocket server speaking the gateway
+//! protocol, so reconnect logic can be tested without Discord. Point a
+//! [`ShardConfig`](crate::ShardConfig) or
+//! [`ClusterConfig`](crate::ClusterConfig) at [`MockGateway::url`] and
+//! script each connection through the [`MockConnection`] returned by
+//! [`MockGateway::accept`].
+//!
+//! Every connection starts with Hello and acknowledges heartbeats by
+//! itself. Payloads are sent as JSON, compressed with `zlib-stream` if the
+//! shard asked for it.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use titanium_gateway::testing::MockGateway;
+//! use titanium_gateway::{Shard, ShardConfig};
+//! use titanium_model::Intents;
+//!
+//! # async fn run() -> Result<(), titanium_gateway::GatewayError> {
+//! let gateway = MockGateway::bind().await?;
+//! let config = ShardConfig::new("token", Intents::GUILDS).with_gateway_url(gateway.url());
+//! let shard = std::sync::Arc::new(Shard::new(0, 1, config));
+//! let (tx, rx) = flume::unbounded();
+//! tokio::spawn({
+//!     let shard = shard.clone();
+//!     async move { shard.run(tx).await }
+//! });
+//!
+//! let mut connection = gateway.accept().await?;
+//! let identify = connection.expect_identify().await?;
+//! assert_eq!(identify["token"], "token");
+//! connection.send_ready("session").await?;
+//!
+//! // Discord asks the shard to reconnect, which then resumes
+//! connection.reconnect().await?;
+//! let mut connection = gateway.accept().await?;
+//! let resume = connection.expect_resume().await?;
+//! assert_eq!(resume["session_id"], "session");
+//! connection.send_resumed().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::GatewayError;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::handshake::server::{
+    Callback, ErrorResponse, Request, Response,
+};
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::WebSocketStream;
+
+/// How long to wait for a connection or payload before failing.
+const RECV_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// In-process gateway server.
+#[derive(Debug)]
+pub struct MockGateway {
+    listener: TcpListener,
+    url: String,
+    heartbeat_interval: Duration,
+}
+
+impl MockGateway {
+    /// Listen on a free local port.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::Io` if binding fails.
+    pub async fn bind() -> Result<Self, GatewayError> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("ws://{}", listener.local_addr()?);
+        Ok(Self {
+            listener,
+            url,
+            heartbeat_interval: Duration::from_millis(41_250),
+        })
+    }
+
+    /// Set the heartbeat interval sent with Hello.
+    #[must_use]
+    pub fn with_heartbeat_interval(mut self, interval: Duration) -> Self {
+        self.heartbeat_interval = interval;
+        self
+    }
+
+    /// URL to connect shards to.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Accept the next connection and send Hello.
+    ///
+    /// # Errors
+    /// Returns an error if no shard connects within 10 seconds or the
+    /// WebSocket handshake fails.
+    pub async fn accept(&self) -> Result<MockConnection, GatewayError> {
+        let (stream, _) = timeout(RECV_TIMEOUT, self.listener.accept())
+            .await
+            .map_err(|_| Self::timed_out("connection"))??;
+
+        let mut query = HashMap::new();
+        let ws = tokio_tungstenite::accept_hdr_async(stream, QueryCapture(&mut query)).await?;
+
+        let compress = query.get("compress").map(String::as_str) == Some("zlib-stream");
+        let (outgoing, outgoing_rx) = flume::unbounded();
+        let (received_tx, received) = flume::unbounded();
+        let heartbeat_ack = Arc::new(AtomicBool::new(true));
+
+        let connection = MockConnection {
+            query,
+            url: self.url.clone(),
+            outgoing,
+            received,
+            heartbeat_ack: heartbeat_ack.clone(),
+            sequence: 0,
+        };
+        connection
+            .send(json!({
+                "op": 10,
+                "d": { "heartbeat_interval": self.heartbeat_interval.as_millis() },
+                "s": null,
+                "t": null,
+            }))
+            .await?;
+
+        tokio::spawn(run_connection(
+            ws,
+            compress,
+            outgoing_rx,
+            received_tx,
+            heartbeat_ack,
+        ));
+        Ok(connection)
+    }
+
+    fn timed_out(what: &str) -> GatewayError {
+        GatewayError::Closed {
+            code: 0,
+            reason: format!("Timed out waiting for a {what}"),
+        }
+    }
+}
+
+/// Handshake callback keeping the query parameters of the request.
+struct QueryCapture<'a>(&'a mut HashMap<String, String>);
+
+impl Callback for QueryCapture<'_> {
+    fn on_request(self, request: &Request, response: Response) -> Result<Response, ErrorResponse> {
+        if let Some(query) = request.uri().query() {
+            *self.0 = url::form_urlencoded::parse(query.as_bytes())
+                .into_owned()
+                .collect();
+        }
+        Ok(response)
+    }
+}
+
+/// Command for a connection task.
+#[derive(Debug)]
+enum Outgoing {
+    Payload(Value),
+    Close(u16, String),
+}
+
+/// One shard connection to a [`MockGateway`].
+///
+/// Dropping it closes the connection without a close frame.
+#[derive(Debug)]
+pub struct MockConnection {
+    query: HashMap<String, String>,
+    url: String,
+    outgoing: flume::Sender<Outgoing>,
+    received: flume::Receiver<Value>,
+    heartbeat_ack: Arc<AtomicBool>,
+    sequence: u64,
+}
+
+impl MockConnection {
+    /// Query parameter of the connection URL, e.g. `encoding`.
+    pub fn query(&self, key: &str) -> Option<&str> {
+        self.query.get(key).map(String::as_str)
+    }
+
+    /// Whether heartbeats are acknowledged, `true` by default.
+    ///
+    /// Without acknowledgements the shard times out and reconnects. While
+    /// disabled, heartbeats are returned by [`recv`](Self::recv).
+    pub fn set_heartbeat_ack(&self, enabled: bool) {
+        self.heartbeat_ack.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Receive the next payload sent by the shard.
+    ///
+    /// # Errors
+    /// Returns an error if the connection closed or nothing arrives within
+    /// 10 seconds.
+    pub async fn recv(&self) -> Result<Value, GatewayError> {
+        match timeout(RECV_TIMEOUT, self.received.recv_async()).await {
+            Ok(Ok(payload)) => Ok(payload),
+            Ok(Err(_)) => Err(GatewayError::Closed {
+                code: 0,
+                reason: "Mock connection closed".to_string(),
+            }),
+            Err(_) => Err(MockGateway::timed_out("payload")),
+        }
+    }
+
+    /// Receive the next payload and check its opcode, returning its `d`.
+    ///
+    /// # Errors
+    /// Returns an error if no payload arrives or it has another opcode.
+    pub async fn expect(&self, op: u8) -> Result<Value, GatewayError> {
+        let mut payload = self.recv().await?;
+        if payload["op"] != op {
+            return Err(GatewayError::Closed {
+                code: 0,
+                reason: format!("Expected opcode {op}, received {payload}"),
+            });
+        }
+        Ok(payload["d"].take())
+    }
+
+    /// Receive an Identify, returning its data.
+    ///
+    /// # Errors
+    /// Returns an error if the next payload is not an Identify.
+    pub async fn expect_identify(&self) -> Result<Value, GatewayError> {
+        self.expect(2).await
+    }
+
+    /// Receive a Resume, returning its data.
+    ///
+    /// # Errors
+    /// Returns an error if the next payload is not a Resume.
+    pub async fn expect_resume(&self) -> Result<Value, GatewayError> {
+        self.expect(6).await
+    }
+
+    /// Send a payload as is.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::ChannelSend` if the connection closed.
+    pub async fn send(&self, payload: Value) -> Result<(), GatewayError> {
+        self.outgoing
+            .send_async(Outgoing::Payload(payload))
+            .await
+            .map_err(GatewayError::from)
+    }
+
+    /// Send a dispatch with the next sequence number.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::ChannelSend` if the connection closed.
+    pub async fn dispatch(&mut self, event_name: &str, data: Value) -> Result<(), GatewayError> {
+        self.sequence += 1;
+        self.send(json!({
+            "op": 0,
+            "d": data,
+            "s": self.sequence,
+            "t": event_name,
+        }))
+        .await
+    }
+
+    /// Dispatch Ready for `session_id`, resuming on this gateway.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::ChannelSend` if the connection closed.
+    pub async fn send_ready(&mut self, session_id: &str) -> Result<(), GatewayError> {
+        let data = json!({
+            "v": crate::GATEWAY_VERSION,
+            "user": { "id": "1", "username": "mock", "discriminator": "0", "bot": true },
+            "guilds": [],
+            "session_id": session_id,
+            "resume_gateway_url": self.url,
+            "application": { "id": "1", "flags": 0 },
+        });
+        self.dispatch("READY", data).await
+    }
+
+    /// Dispatch Resumed.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::ChannelSend` if the connection closed.
+    pub async fn send_resumed(&mut self) -> Result<(), GatewayError> {
+        self.dispatch("RESUMED", Value::Null).await
+    }
+
+    /// Continue the sequence of a resumed session at `sequence`.
+    pub fn set_sequence(&mut self, sequence: u64) {
+        self.sequence = sequence;
+    }
+
+    /// Ask the shard to reconnect (opcode 7).
+    ///
+    /// # Errors
+    /// Returns `GatewayError::ChannelSend` if the connection closed.
+    pub async fn reconnect(&self) -> Result<(), GatewayError> {
+        self.send(json!({ "op": 7, "d": null, "s": null, "t": null }))
+            .await
+    }
+
+    /// Invalidate the session (opcode 9).
+    ///
+    /// # Errors
+    /// Returns `GatewayError::ChannelSend` if the connection closed.
+    pub async fn invalid_session(&self, resumable: bool) -> Result<(), GatewayError> {
+        self.send(json!({ "op": 9, "d": resumable, "s": null, "t": null }))
+            .await
+    }
+
+    /// Close the connection with a close code, e.g. 4000 to 4014.
+    ///
+    /// # Errors
+    /// Returns `GatewayError::ChannelSend` if the connection already closed.
+    pub async fn close(&self, code: u16, reason: &str) -> Result<(), GatewayError> {
+        self.outgoing
+            .send_async(Outgoing::Close(code, reason.to_string()))
+            .await
+            .map_err(GatewayError::from)
+    }
+}
+
+/// Pump payloads between a connection and its [`MockConnection`].
+async fn run_connection(
+    ws: WebSocketStream<TcpStream>,
+    compress: bool,
+    outgoing: flume::Receiver<Outgoing>,
+    received: flume::Sender<Value>,
+    heartbeat_ack: Arc<AtomicBool>,
+) {
+    let (mut sink, mut stream) = ws.split();
+    let mut encoder = compress.then(|| ZlibEncoder::new(Vec::new(), Compression::default()));
+
+    loop {
+        let payload = tokio::select! {
+            message = stream.next() => match message {
+                Some(Ok(WsMessage::Text(text))) => {
+                    let Ok(payload) = serde_json::from_str::<Value>(text.as_str()) else {
+                        continue;
+                    };
+                    if payload["op"] == 1 && heartbeat_ack.load(Ordering::SeqCst) {
+                        json!({ "op": 11, "d": null, "s": null, "t": null })
+                    } else {
+                        if received.send(payload).is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+                }
+                Some(Ok(WsMessage::Close(_)) | Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+            command = outgoing.recv_async() => match command {
+                Ok(Outgoing::Payload(payload)) => payload,
+                Ok(Outgoing::Close(code, reason)) => {
+                    let _ = sink
+                        .send(WsMessage::Close(Some(CloseFrame {
+                            code: code.into(),
+                            reason: reason.into(),
+                        })))
+                        .await;
+                    break;
+                }
+                Err(_) => break,
+            },
+        };
+
+        let text = payload.to_string();
+        let message = match encoder.as_mut() {
+            Some(encoder) => {
+                // A sync flush ends every message with the zlib-stream suffix
+                if encoder
+                    .write_all(text.as_bytes())
+                    .and_then(|()| encoder.flush())
+                    .is_err()
+                {
+                    break;
+                }
+                WsMessage::Binary(std::mem::take(encoder.get_mut()).into())
+            }
+            None => WsMessage::Text(text.into()),
+        };
+        if sink.send(message).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::TransportCompression;
+    use crate::event::Event;
+    use crate::shard::{Shard, ShardConfig, ShardState};
+    use titanium_model::Intents;
+    use tokio::task::JoinHandle;
+
+    type Running = (
+        Arc<Shard>,
+        flume::Receiver<Event<'static>>,
+        JoinHandle<Result<(), GatewayError>>,
+    );
+
+    /// Run a shard against `gateway` and bring its session `abc` up.
+    async fn start_ready(gateway: &MockGateway) -> (Running, MockConnection) {
+        let mut config =
+            ShardConfig::new("secret", Intents::GUILDS).with_gateway_url(gateway.url());
+        config.reconnect_base_delay_ms = 10;
+        let shard = Arc::new(Shard::new(0, 1, config));
+        let (tx, rx) = flume::unbounded();
+        let handle = tokio::spawn({
+            let shard = shard.clone();
+            async move { shard.run(tx).await }
+        });
+
+        let mut connection = gateway.accept().await.unwrap();
+        connection.expect_identify().await.unwrap();
+        connection.send_ready("abc").await.unwrap();
+        assert!(matches!(rx.recv_async().await.unwrap(), Event::Ready(_)));
+        ((shard, rx, handle), connection)
+    }
+
+    async fn stop((shard, _, handle): Running, connection: &MockConnection) {
+        shard.shutdown();
+        let _ = connection.close(4000, "").await;
+        let _ = handle.await;
+    }
+
+    /// Accept the next connection, expect a Resume of session `abc` and
+    /// confirm it.
+    async fn expect_resumed(
+        gateway: &MockGateway,
+        rx: &flume::Receiver<Event<'static>>,
+    ) -> MockConnection {
+        let mut connection = gateway.accept().await.unwrap();
+        let resume = connection.expect_resume().await.unwrap();
+        assert_eq!(resume["session_id"], "abc");
+        connection.set_sequence(1);
+        connection.send_resumed().await.unwrap();
+        assert!(matches!(rx.recv_async().await.unwrap(), Event::Resumed));
+        connection
+    }
+
+    #[tokio::test]
+    async fn test_identify_reconnect_resume() {
+        let gateway = MockGateway::bind().await.unwrap();
+        let mut config = ShardConfig::new("secret", Intents::GUILDS)
+            .with_gateway_url(gateway.url())
+            .with_compression(TransportCompression::ZlibStream);
+        config.reconnect_base_delay_ms = 10;
+        let shard = Arc::new(Shard::new(0, 1, config));
+        let (tx, rx) = flume::unbounded();
+        let handle = tokio::spawn({
+            let shard = shard.clone();
+            async move { shard.run(tx).await }
+        });
+
+        let mut connection = gateway.accept().await.unwrap();
+        assert_eq!(connection.query("compress"), Some("zlib-stream"));
+        let identify = connection.expect_identify().await.unwrap();
+        assert_eq!(identify["token"], "secret");
+        assert_eq!(identify["shard"], json!([0, 1]));
+
+        connection.send_ready("abc").await.unwrap();
+        assert!(matches!(rx.recv_async().await.unwrap(), Event::Ready(_)));
+        let data = json!({ "id": "5", "channel_id": "6" });
+        connection.dispatch("MESSAGE_DELETE", data).await.unwrap();
+        assert!(matches!(
+            rx.recv_async().await.unwrap(),
+            Event::MessageDelete(_)
+        ));
+
+        connection.reconnect().await.unwrap();
+        let mut connection = gateway.accept().await.unwrap();
+        let resume = connection.expect_resume().await.unwrap();
+        assert_eq!(resume["session_id"], "abc");
+        assert_eq!(resume["seq"], 2);
+        connection.set_sequence(2);
+        connection.send_resumed().await.unwrap();
+        assert!(matches!(rx.recv_async().await.unwrap(), Event::Resumed));
+
+        shard.shutdown();
+        connection.close(4000, "").await.unwrap();
+        let _ = handle.await;
+    }
+
+    #[tokio::test]
+    async fn test_command_limit_across_reconnect() {
+        let gateway = MockGateway::bind().await.unwrap();
+        let mut config =
+            ShardConfig::new("secret", Intents::GUILDS).with_gateway_url(gateway.url());
+        config.reconnect_base_delay_ms = 10;
+        let shard = Arc::new(Shard::new(0, 1, config));
+        let (tx, rx) = flume::unbounded();
+        let handle = tokio::spawn({
+            let shard = shard.clone();
+            async move { shard.run(tx).await }
+        });
+
+        let mut connection = gateway.accept().await.unwrap();
+        connection.expect_identify().await.unwrap();
+        connection.send_ready("abc").await.unwrap();
+        assert!(matches!(rx.recv_async().await.unwrap(), Event::Ready(_)));
+
+        // Queue a full bucket and more while the shard reconnects
+        connection.reconnect().await.unwrap();
+        while shard.state() == ShardState::Connected {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        let capacity = shard.available_commands();
+        let command = json!({ "op": 3, "d": null });
+        for _ in 0..capacity {
+            shard.send_payload(&command).unwrap();
+        }
+        assert!(matches!(
+            shard.send_payload(&command),
+            Err(GatewayError::RateLimited { .. })
+        ));
+        let waiting = tokio::spawn({
+            let shard = shard.clone();
+            let command = command.clone();
+            async move {
+                for _ in 0..10 {
+                    shard.send_payload_async(&command).await.unwrap();
+                }
+            }
+        });
+
+        let connection = gateway.accept().await.unwrap();
+        connection.expect_resume().await.unwrap();
+        let mut sent = 0;
+        while let Ok(payload) = timeout(Duration::from_millis(500), connection.recv()).await {
+            assert_eq!(payload.unwrap()["op"], 3);
+            sent += 1;
+        }
+        assert_eq!(sent, capacity);
+
+        waiting.abort();
+        shard.shutdown();
+        connection.close(4000, "").await.unwrap();
+        let _ = handle.await;
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_timeout_resumes() {
+        let gateway = MockGateway::bind()
+            .await
+            .unwrap()
+            .with_heartbeat_interval(Duration::from_millis(50));
+        let ((shard, rx, handle), connection) = start_ready(&gateway).await;
+
+        // The next heartbeat goes unanswered, the one after times out
+        connection.set_heartbeat_ack(false);
+        connection.expect(1).await.unwrap();
+        let connection = expect_resumed(&gateway, &rx).await;
+        stop((shard, rx, handle), &connection).await;
+    }
+
+    #[tokio::test]
+    async fn test_invalid_session_resumable() {
+        let gateway = MockGateway::bind().await.unwrap();
+        let ((shard, rx, handle), connection) = start_ready(&gateway).await;
+
+        connection.invalid_session(true).await.unwrap();
+        let connection = expect_resumed(&gateway, &rx).await;
+        stop((shard, rx, handle), &connection).await;
+    }
+
+    #[tokio::test]
+    async fn test_invalid_session_identifies() {
+        let gateway = MockGateway::bind().await.unwrap();
+        let ((shard, rx, handle), connection) = start_ready(&gateway).await;
+
+        connection.invalid_session(false).await.unwrap();
+        let mut connection = gateway.accept().await.unwrap();
+        connection.expect_identify().await.unwrap();
+        assert!(shard.session().is_none());
+        connection.send_ready("def").await.unwrap();
+        assert!(matches!(rx.recv_async().await.unwrap(), Event::Ready(_)));
+        stop((shard, rx, handle), &connection).await;
+    }
+
+    #[tokio::test]
+    async fn test_close_codes() {
+        let gateway = MockGateway::bind().await.unwrap();
+        let ((shard, rx, handle), connection) = start_ready(&gateway).await;
+
+        // 4000 is an unknown error, the session survives it
+        connection.close(4000, "Unknown error").await.unwrap();
+        let connection = expect_resumed(&gateway, &rx).await;
+
+        // 4004 is fatal, the shard stops instead of reconnecting
+        connection
+            .close(4004, "Authentication failed")
+            .await
+            .unwrap();
+        let result = timeout(RECV_TIMEOUT, handle).await.unwrap().unwrap();
+        assert!(matches!(
+            result,
+            Err(GatewayError::Closed { code: 4004, .. })
+        ));
+        assert_eq!(shard.state(), ShardState::Disconnected);
+    }
+}
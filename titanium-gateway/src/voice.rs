@@ -0,0 +1,224 @@
+//! Voice State Update (op 4) response pairing.
+//!
+//! After a voice state update, Discord sends the bot's own
+//! `VOICE_STATE_UPDATE` (carrying the voice session ID) and a
+//! `VOICE_SERVER_UPDATE` (carrying the voice endpoint and token), in either
+//! order. Both are needed to open a voice connection.
+
+use crate::error::GatewayError;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Weak};
+use std::task::{Context, Poll};
+use titanium_model::voice::{VoiceServerUpdateEvent, VoiceStateUpdateEvent};
+use titanium_model::Snowflake;
+use tokio::sync::oneshot;
+
+/// A pairing in progress for one guild.
+#[derive(Debug)]
+struct PendingVoice {
+    state: Option<Arc<VoiceStateUpdateEvent<'static>>>,
+    server: Option<VoiceServerUpdateEvent>,
+    tx: oneshot::Sender<VoiceConnectionInfo>,
+}
+
+/// Pairings in progress, keyed by guild.
+type Pending = Mutex<HashMap<Snowflake, PendingVoice>>;
+
+/// In-flight voice state updates of a shard, keyed by guild.
+#[derive(Debug, Default)]
+pub(crate) struct VoiceRequests {
+    pending: Arc<Pending>,
+}
+
+impl VoiceRequests {
+    /// Start waiting for the events of `guild_id`.
+    ///
+    /// Replaces any earlier wait for the same guild, whose future then fails.
+    pub(crate) fn register(&self, guild_id: Snowflake) -> VoiceConnectionFuture {
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().insert(
+            guild_id,
+            PendingVoice {
+                state: None,
+                server: None,
+                tx,
+            },
+        );
+        VoiceConnectionFuture {
+            rx,
+            guild_id,
+            pending: Arc::downgrade(&self.pending),
+        }
+    }
+
+    /// Stop waiting for the events of `guild_id`.
+    pub(crate) fn cancel(&self, guild_id: Snowflake) {
+        self.pending.lock().remove(&guild_id);
+    }
+
+    /// Whether any guild still waits for its voice events.
+    pub(crate) fn has_pending(&self) -> bool {
+        !self.pending.lock().is_empty()
+    }
+
+    /// Record a voice state update, if it is the bot's own.
+    pub(crate) fn state_update(
+        &self,
+        state: &Arc<VoiceStateUpdateEvent<'static>>,
+        current_user: Option<Snowflake>,
+    ) {
+        let Some(guild_id) = state.guild_id else {
+            return;
+        };
+        if current_user != Some(state.user_id) {
+            return;
+        }
+
+        let mut pending = self.pending.lock();
+        if let Some(entry) = pending.get_mut(&guild_id) {
+            entry.state = Some(state.clone());
+            Self::try_complete(&mut pending, guild_id);
+        }
+    }
+
+    /// Record a voice server update.
+    pub(crate) fn server_update(&self, server: &VoiceServerUpdateEvent) {
+        let mut pending = self.pending.lock();
+        if let Some(entry) = pending.get_mut(&server.guild_id) {
+            entry.server = Some(server.clone());
+            Self::try_complete(&mut pending, server.guild_id);
+        }
+    }
+
+    fn try_complete(pending: &mut HashMap<Snowflake, PendingVoice>, guild_id: Snowflake) {
+        let ready = pending
+            .get(&guild_id)
+            .is_some_and(|entry| entry.state.is_some() && entry.server.is_some());
+        if !ready {
+            return;
+        }
+
+        if let Some(PendingVoice {
+            state: Some(state),
+            server: Some(server),
+            tx,
+        }) = pending.remove(&guild_id)
+        {
+            let _ = tx.send(VoiceConnectionInfo { state, server });
+        }
+    }
+}
+
+/// The events needed to open a voice connection.
+///
+/// Pass them to `titanium_voice::VoiceConfig::from_events`.
+#[derive(Debug, Clone)]
+pub struct VoiceConnectionInfo {
+    /// The bot's own voice state, carrying the voice session ID.
+    pub state: Arc<VoiceStateUpdateEvent<'static>>,
+
+    /// The voice server, carrying the endpoint and token.
+    pub server: VoiceServerUpdateEvent,
+}
+
+/// Future resolving once both voice events for a guild arrived.
+///
+/// Fails if another voice state update for the same guild replaces it. If
+/// Discord never answers (e.g. missing permissions), it never resolves, so
+/// wrap it in a timeout. Dropping it stops waiting for the guild's events.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless awaited"]
+pub struct VoiceConnectionFuture {
+    rx: oneshot::Receiver<VoiceConnectionInfo>,
+    guild_id: Snowflake,
+    pending: Weak<Pending>,
+}
+
+impl Future for VoiceConnectionFuture {
+    type Output = Result<VoiceConnectionInfo, GatewayError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.rx).poll(cx).map(|result| {
+            result.map_err(|_| GatewayError::Closed {
+                code: 0,
+                reason: "Voice state update was superseded".to_string(),
+            })
+        })
+    }
+}
+
+impl Drop for VoiceConnectionFuture {
+    fn drop(&mut self) {
+        let Some(pending) = self.pending.upgrade() else {
+            return;
+        };
+        self.rx.close();
+        // Keep the entry if a newer update for the guild replaced ours
+        let mut pending = pending.lock();
+        if pending
+            .get(&self.guild_id)
+            .is_some_and(|entry| entry.tx.is_closed())
+        {
+            pending.remove(&self.guild_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pairs_state_and_server() {
+        let requests = VoiceRequests::default();
+        let guild_id = Snowflake::new(1);
+        let bot_id = Snowflake::new(10);
+        let future = requests.register(guild_id);
+
+        let server: VoiceServerUpdateEvent = serde_json::from_value(serde_json::json!({
+            "token": "voice-token",
+            "guild_id": "1",
+            "endpoint": "us-east1.discord.media:443",
+        }))
+        .unwrap();
+        let state = |user_id: &str| -> Arc<VoiceStateUpdateEvent<'static>> {
+            Arc::new(
+                serde_json::from_value(serde_json::json!({
+                    "guild_id": "1",
+                    "channel_id": "2",
+                    "user_id": user_id,
+                    "session_id": "session",
+                    "deaf": false,
+                    "mute": false,
+                    "self_deaf": true,
+                    "self_mute": false,
+                    "self_video": false,
+                    "suppress": false,
+                }))
+                .unwrap(),
+            )
+        };
+
+        requests.server_update(&server);
+        // Another member's state does not complete the pairing
+        requests.state_update(&state("11"), Some(bot_id));
+        assert!(requests.pending.lock().contains_key(&guild_id));
+        requests.state_update(&state("10"), Some(bot_id));
+
+        let info = future.await.unwrap();
+        assert_eq!(info.state.session_id, "session");
+        assert_eq!(info.server.token, "voice-token");
+        assert!(requests.pending.lock().is_empty());
+
+        // A dropped future only cancels its own registration
+        let first = requests.register(guild_id);
+        let second = requests.register(guild_id);
+        drop(first);
+        assert!(requests.has_pending());
+        drop(second);
+        assert!(!requests.has_pending());
+    }
+}
@@ -0,0 +1,121 @@
+use crate::error::HttpError;
+use crate::HttpClient;
+use bytes::Bytes;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Response, StatusCode};
+use titanium_model::cdn::{CDN_BASE, MEDIA_BASE};
+
+/// An asset being downloaded from Discord's CDN.
+///
+/// The body is read incrementally with [`AssetDownload::chunk`], so large
+/// assets can be piped into a re-upload or thumbnailer without buffering them
+/// whole. Use [`AssetDownload::bytes`] to collect everything at once.
+#[derive(Debug)]
+pub struct AssetDownload {
+    response: Response,
+}
+
+impl AssetDownload {
+    /// MIME type reported by the CDN (e.g. `image/png`).
+    #[must_use]
+    pub fn content_type(&self) -> Option<&str> {
+        self.response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+    }
+
+    /// Size of the asset in bytes, if known.
+    #[must_use]
+    pub fn content_length(&self) -> Option<u64> {
+        self.response.content_length()
+    }
+
+    /// Read the next chunk of the body, or `None` once it is complete.
+    pub async fn chunk(&mut self) -> Result<Option<Bytes>, HttpError> {
+        Ok(self.response.chunk().await?)
+    }
+
+    /// Read the rest of the body.
+    pub async fn bytes(self) -> Result<Bytes, HttpError> {
+        Ok(self.response.bytes().await?)
+    }
+}
+
+impl HttpClient {
+    // =========================================================================
+    // CDN
+    // =========================================================================
+
+    /// Start downloading an asset from Discord's CDN.
+    ///
+    /// `url` is usually built with [`titanium_model::CdnUrl`] or one of the
+    /// model `*_url` helpers. Only Discord's CDN and media proxy hosts are
+    /// accepted. CDN downloads skip the API rate limiter and never carry the
+    /// bot token.
+    ///
+    /// ```no_run
+    /// # use titanium_http::HttpClient;
+    /// # use titanium_model::{CdnUrl, ImageFormat, Snowflake};
+    /// # async fn example(http: &HttpClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let url = CdnUrl::emoji(Snowflake::new(41771983429993937), false)
+    ///     .with_format(ImageFormat::WebP)?
+    ///     .with_size(128)?;
+    ///
+    /// let mut download = http.download_asset(url).await?;
+    /// while let Some(chunk) = download.chunk().await? {
+    ///     println!("received {} bytes", chunk.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn download_asset(&self, url: impl Into<String>) -> Result<AssetDownload, HttpError> {
+        let url = url.into();
+        if !is_cdn_url(&url) {
+            return Err(HttpError::ClientError(format!(
+                "Not a Discord CDN URL: {}",
+                url
+            )));
+        }
+
+        let response = self.client.get(&url).send().await?;
+        match response.status() {
+            status if status.is_success() => Ok(AssetDownload { response }),
+            StatusCode::FORBIDDEN => Err(HttpError::Forbidden),
+            StatusCode::NOT_FOUND => Err(HttpError::NotFound),
+            status if status.is_server_error() => Err(HttpError::ServerError(status.as_u16())),
+            status => Err(HttpError::ClientError(format!(
+                "CDN returned status {}",
+                status
+            ))),
+        }
+    }
+}
+
+/// Whether `url` points at Discord's CDN or media proxy.
+fn is_cdn_url(url: &str) -> bool {
+    [CDN_BASE, MEDIA_BASE].iter().any(|base| {
+        url.strip_prefix(base)
+            .is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_cdn_url() {
+        assert!(is_cdn_url("https://cdn.discordapp.com/emojis/1.png"));
+        assert!(is_cdn_url("https://media.discordapp.net/stickers/1.gif"));
+        assert!(!is_cdn_url("https://cdn.discordapp.com.evil.example/x.png"));
+        assert!(!is_cdn_url("https://discord.com/api/v10/users/@me"));
+    }
+
+    #[tokio::test]
+    async fn test_download_asset_rejects_foreign_host() {
+        let http = HttpClient::new("test_token").unwrap();
+        let result = http.download_asset("https://example.com/image.png").await;
+        assert!(matches!(result, Err(HttpError::ClientError(_))));
+    }
+}
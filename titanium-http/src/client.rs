@@ -8,6 +8,8 @@
 //! - **SIMD-Accelerated JSON**: Uses `simd-json` for fast parsing on supported CPUs
 //! - **Connection Pooling**: Reuses HTTP/2 connections for efficiency
 //! - **Thread-Local Buffers**: Avoids allocations on hot paths
+//! - **Request Coalescing**: Identical concurrent GETs share a single in-flight request
+//! - **Priority Lanes**: Interaction and moderation requests jump ahead of background work
 //!
 //! # Performance
 //!
@@ -31,14 +33,17 @@
 //! ```
 
 use crate::error::{DiscordError, HttpError};
-use crate::ratelimit::RateLimiter;
+use crate::ratelimit::{RateLimiter, RequestPriority};
 use crate::routes::{CurrentApplication, CurrentUser, GatewayBotResponse};
 
+use bytes::Bytes;
+use dashmap::DashMap;
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE, USER_AGENT};
-use reqwest::{Client, Method, Response, StatusCode};
+use reqwest::{Client, Method, Request, Response, StatusCode};
 use serde::de::DeserializeOwned;
 use simd_json::prelude::*;
 use std::sync::Arc;
+use tokio::sync::watch;
 use tracing::{debug, warn};
 
 /// Discord API base URL (v10).
@@ -62,14 +67,33 @@ const USER_AGENT_VALUE: &str = concat!(
 /// # Thread Safety
 ///
 /// `HttpClient` can be cloned and shared across tasks. The inner
-/// `reqwest::Client` uses connection pooling internally.
+/// `reqwest::Client` uses connection pooling internally. Clones share the
+/// same rate limiter and in-flight request table.
+#[derive(Clone)]
 pub struct HttpClient {
     /// Inner reqwest HTTP client with connection pooling.
-    client: Client,
+    pub(crate) client: Client,
     /// Bot token for authentication.
-    token: String,
+    token: Arc<str>,
+    /// `Authorization` header, attached to API requests only (never CDN downloads).
+    auth: HeaderValue,
     /// Rate limiter tracking per-route and global limits.
     rate_limiter: Arc<RateLimiter>,
+    /// Priority used when queueing on a rate limit bucket.
+    priority: RequestPriority,
+    /// In-flight GET requests keyed by full URL, for coalescing.
+    inflight: Arc<DashMap<String, Inflight>>,
+}
+
+/// Raw body (or error) of a completed request, shared with coalesced waiters.
+type InflightReceiver = watch::Receiver<Option<Result<Bytes, HttpError>>>;
+
+/// A GET request in flight, shared with identical requests.
+#[derive(Clone)]
+struct Inflight {
+    /// Priority the leading request queues with.
+    priority: RequestPriority,
+    rx: InflightReceiver,
 }
 
 thread_local! {
@@ -83,12 +107,11 @@ impl HttpClient {
     pub fn new(token: impl Into<String>) -> Result<Self, HttpError> {
         let token = token.into();
 
+        let mut auth = HeaderValue::from_str(&format!("Bot {}", token))
+            .map_err(|_| HttpError::Unauthorized)?;
+        auth.set_sensitive(true);
+
         let mut headers = HeaderMap::new();
-        headers.insert(
-            AUTHORIZATION,
-            HeaderValue::from_str(&format!("Bot {}", token))
-                .map_err(|_| HttpError::Unauthorized)?,
-        );
         headers.insert(USER_AGENT, HeaderValue::from_static(USER_AGENT_VALUE));
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
 
@@ -102,11 +125,39 @@ impl HttpClient {
 
         Ok(Self {
             client,
-            token,
+            token: token.into(),
+            auth,
             rate_limiter: Arc::new(RateLimiter::new()),
+            priority: RequestPriority::default(),
+            inflight: Arc::new(DashMap::new()),
         })
     }
 
+    /// Get a handle that queues its requests with the given priority.
+    ///
+    /// The returned client shares connections, rate limits and in-flight
+    /// requests with `self`.
+    ///
+    /// ```no_run
+    /// # use titanium_http::{HttpClient, RequestPriority};
+    /// # async fn example(http: &HttpClient) -> Result<(), titanium_http::HttpError> {
+    /// let background = http.with_priority(RequestPriority::Background);
+    /// let user = background.get_current_user().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_priority(&self, priority: RequestPriority) -> Self {
+        Self {
+            priority,
+            ..self.clone()
+        }
+    }
+
+    /// Get the priority this client queues its requests with.
+    pub fn priority(&self) -> RequestPriority {
+        self.priority
+    }
+
     /// Get the bot token.
     pub fn token(&self) -> &str {
         &self.token
@@ -178,6 +229,24 @@ impl HttpClient {
         Ok(())
     }
 
+    /// Make a POST request with headers.
+    pub(crate) async fn post_with_headers<T: DeserializeOwned, B: serde::Serialize>(
+        &self,
+        route: &str,
+        body: B,
+        headers: Option<HeaderMap>,
+    ) -> Result<T, HttpError> {
+        self.request(Method::POST, route, Some(body), headers).await
+    }
+
+    /// Make a POST request without a body (e.g., consuming an entitlement).
+    pub(crate) async fn post_empty<T: DeserializeOwned>(
+        &self,
+        route: &str,
+    ) -> Result<T, HttpError> {
+        self.request(Method::POST, route, None::<()>, None).await
+    }
+
     /// Make a PUT request.
     #[allow(dead_code)]
     pub(crate) async fn put<T: DeserializeOwned, B: serde::Serialize>(
@@ -208,6 +277,17 @@ impl HttpClient {
         self.request(Method::PATCH, route, Some(body), None).await
     }
 
+    /// Make a PATCH request with headers.
+    pub(crate) async fn patch_with_headers<T: DeserializeOwned, B: serde::Serialize>(
+        &self,
+        route: &str,
+        body: B,
+        headers: Option<HeaderMap>,
+    ) -> Result<T, HttpError> {
+        self.request(Method::PATCH, route, Some(body), headers)
+            .await
+    }
+
     /// Make a DELETE request.
     #[allow(dead_code)]
     pub(crate) async fn delete<T: DeserializeOwned>(&self, route: &str) -> Result<T, HttpError> {
@@ -240,6 +320,31 @@ impl HttpClient {
             .await
     }
 
+    /// Make a `multipart/form-data` request (file uploads).
+    pub(crate) async fn request_multipart<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        route: &str,
+        form: reqwest::multipart::Form,
+        headers: Option<HeaderMap>,
+    ) -> Result<T, HttpError> {
+        let url = format!("{}{}", API_BASE, route);
+
+        // The form sets its own Content-Type (with boundary), overriding the JSON default.
+        let mut request = self.client.request(method.clone(), &url).multipart(form);
+
+        if let Some(headers) = headers {
+            request = request.headers(headers);
+        }
+
+        let request = request.build()?;
+
+        debug!(method = %method, route = %route, priority = ?self.priority, "Making multipart request");
+
+        let bytes = self.execute(route, request).await?;
+        decode(&bytes)
+    }
+
     /// Make an HTTP request with rate limit handling.
     async fn request<T: DeserializeOwned, B: serde::Serialize>(
         &self,
@@ -253,6 +358,10 @@ impl HttpClient {
     }
 
     /// Make an HTTP request with query params, rate limit handling, and headers.
+    ///
+    /// Plain GET requests (no body, no extra headers) are coalesced: while one is
+    /// in flight, identical requests of the same or lower priority wait for and
+    /// share its response.
     async fn request_with_query<
         T: DeserializeOwned,
         Q: serde::Serialize + ?Sized,
@@ -266,9 +375,7 @@ impl HttpClient {
         headers: Option<HeaderMap>,
     ) -> Result<T, HttpError> {
         let url = format!("{}{}", API_BASE, route);
-
-        // Acquire rate limit permit
-        self.rate_limiter.acquire(route).await?;
+        let coalesce = method == Method::GET && body.is_none() && headers.is_none();
 
         // Build request
         let mut request = self.client.request(method.clone(), &url);
@@ -290,21 +397,89 @@ impl HttpClient {
             request = request.body(body_bytes);
         }
 
-        debug!(method = %method, route = %route, "Making request");
+        let request = request.build()?;
+
+        debug!(method = %method, route = %route, priority = ?self.priority, "Making request");
+
+        let bytes = if coalesce {
+            self.execute_coalesced(route, request).await?
+        } else {
+            self.execute(route, request).await?
+        };
+
+        decode(&bytes)
+    }
+
+    /// Execute a GET request, sharing the response with identical concurrent requests.
+    async fn execute_coalesced(&self, route: &str, request: Request) -> Result<Bytes, HttpError> {
+        let key = request.url().to_string();
+
+        loop {
+            let existing = match self.inflight.entry(key.clone()) {
+                dashmap::Entry::Occupied(entry) if entry.get().priority < self.priority => None,
+                dashmap::Entry::Occupied(entry) => Some(entry.get().rx.clone()),
+                dashmap::Entry::Vacant(entry) => {
+                    let (tx, rx) = watch::channel(None);
+                    entry.insert(Inflight {
+                        priority: self.priority,
+                        rx: rx.clone(),
+                    });
+
+                    // Removes the entry even if this future is cancelled.
+                    let _guard = InflightGuard {
+                        inflight: &self.inflight,
+                        key: &key,
+                        rx,
+                    };
+
+                    let result = self.execute(route, request).await;
+                    let shared = match &result {
+                        Ok(bytes) => Ok(bytes.clone()),
+                        Err(e) => Err(e.duplicate()),
+                    };
+                    let _ = tx.send(Some(shared));
+                    return result;
+                }
+            };
+
+            // Waiting would hold this request back to the leader's priority
+            let Some(mut rx) = existing else {
+                return self.execute(route, request).await;
+            };
+
+            debug!(route = %route, "Coalescing with in-flight request");
+            let settled = rx.wait_for(Option::is_some).await;
+            if let Ok(result) = settled {
+                return match result.as_ref() {
+                    Some(Ok(bytes)) => Ok(bytes.clone()),
+                    Some(Err(e)) => Err(e.duplicate()),
+                    None => unreachable!("wait_for only returns once a value is set"),
+                };
+            }
+            // The leading request was cancelled before completing; retry.
+        }
+    }
+
+    /// Execute a request after waiting on its rate limit bucket.
+    async fn execute(&self, route: &str, mut request: Request) -> Result<Bytes, HttpError> {
+        request
+            .headers_mut()
+            .insert(AUTHORIZATION, self.auth.clone());
+
+        // Acquire rate limit permit
+        self.rate_limiter
+            .acquire_with_priority(route, self.priority)
+            .await?;
 
         // Send request
-        let response = request.send().await?;
+        let response = self.client.execute(request).await?;
 
         // Handle response
         self.handle_response(route, response).await
     }
 
-    /// Handle an HTTP response.
-    async fn handle_response<T: DeserializeOwned>(
-        &self,
-        route: &str,
-        response: Response,
-    ) -> Result<T, HttpError> {
+    /// Handle an HTTP response, returning the raw body on success.
+    async fn handle_response(&self, route: &str, response: Response) -> Result<Bytes, HttpError> {
         let status = response.status();
 
         // Extract rate limit headers
@@ -328,54 +503,11 @@ impl HttpClient {
         // Handle errors
         match status {
             StatusCode::OK | StatusCode::CREATED | StatusCode::NO_CONTENT => {
-                let bytes = response.bytes().await?;
-                if bytes.is_empty() {
-                    // For NO_CONTENT or empty responses
-                    // simd-json might choke on empty, but "null" is better?
-                    // T might be () or Option<T>.
-                    // Let's assume empty body means "null" or default.
-                    // But if T is a struct, from_slice(b"null") might fail if not Option.
-                    // Actually, existing code used b"null".to_vec();
-
-                    RESPONSE_BUFFER.with(|buf_cell| {
-                        let mut buf = buf_cell.borrow_mut();
-                        buf.clear();
-                        buf.extend_from_slice(b"null");
-                        simd_json::from_slice(&mut buf).map_err(|e| HttpError::Discord {
-                            code: 0,
-                            message: e.to_string(),
-                        })
-                    })
-                } else {
-                    RESPONSE_BUFFER.with(|buf_cell| {
-                        let mut buf = buf_cell.borrow_mut();
-
-                        // Memory management: Shrink buffer if it gets too large (> 10MB)
-                        if buf.capacity() > 10 * 1024 * 1024 {
-                            buf.shrink_to(1024 * 1024); // Shrink to 1MB
-                        }
-
-                        buf.clear();
-                        buf.extend_from_slice(&bytes);
-                        // simd-json parses in-place
-                        simd_json::from_slice(&mut buf).map_err(|e| HttpError::Discord {
-                            code: 0,
-                            message: e.to_string(),
-                        })
-                    })
-                }
+                Ok(response.bytes().await?)
             }
             StatusCode::TOO_MANY_REQUESTS => {
                 let bytes = response.bytes().await?;
-                let body: simd_json::OwnedValue = RESPONSE_BUFFER.with(|buf_cell| {
-                    let mut buf = buf_cell.borrow_mut();
-                    buf.clear();
-                    buf.extend_from_slice(&bytes);
-                    simd_json::from_slice(&mut buf).map_err(|e| HttpError::Discord {
-                        code: 0,
-                        message: e.to_string(),
-                    })
-                })?;
+                let body: simd_json::OwnedValue = decode(&bytes)?;
 
                 let retry_after = body
                     .get("retry_after")
@@ -404,15 +536,7 @@ impl HttpClient {
             _ if status.is_server_error() => Err(HttpError::ServerError(status.as_u16())),
             _ => {
                 let bytes = response.bytes().await?;
-                let error: DiscordError = RESPONSE_BUFFER.with(|buf_cell| {
-                    let mut buf = buf_cell.borrow_mut();
-                    buf.clear();
-                    buf.extend_from_slice(&bytes);
-                    simd_json::from_slice(&mut buf).map_err(|e| HttpError::Discord {
-                        code: 0,
-                        message: e.to_string(),
-                    })
-                })?;
+                let error: DiscordError = decode(&bytes)?;
 
                 Err(HttpError::Discord {
                     code: error.code,
@@ -450,6 +574,46 @@ impl HttpClient {
     }
 }
 
+/// Removes a coalesced request from the in-flight table once it settles.
+struct InflightGuard<'a> {
+    inflight: &'a DashMap<String, Inflight>,
+    key: &'a str,
+    rx: InflightReceiver,
+}
+
+impl Drop for InflightGuard<'_> {
+    fn drop(&mut self) {
+        self.inflight
+            .remove_if(self.key, |_, inflight| inflight.rx.same_channel(&self.rx));
+    }
+}
+
+/// Deserialize a response body using the thread-local scratch buffer.
+///
+/// Empty bodies (e.g. 204 No Content) are parsed as `null`.
+pub(crate) fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, HttpError> {
+    RESPONSE_BUFFER.with(|buf_cell| {
+        let mut buf = buf_cell.borrow_mut();
+
+        // Memory management: Shrink buffer if it gets too large (> 10MB)
+        if buf.capacity() > 10 * 1024 * 1024 {
+            buf.shrink_to(1024 * 1024); // Shrink to 1MB
+        }
+
+        buf.clear();
+        if bytes.is_empty() {
+            buf.extend_from_slice(b"null");
+        } else {
+            buf.extend_from_slice(bytes);
+        }
+        // simd-json parses in-place
+        simd_json::from_slice(&mut buf).map_err(|e| HttpError::Discord {
+            code: 0,
+            message: e.to_string(),
+        })
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -459,4 +623,20 @@ mod tests {
         let client = HttpClient::new("test_token");
         assert!(client.is_ok());
     }
+
+    #[test]
+    fn test_with_priority_shares_state() {
+        let client = HttpClient::new("test_token").unwrap();
+        let urgent = client.with_priority(RequestPriority::Interaction);
+        assert_eq!(client.priority(), RequestPriority::Normal);
+        assert_eq!(urgent.priority(), RequestPriority::Interaction);
+        assert!(Arc::ptr_eq(&client.rate_limiter, &urgent.rate_limiter));
+        assert!(Arc::ptr_eq(&client.inflight, &urgent.inflight));
+    }
+
+    #[test]
+    fn test_decode_empty_body() {
+        let value: Option<u32> = decode(b"").unwrap();
+        assert_eq!(value, None);
+    }
 }
@@ -1,6 +1,9 @@
 use crate::error::HttpError;
 use crate::HttpClient;
-use titanium_model::{Emoji, Snowflake, Sticker};
+use reqwest::multipart::{Form, Part};
+use reqwest::Method;
+use serde::Deserialize;
+use titanium_model::{Emoji, FileUpload, Snowflake, Sticker, StickerPack};
 
 impl HttpClient {
     // =========================================================================
@@ -57,10 +60,98 @@ impl HttpClient {
         self.delete(&route).await
     }
 
+    // =========================================================================
+    // Application Emoji Endpoints
+    // =========================================================================
+
+    /// List application emojis.
+    ///
+    /// Application emojis are owned by the application and usable in every guild.
+    pub async fn list_application_emojis(
+        &self,
+        application_id: Snowflake,
+    ) -> Result<Vec<Emoji<'static>>, HttpError> {
+        let route = format!("/applications/{}/emojis", application_id);
+        let response: ApplicationEmojis = self.get(&route).await?;
+        Ok(response.items)
+    }
+
+    /// Get application emoji.
+    pub async fn get_application_emoji(
+        &self,
+        application_id: Snowflake,
+        emoji_id: Snowflake,
+    ) -> Result<Emoji<'static>, HttpError> {
+        let route = format!("/applications/{}/emojis/{}", application_id, emoji_id);
+        self.get(&route).await
+    }
+
+    /// Create application emoji.
+    ///
+    /// Roles are ignored; application emojis cannot be role-restricted.
+    pub async fn create_application_emoji(
+        &self,
+        application_id: Snowflake,
+        params: &CreateEmojiParams,
+    ) -> Result<Emoji<'static>, HttpError> {
+        #[derive(serde::Serialize)]
+        struct Body<'a> {
+            name: &'a str,
+            image: &'a str,
+        }
+
+        let route = format!("/applications/{}/emojis", application_id);
+        let body = Body {
+            name: &params.name,
+            image: &params.image,
+        };
+        self.post(&route, body).await
+    }
+
+    /// Modify application emoji.
+    ///
+    /// Only the name can be changed.
+    pub async fn modify_application_emoji(
+        &self,
+        application_id: Snowflake,
+        emoji_id: Snowflake,
+        name: &str,
+    ) -> Result<Emoji<'static>, HttpError> {
+        #[derive(serde::Serialize)]
+        struct Body<'a> {
+            name: &'a str,
+        }
+
+        let route = format!("/applications/{}/emojis/{}", application_id, emoji_id);
+        self.patch(&route, Body { name }).await
+    }
+
+    /// Delete application emoji.
+    pub async fn delete_application_emoji(
+        &self,
+        application_id: Snowflake,
+        emoji_id: Snowflake,
+    ) -> Result<(), HttpError> {
+        let route = format!("/applications/{}/emojis/{}", application_id, emoji_id);
+        self.delete(&route).await
+    }
+
     // =========================================================================
     // Sticker Endpoints
     // =========================================================================
 
+    /// Get a sticker by ID (standard or guild).
+    pub async fn get_sticker(&self, sticker_id: Snowflake) -> Result<Sticker<'static>, HttpError> {
+        let route = format!("/stickers/{}", sticker_id);
+        self.get(&route).await
+    }
+
+    /// List the standard sticker packs.
+    pub async fn list_sticker_packs(&self) -> Result<Vec<StickerPack<'static>>, HttpError> {
+        let response: StickerPacks = self.get("/sticker-packs").await?;
+        Ok(response.sticker_packs)
+    }
+
     /// List guild stickers.
     pub async fn list_guild_stickers(
         &self,
@@ -81,17 +172,51 @@ impl HttpClient {
     }
 
     /// Create guild sticker.
+    ///
+    /// `file` must be a PNG, APNG, GIF or Lottie JSON file of at most 512 KiB.
+    /// The content type is inferred from the file extension.
     pub async fn create_guild_sticker(
         &self,
         guild_id: Snowflake,
         params: &CreateStickerParams,
+        file: &FileUpload,
+        reason: Option<&str>,
     ) -> Result<Sticker<'static>, HttpError> {
-        // multipart/form-data is required for stickers (file upload).
-        // titan-http v0.1 basic client might struggle with this without a refactor for multipart.
-        // For 1000/1000 we acknowledge this complexity.
-        // Providing the signature, but noting implementation limit in v0.1
         let route = format!("/guilds/{}/stickers", guild_id);
-        self.post(&route, params).await
+
+        let part = Part::bytes(file.data.clone())
+            .file_name(file.filename.clone())
+            .mime_str(sticker_content_type(&file.filename))?;
+        let form = Form::new()
+            .text("name", params.name.clone())
+            .text("description", params.description.clone())
+            .text("tags", params.tags.clone())
+            .part("file", part);
+
+        let headers = reason
+            .map(|r| -> Result<_, HttpError> {
+                let mut h = reqwest::header::HeaderMap::new();
+                h.insert(
+                    "X-Audit-Log-Reason",
+                    reqwest::header::HeaderValue::from_str(r)?,
+                );
+                Ok(h)
+            })
+            .transpose()?;
+
+        self.request_multipart(Method::POST, &route, form, headers)
+            .await
+    }
+
+    /// Modify guild sticker.
+    pub async fn modify_guild_sticker(
+        &self,
+        guild_id: Snowflake,
+        sticker_id: Snowflake,
+        params: &ModifyStickerParams,
+    ) -> Result<Sticker<'static>, HttpError> {
+        let route = format!("/guilds/{}/stickers/{}", guild_id, sticker_id);
+        self.patch(&route, params).await
     }
 
     /// Delete guild sticker.
@@ -105,12 +230,52 @@ impl HttpClient {
     }
 }
 
+/// Response wrapper for List Application Emojis.
+#[derive(Debug, Deserialize)]
+struct ApplicationEmojis {
+    items: Vec<Emoji<'static>>,
+}
+
+/// Response wrapper for List Sticker Packs.
+#[derive(Debug, Deserialize)]
+struct StickerPacks {
+    sticker_packs: Vec<StickerPack<'static>>,
+}
+
+/// Content type of a sticker upload, by file extension.
+fn sticker_content_type(filename: &str) -> &'static str {
+    let ext = filename
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "json" => "application/json",
+        "gif" => "image/gif",
+        // PNG and APNG
+        _ => "image/png",
+    }
+}
+
 // Use titanium_model types instead of local definitions for consistency with builders
-use titanium_model::builder::{CreateEmoji, CreateSticker, ModifyEmoji};
+use titanium_model::builder::{CreateEmoji, CreateSticker, ModifyEmoji, ModifySticker};
 
 // Alias local params to the model types so existing code works
 pub type CreateEmojiParams = CreateEmoji;
 pub type ModifyEmojiParams = ModifyEmoji;
 pub type CreateStickerParams = CreateSticker;
+pub type ModifyStickerParams = ModifySticker;
 
 // Local definitions removed/replaced by above aliases
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sticker_content_type() {
+        assert_eq!(sticker_content_type("wave.png"), "image/png");
+        assert_eq!(sticker_content_type("wave.GIF"), "image/gif");
+        assert_eq!(sticker_content_type("wave.json"), "application/json");
+        assert_eq!(sticker_content_type("wave"), "image/png");
+    }
+}
@@ -54,6 +54,44 @@ pub enum HttpError {
     /// Client internal error.
     #[error("Client error: {0}")]
     ClientError(String),
+
+    /// Invalid application public key (expected 32 hex-encoded bytes).
+    #[error("Invalid public key: {0}")]
+    InvalidPublicKey(String),
+
+    /// I/O error (e.g. binding the interactions server).
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl HttpError {
+    /// Duplicate this error so it can be handed to several waiters.
+    ///
+    /// Errors wrapping non-cloneable sources are flattened into [`HttpError::ClientError`].
+    pub(crate) fn duplicate(&self) -> Self {
+        match self {
+            Self::RateLimited {
+                retry_after_ms,
+                global,
+            } => Self::RateLimited {
+                retry_after_ms: *retry_after_ms,
+                global: *global,
+            },
+            Self::Discord { code, message } => Self::Discord {
+                code: *code,
+                message: message.clone(),
+            },
+            Self::Unauthorized => Self::Unauthorized,
+            Self::Forbidden => Self::Forbidden,
+            Self::NotFound => Self::NotFound,
+            Self::ServerError(status) => Self::ServerError(*status),
+            Self::ClientError(message) => Self::ClientError(message.clone()),
+            Self::InvalidPublicKey(message) => Self::InvalidPublicKey(message.clone()),
+            Self::Request(_) | Self::Json(_) | Self::InvalidHeaderValue(_) | Self::Io(_) => {
+                Self::ClientError(self.to_string())
+            }
+        }
+    }
 }
 
 /// Discord API error response.
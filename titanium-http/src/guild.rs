@@ -1,9 +1,9 @@
 use crate::error::HttpError;
-use crate::HttpClient;
+use crate::{HttpClient, RequestPriority};
 use serde::{Deserialize, Serialize};
 use titanium_model::{
-    AuditLogEntry, AutoModRule, Channel, GuildMember, Integration, Role, ScheduledEvent, Snowflake,
-    SoundboardSound, User, Webhook,
+    AuditLogEntry, AutoModRule, Ban, Channel, GuildMember, Integration, Role, ScheduledEvent,
+    Snowflake, SoundboardSound, User, Webhook,
 };
 
 // ============================================================================
@@ -31,6 +31,50 @@ pub struct GetAuditLogParams {
     pub limit: Option<u32>,
 }
 
+/// Parameters for Add Guild Member.
+///
+/// Requires an OAuth2 access token with the `guilds.join` scope for the user.
+#[derive(Debug, Default, Serialize)]
+pub struct AddGuildMemberParams {
+    /// OAuth2 access token of the user.
+    pub access_token: String,
+    /// Nickname to give the user.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nick: Option<String>,
+    /// Roles to assign to the user.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roles: Option<Vec<Snowflake>>,
+    /// Whether the user is muted in voice channels.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mute: Option<bool>,
+    /// Whether the user is deafened in voice channels.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deaf: Option<bool>,
+}
+
+/// Query parameters for Get Guild Bans.
+#[derive(Debug, Default, Serialize)]
+pub struct GetGuildBansParams {
+    /// Number of bans to return (1-1000).
+    pub limit: Option<u32>,
+    /// Return bans of users before this ID.
+    pub before: Option<Snowflake>,
+    /// Return bans of users after this ID.
+    pub after: Option<Snowflake>,
+}
+
+/// Response structure for Bulk Guild Ban.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BulkBanResponse {
+    /// Users that were successfully banned.
+    pub banned_users: Vec<Snowflake>,
+    /// Users that could not be banned.
+    pub failed_users: Vec<Snowflake>,
+}
+
+/// Maximum number of users in a single bulk ban request.
+pub const MAX_BULK_BAN_USERS: usize = 200;
+
 impl HttpClient {
     // =========================================================================
     // Guild Member Operations
@@ -68,6 +112,74 @@ impl HttpClient {
         self.get_with_query(&route, &query).await
     }
 
+    /// Search guild members whose username or nickname starts with `query`.
+    ///
+    /// `limit` defaults to 1 and is capped at 1000 by Discord.
+    pub async fn search_members(
+        &self,
+        guild_id: Snowflake,
+        query: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<GuildMember<'static>>, HttpError> {
+        #[derive(Serialize)]
+        struct Query<'a> {
+            query: &'a str,
+            limit: u32,
+        }
+
+        let query = Query {
+            query,
+            limit: limit.unwrap_or(1),
+        };
+
+        let route = format!("/guilds/{}/members/search", guild_id);
+        self.get_with_query(&route, &query).await
+    }
+
+    /// Add a user to the guild using their OAuth2 access token.
+    ///
+    /// Returns `None` if the user is already a member.
+    pub async fn add_member(
+        &self,
+        guild_id: Snowflake,
+        user_id: Snowflake,
+        params: &AddGuildMemberParams,
+    ) -> Result<Option<GuildMember<'static>>, HttpError> {
+        let route = format!("/guilds/{}/members/{}", guild_id, user_id);
+        self.put(&route, Some(params)).await
+    }
+
+    /// Modify the current member (the bot) in the guild.
+    ///
+    /// Pass `None` to reset the nickname.
+    pub async fn modify_current_member(
+        &self,
+        guild_id: Snowflake,
+        nick: Option<&str>,
+        reason: Option<&str>,
+    ) -> Result<GuildMember<'static>, HttpError> {
+        #[derive(Serialize)]
+        struct Body<'a> {
+            nick: Option<&'a str>,
+        }
+
+        let route = format!("/guilds/{}/members/@me", guild_id);
+
+        let headers = reason
+            .map(|r| -> Result<_, HttpError> {
+                let mut h = reqwest::header::HeaderMap::new();
+                h.insert(
+                    "X-Audit-Log-Reason",
+                    reqwest::header::HeaderValue::from_str(r)?,
+                );
+                Ok(h)
+            })
+            .transpose()?;
+
+        self.patch_with_headers(&route, Body { nick }, headers)
+            .await
+    }
+
     /// Kick a member from the guild.
     pub async fn kick_member(
         &self,
@@ -88,7 +200,7 @@ impl HttpClient {
             })
             .transpose()?;
 
-        self.delete_with_headers(&route, headers).await
+        self.moderation().delete_with_headers(&route, headers).await
     }
 
     /// Ban a member from the guild.
@@ -117,7 +229,77 @@ impl HttpClient {
             })
         });
 
-        self.put_with_headers(&route, Some(body), headers).await
+        self.moderation()
+            .put_with_headers(&route, Some(body), headers)
+            .await
+    }
+
+    /// Ban up to 200 users from the guild in a single request.
+    ///
+    /// `delete_message_seconds` deletes messages sent by the users in that
+    /// window (0-604800). Returns which users were banned and which failed.
+    pub async fn bulk_ban(
+        &self,
+        guild_id: Snowflake,
+        user_ids: &[Snowflake],
+        delete_message_seconds: Option<u32>,
+        reason: Option<&str>,
+    ) -> Result<BulkBanResponse, HttpError> {
+        #[derive(Serialize)]
+        struct BulkBanBody<'a> {
+            user_ids: &'a [Snowflake],
+            #[serde(skip_serializing_if = "Option::is_none")]
+            delete_message_seconds: Option<u32>,
+        }
+
+        if user_ids.is_empty() || user_ids.len() > MAX_BULK_BAN_USERS {
+            return Err(HttpError::ClientError(format!(
+                "bulk ban requires 1 to {} users, got {}",
+                MAX_BULK_BAN_USERS,
+                user_ids.len()
+            )));
+        }
+
+        let body = BulkBanBody {
+            user_ids,
+            delete_message_seconds,
+        };
+        let route = format!("/guilds/{}/bulk-ban", guild_id);
+
+        let headers = reason
+            .map(|r| -> Result<_, HttpError> {
+                let mut h = reqwest::header::HeaderMap::new();
+                h.insert(
+                    "X-Audit-Log-Reason",
+                    reqwest::header::HeaderValue::from_str(r)?,
+                );
+                Ok(h)
+            })
+            .transpose()?;
+
+        self.moderation()
+            .post_with_headers(&route, body, headers)
+            .await
+    }
+
+    /// Get the ban for a user.
+    pub async fn get_ban(
+        &self,
+        guild_id: Snowflake,
+        user_id: Snowflake,
+    ) -> Result<Ban<'static>, HttpError> {
+        let route = format!("/guilds/{}/bans/{}", guild_id, user_id);
+        self.get(&route).await
+    }
+
+    /// List the bans of the guild.
+    pub async fn list_bans(
+        &self,
+        guild_id: Snowflake,
+        params: &GetGuildBansParams,
+    ) -> Result<Vec<Ban<'static>>, HttpError> {
+        let route = format!("/guilds/{}/bans", guild_id);
+        self.get_with_query(&route, params).await
     }
 
     /// Unban a member.
@@ -137,7 +319,12 @@ impl HttpClient {
             })
         });
 
-        self.delete_with_headers(&route, headers).await
+        self.moderation().delete_with_headers(&route, headers).await
+    }
+
+    /// Get a handle that queues with at least moderation priority.
+    fn moderation(&self) -> HttpClient {
+        self.with_priority(self.priority().max(RequestPriority::Moderation))
     }
 
     /// Modify a guild member.
@@ -212,13 +399,56 @@ impl HttpClient {
     // Soundboard (merged from soundboard.rs)
     // =========================================================================
 
+    /// List the default soundboard sounds available to everyone.
+    pub async fn list_default_soundboard_sounds(
+        &self,
+    ) -> Result<Vec<SoundboardSound<'static>>, HttpError> {
+        self.get("/soundboard-default-sounds").await
+    }
+
     /// List soundboard sounds for a guild.
     pub async fn list_guild_soundboard_sounds(
         &self,
         guild_id: Snowflake,
     ) -> Result<Vec<SoundboardSound<'static>>, HttpError> {
-        self.get(&format!("/guilds/{}/soundboard-sounds", guild_id))
-            .await
+        #[derive(Deserialize)]
+        struct Sounds {
+            items: Vec<SoundboardSound<'static>>,
+        }
+
+        let sounds: Sounds = self
+            .get(&format!("/guilds/{}/soundboard-sounds", guild_id))
+            .await?;
+        Ok(sounds.items)
+    }
+
+    /// Play a soundboard sound in a voice channel.
+    ///
+    /// The bot must be connected to the voice channel. `source_guild_id` is
+    /// required when playing a sound from a different guild, and must be
+    /// `None` for default sounds.
+    pub async fn send_soundboard_sound(
+        &self,
+        channel_id: Snowflake,
+        sound_id: Snowflake,
+        source_guild_id: Option<Snowflake>,
+    ) -> Result<(), HttpError> {
+        #[derive(Serialize)]
+        struct SendSoundBody {
+            sound_id: Snowflake,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            source_guild_id: Option<Snowflake>,
+        }
+
+        let body = SendSoundBody {
+            sound_id,
+            source_guild_id,
+        };
+        self.post_no_response(
+            &format!("/channels/{}/send-soundboard-sound", channel_id),
+            body,
+        )
+        .await
     }
 
     /// Get a specific soundboard sound.
@@ -271,3 +501,19 @@ impl HttpClient {
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bulk_ban_rejects_oversized_batch() {
+        let http = HttpClient::new("test_token").unwrap();
+        let users: Vec<Snowflake> = (0..=MAX_BULK_BAN_USERS as u64)
+            .map(Snowflake::new)
+            .collect();
+
+        let result = http.bulk_ban(Snowflake::new(1), &users, None, None).await;
+        assert!(matches!(result, Err(HttpError::ClientError(_))));
+    }
+}
@@ -1,8 +1,10 @@
 use crate::error::HttpError;
-use crate::HttpClient;
+use crate::{HttpClient, RequestPriority};
 use serde::Serialize;
 use titanium_model::{InteractionResponse, Message, Snowflake};
 
+// Interaction endpoints always use `RequestPriority::Interaction` so that
+// responses are not queued behind background work on the same bucket.
 impl HttpClient {
     /// Create a response to an Interaction.
     ///
@@ -15,7 +17,9 @@ impl HttpClient {
         response: &InteractionResponse<'_>,
     ) -> Result<(), HttpError> {
         let route = format!("/interactions/{}/{}/callback", interaction_id, token);
-        self.post(&route, response).await
+        self.with_priority(RequestPriority::Interaction)
+            .post(&route, response)
+            .await
     }
 
     /// Get the original response message.
@@ -25,7 +29,9 @@ impl HttpClient {
         token: &str,
     ) -> Result<Message<'static>, HttpError> {
         let route = format!("/webhooks/{}/{}/messages/@original", application_id, token);
-        self.get(&route).await
+        self.with_priority(RequestPriority::Interaction)
+            .get(&route)
+            .await
     }
 
     /// Edit the original response message.
@@ -38,7 +44,9 @@ impl HttpClient {
         body: B,
     ) -> Result<Message<'static>, HttpError> {
         let route = format!("/webhooks/{}/{}/messages/@original", application_id, token);
-        self.patch(&route, body).await
+        self.with_priority(RequestPriority::Interaction)
+            .patch(&route, body)
+            .await
     }
 
     /// Delete the original response message.
@@ -48,7 +56,9 @@ impl HttpClient {
         token: &str,
     ) -> Result<(), HttpError> {
         let route = format!("/webhooks/{}/{}/messages/@original", application_id, token);
-        self.delete(&route).await
+        self.with_priority(RequestPriority::Interaction)
+            .delete(&route)
+            .await
     }
 
     /// Create a followup message.
@@ -62,7 +72,8 @@ impl HttpClient {
     ) -> Result<Message<'static>, HttpError> {
         let route = format!("/webhooks/{}/{}", application_id, token);
         // "wait=true" ensures we get the Message object back
-        self.post_with_query(&route, body, &[("wait", "true")])
+        self.with_priority(RequestPriority::Interaction)
+            .post_with_query(&route, body, &[("wait", "true")])
             .await
     }
 }
@@ -2,11 +2,13 @@
 //!
 //! High-performance HTTP client for Discord's REST API with:
 //! - Automatic rate limit handling
-//! - Request queuing per-route
+//! - Request queuing per-route, ordered by priority
+//! - Coalescing of identical concurrent GET requests
 //! - Retry logic with exponential backoff
 
 #![allow(dead_code)]
 pub mod automod;
+pub mod cdn;
 pub mod channel;
 pub mod client;
 pub mod emoji;
@@ -16,8 +18,11 @@ pub mod interaction;
 pub mod monetization;
 pub mod ratelimit;
 pub mod routes;
+#[cfg(feature = "server")]
+pub mod server;
 
+pub use cdn::AssetDownload;
 pub use client::HttpClient;
 pub use error::HttpError;
-pub use ratelimit::RateLimiter;
+pub use ratelimit::{RateLimiter, RequestPriority};
 pub use routes::*;
@@ -1,6 +1,20 @@
 use crate::error::HttpError;
 use crate::HttpClient;
-use titanium_model::{Entitlement, Sku, Snowflake};
+use serde::Serialize;
+use titanium_model::{Entitlement, Sku, Snowflake, Subscription};
+
+/// Query parameters for List SKU Subscriptions.
+#[derive(Debug, Default, Serialize)]
+pub struct ListSkuSubscriptionsParams {
+    /// List subscriptions before this ID.
+    pub before: Option<Snowflake>,
+    /// List subscriptions after this ID.
+    pub after: Option<Snowflake>,
+    /// Number of results to return (1-100).
+    pub limit: Option<u32>,
+    /// User ID to list subscriptions for. Required unless using an OAuth token.
+    pub user_id: Option<Snowflake>,
+}
 
 impl HttpClient {
     /// List SKUs for an application.
@@ -37,6 +51,21 @@ impl HttpClient {
         .await
     }
 
+    /// Consume a one-time purchase (consumable SKU) entitlement.
+    ///
+    /// The entitlement will have `consumed: true` afterwards.
+    pub async fn consume_entitlement(
+        &self,
+        application_id: Snowflake,
+        entitlement_id: Snowflake,
+    ) -> Result<(), HttpError> {
+        self.post_empty(&format!(
+            "/applications/{}/entitlements/{}/consume",
+            application_id, entitlement_id
+        ))
+        .await
+    }
+
     /// Create a test entitlement.
     pub async fn create_test_entitlement(
         &self,
@@ -62,4 +91,27 @@ impl HttpClient {
         ))
         .await
     }
+
+    /// List subscriptions to a SKU.
+    pub async fn list_sku_subscriptions(
+        &self,
+        sku_id: Snowflake,
+        params: &ListSkuSubscriptionsParams,
+    ) -> Result<Vec<Subscription>, HttpError> {
+        self.get_with_query(&format!("/skus/{}/subscriptions", sku_id), params)
+            .await
+    }
+
+    /// Get a subscription to a SKU.
+    pub async fn get_sku_subscription(
+        &self,
+        sku_id: Snowflake,
+        subscription_id: Snowflake,
+    ) -> Result<Subscription, HttpError> {
+        self.get(&format!(
+            "/skus/{}/subscriptions/{}",
+            sku_id, subscription_id
+        ))
+        .await
+    }
 }
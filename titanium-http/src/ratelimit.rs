@@ -1,14 +1,36 @@
 //! HTTP rate limiting.
 //!
 //! Implements Discord's bucket-based rate limiting system.
+//!
+//! Requests waiting on the same bucket are admitted in [`RequestPriority`]
+//! order, so interaction responses are not stuck behind bulk background jobs.
 
 use dashmap::DashMap;
 use parking_lot::Mutex;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::Semaphore;
+use tokio::sync::{oneshot, Semaphore};
 use tokio::time::sleep;
 
+/// Priority class of a request within a rate limit bucket.
+///
+/// When several requests are queued on the same bucket, higher priorities
+/// are admitted first. Requests of equal priority keep FIFO order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum RequestPriority {
+    /// Bulk and background synchronization work.
+    Background,
+    /// Regular requests.
+    #[default]
+    Normal,
+    /// Moderation actions (kicks, bans, unbans).
+    Moderation,
+    /// Interaction responses, which must land within Discord's 3 second deadline.
+    Interaction,
+}
+
 /// Rate limiter for Discord API requests.
 pub struct RateLimiter {
     /// Per-route buckets.
@@ -26,8 +48,8 @@ struct Bucket {
     remaining: Mutex<u32>,
     /// When the bucket resets.
     reset_at: Mutex<Instant>,
-    /// Semaphore to queue requests.
-    semaphore: Semaphore,
+    /// Priority queue of requests waiting for this bucket.
+    queue: PriorityQueue,
 }
 
 impl RateLimiter {
@@ -42,6 +64,18 @@ impl RateLimiter {
 
     /// Acquire permission to make a request to the given route.
     pub async fn acquire(&self, route: &str) -> Result<(), crate::HttpError> {
+        self.acquire_with_priority(route, RequestPriority::Normal)
+            .await
+    }
+
+    /// Acquire permission to make a request to the given route with a priority.
+    ///
+    /// Requests queued on the same bucket are admitted highest priority first.
+    pub async fn acquire_with_priority(
+        &self,
+        route: &str,
+        priority: RequestPriority,
+    ) -> Result<(), crate::HttpError> {
         // Check global rate limit
         let until = { *self.global_until.lock() };
         if let Some(until) = until {
@@ -58,15 +92,13 @@ impl RateLimiter {
                 Arc::new(Bucket {
                     remaining: Mutex::new(1),
                     reset_at: Mutex::new(Instant::now()),
-                    semaphore: Semaphore::new(1),
+                    queue: PriorityQueue::new(),
                 })
             })
             .clone();
 
-        // Acquire semaphore permit
-        let _permit = bucket.semaphore.acquire().await.map_err(|_| {
-            crate::HttpError::ClientError("Rate limit semaphore closed".to_string())
-        })?;
+        // Wait for our turn in the bucket queue
+        let _turn = bucket.queue.acquire(priority).await;
 
         // Check if we need to wait for reset
         let wait = {
@@ -109,3 +141,205 @@ impl Default for RateLimiter {
         Self::new()
     }
 }
+
+// ============================================================================
+// Priority Queue
+// ============================================================================
+
+/// A single-slot gate that hands its slot to waiters in priority order.
+struct PriorityQueue {
+    state: Mutex<QueueState>,
+}
+
+struct QueueState {
+    /// Whether the slot is currently held.
+    busy: bool,
+    /// Monotonic counter used to keep FIFO order within a priority.
+    next_ticket: u64,
+    /// Waiters ordered by priority, then arrival.
+    waiters: BinaryHeap<Waiter>,
+}
+
+struct Waiter {
+    priority: RequestPriority,
+    ticket: u64,
+    tx: oneshot::Sender<()>,
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority && self.ticket == other.ticket
+    }
+}
+
+impl Eq for Waiter {}
+
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Waiter {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Max-heap: higher priority first, then the lower (older) ticket.
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.ticket.cmp(&self.ticket))
+    }
+}
+
+impl PriorityQueue {
+    fn new() -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                busy: false,
+                next_ticket: 0,
+                waiters: BinaryHeap::new(),
+            }),
+        }
+    }
+
+    /// Wait until the slot is handed to us.
+    async fn acquire(&self, priority: RequestPriority) -> QueueTurn<'_> {
+        let rx = {
+            let mut state = self.state.lock();
+            if !state.busy {
+                state.busy = true;
+                return QueueTurn { queue: self };
+            }
+
+            let (tx, rx) = oneshot::channel();
+            let ticket = state.next_ticket;
+            state.next_ticket += 1;
+            state.waiters.push(Waiter {
+                priority,
+                ticket,
+                tx,
+            });
+            rx
+        };
+
+        let mut pending = PendingTurn {
+            queue: self,
+            rx: Some(rx),
+        };
+
+        if let Some(rx) = pending.rx.as_mut() {
+            // The sender is only dropped when the queue itself is dropped,
+            // which cannot happen while we hold a reference to it.
+            let _ = rx.await;
+        }
+        pending.rx = None;
+
+        QueueTurn { queue: self }
+    }
+
+    /// Hand the slot to the next live waiter, or mark it free.
+    fn release(&self) {
+        let mut state = self.state.lock();
+        while let Some(waiter) = state.waiters.pop() {
+            if waiter.tx.send(()).is_ok() {
+                return;
+            }
+        }
+        state.busy = false;
+    }
+}
+
+/// Holds the bucket slot; releases it to the next waiter on drop.
+struct QueueTurn<'a> {
+    queue: &'a PriorityQueue,
+}
+
+impl Drop for QueueTurn<'_> {
+    fn drop(&mut self) {
+        self.queue.release();
+    }
+}
+
+/// A queued waiter. If cancelled after being handed the slot, passes it on.
+struct PendingTurn<'a> {
+    queue: &'a PriorityQueue,
+    rx: Option<oneshot::Receiver<()>>,
+}
+
+impl Drop for PendingTurn<'_> {
+    fn drop(&mut self) {
+        if let Some(mut rx) = self.rx.take() {
+            rx.close();
+            if rx.try_recv().is_ok() {
+                self.queue.release();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_priority_admission_order() {
+        let queue = Arc::new(PriorityQueue::new());
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        // Hold the slot so everyone else has to queue.
+        let held = queue.acquire(RequestPriority::Normal).await;
+
+        let mut handles = Vec::new();
+        for priority in [
+            RequestPriority::Background,
+            RequestPriority::Normal,
+            RequestPriority::Interaction,
+            RequestPriority::Moderation,
+        ] {
+            let queue = queue.clone();
+            let order = order.clone();
+            handles.push(tokio::spawn(async move {
+                let _turn = queue.acquire(priority).await;
+                order.lock().push(priority);
+            }));
+            tokio::task::yield_now().await;
+        }
+
+        drop(held);
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        assert_eq!(
+            *order.lock(),
+            vec![
+                RequestPriority::Interaction,
+                RequestPriority::Moderation,
+                RequestPriority::Normal,
+                RequestPriority::Background,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_waiter_passes_slot() {
+        let queue = Arc::new(PriorityQueue::new());
+        let held = queue.acquire(RequestPriority::Normal).await;
+
+        let cancelled = {
+            let queue = queue.clone();
+            tokio::spawn(async move {
+                let _turn = queue.acquire(RequestPriority::Interaction).await;
+            })
+        };
+        tokio::task::yield_now().await;
+        cancelled.abort();
+        let _ = cancelled.await;
+
+        drop(held);
+        let turn = tokio::time::timeout(
+            Duration::from_secs(1),
+            queue.acquire(RequestPriority::Background),
+        )
+        .await;
+        assert!(turn.is_ok());
+    }
+}
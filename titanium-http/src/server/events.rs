@@ -0,0 +1,142 @@
+//! Application webhook events receiver.
+
+use super::{SignatureVerifier, SignedEndpoint, WebhookResponse};
+use crate::client::decode;
+use crate::error::HttpError;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use titanium_model::{ApplicationEvent, WebhookEventPayload};
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tracing::warn;
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+type Handler = dyn Fn(ApplicationEvent<'static>) -> BoxFuture<()> + Send + Sync;
+
+/// HTTP receiver for Discord's application webhook events.
+///
+/// Verifies each request's Ed25519 signature, acknowledges PINGs and events
+/// with `204 No Content`, and passes each decoded event to the handler on a
+/// separate task, so slow handlers never delay the acknowledgement.
+///
+/// # Example
+///
+/// ```no_run
+/// # use titanium_http::server::WebhookEventServer;
+/// # use titanium_model::WebhookEvent;
+/// # async fn example() -> Result<(), titanium_http::HttpError> {
+/// let server = WebhookEventServer::new("application-public-key", |event| async move {
+///     if let WebhookEvent::EntitlementCreate(entitlement) = event.event {
+///         println!("New purchase of SKU {}", entitlement.sku_id);
+///     }
+/// })?;
+///
+/// server.serve("0.0.0.0:8081").await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct WebhookEventServer {
+    verifier: SignatureVerifier,
+    handler: Arc<Handler>,
+}
+
+impl WebhookEventServer {
+    /// Create a receiver for the application with the given hex-encoded public key.
+    pub fn new<F, Fut>(public_key: &str, handler: F) -> Result<Self, HttpError>
+    where
+        F: Fn(ApplicationEvent<'static>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        Ok(Self {
+            verifier: SignatureVerifier::new(public_key)?,
+            handler: Arc::new(move |event| Box::pin(handler(event))),
+        })
+    }
+
+    /// Bind to `addr` and receive events until an I/O error occurs.
+    pub async fn serve(self, addr: impl ToSocketAddrs) -> Result<(), HttpError> {
+        super::serve(Arc::new(self), addr).await
+    }
+
+    /// Receive events on an already bound listener.
+    pub async fn serve_listener(self, listener: TcpListener) -> Result<(), HttpError> {
+        super::serve_listener(Arc::new(self), listener).await
+    }
+
+    /// Handle a raw request.
+    ///
+    /// See [`InteractionServer::process`](super::InteractionServer::process).
+    pub async fn process(
+        &self,
+        signature: Option<&str>,
+        timestamp: Option<&str>,
+        body: Vec<u8>,
+    ) -> WebhookResponse {
+        let (Some(signature), Some(timestamp)) = (signature, timestamp) else {
+            return WebhookResponse::error(401, "missing request signature");
+        };
+        if !self.verifier.verify(signature, timestamp, &body) {
+            return WebhookResponse::error(401, "invalid request signature");
+        }
+
+        let event = decode::<WebhookEventPayload>(&body)
+            .map_err(|e| e.to_string())
+            .and_then(|payload| payload.into_event().map_err(|e| e.to_string()));
+
+        match event {
+            Ok(Some(event)) => {
+                tokio::spawn((self.handler)(event));
+                WebhookResponse::empty(204)
+            }
+            Ok(None) => WebhookResponse::empty(204),
+            Err(e) => {
+                warn!(error = %e, "Failed to parse webhook event");
+                WebhookResponse::error(400, "invalid webhook event payload")
+            }
+        }
+    }
+}
+
+impl SignedEndpoint for WebhookEventServer {
+    fn process(
+        &self,
+        signature: Option<&str>,
+        timestamp: Option<&str>,
+        body: Vec<u8>,
+    ) -> impl Future<Output = WebhookResponse> + Send {
+        WebhookEventServer::process(self, signature, timestamp, body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::verify::tests::{hex, sign, signing_key};
+    use titanium_model::WebhookEvent;
+
+    #[tokio::test]
+    async fn test_dispatches_entitlement_event() {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let tx = parking_lot::Mutex::new(Some(tx));
+
+        let key = hex(signing_key().verifying_key().as_bytes());
+        let server = WebhookEventServer::new(&key, move |event| {
+            let tx = tx.lock().take();
+            async move {
+                if let Some(tx) = tx {
+                    let _ = tx.send(event);
+                }
+            }
+        })
+        .unwrap();
+
+        let body = br#"{"version":1,"application_id":"789","type":1,"event":{"type":"ENTITLEMENT_CREATE","timestamp":"2024-10-18T14:42:53.064834","data":{"id":"123","sku_id":"456","application_id":"789","type":8}}}"#.to_vec();
+        let signature = sign("1", &body);
+
+        let response = server.process(Some(&signature), Some("1"), body).await;
+        assert_eq!(response.status, 204);
+
+        let event = rx.await.unwrap();
+        assert!(matches!(event.event, WebhookEvent::EntitlementCreate(_)));
+    }
+}
@@ -0,0 +1,255 @@
+//! Interactions endpoint server.
+
+use super::{SignatureVerifier, SignedEndpoint, WebhookResponse};
+use crate::client::decode;
+use crate::error::HttpError;
+use crate::HttpClient;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use titanium_model::{Interaction, InteractionResponse, InteractionType};
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tracing::{error, warn};
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+type Handler =
+    dyn Fn(Interaction<'static>) -> BoxFuture<InteractionResponse<'static>> + Send + Sync;
+
+/// Default time to wait for the handler before falling back to a REST callback.
+const DEFAULT_RESPONSE_TIMEOUT: Duration = Duration::from_millis(2500);
+
+/// HTTP server for Discord's Interactions Endpoint URL.
+///
+/// Verifies each request's Ed25519 signature, answers PINGs, and passes
+/// every other interaction to the handler. The handler's
+/// [`InteractionResponse`] is returned as the HTTP response body.
+///
+/// If the handler does not finish within the response timeout (2.5 s by
+/// default), the request is acknowledged with `202 Accepted` and the response
+/// is sent through the REST callback endpoint once the handler completes.
+///
+/// The callback only succeeds while the interaction is within Discord's 3
+/// second window for the initial response. The fallback covers handlers
+/// finishing just past the timeout; handlers that may take longer must
+/// return a deferred response and follow up instead of relying on it.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use titanium_http::HttpClient;
+/// # use titanium_http::server::InteractionServer;
+/// # use titanium_model::InteractionResponse;
+/// # async fn example() -> Result<(), titanium_http::HttpError> {
+/// let http = Arc::new(HttpClient::new("token")?);
+/// let server = InteractionServer::new("application-public-key", http, |interaction| async move {
+///     InteractionResponse::reply(format!("Hello from {}", interaction.id)).build()
+/// })?;
+///
+/// server.serve("0.0.0.0:8080").await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct InteractionServer {
+    verifier: SignatureVerifier,
+    http: Arc<HttpClient>,
+    handler: Arc<Handler>,
+    response_timeout: Duration,
+}
+
+impl InteractionServer {
+    /// Create a server for the application with the given hex-encoded public key.
+    pub fn new<F, Fut>(
+        public_key: &str,
+        http: Arc<HttpClient>,
+        handler: F,
+    ) -> Result<Self, HttpError>
+    where
+        F: Fn(Interaction<'static>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = InteractionResponse<'static>> + Send + 'static,
+    {
+        Ok(Self {
+            verifier: SignatureVerifier::new(public_key)?,
+            http,
+            handler: Arc::new(move |interaction| Box::pin(handler(interaction))),
+            response_timeout: DEFAULT_RESPONSE_TIMEOUT,
+        })
+    }
+
+    /// Set how long to wait for the handler before using the REST callback.
+    #[must_use]
+    pub fn with_response_timeout(mut self, timeout: Duration) -> Self {
+        self.response_timeout = timeout;
+        self
+    }
+
+    /// Bind to `addr` and serve interactions until an I/O error occurs.
+    pub async fn serve(self, addr: impl ToSocketAddrs) -> Result<(), HttpError> {
+        super::serve(Arc::new(self), addr).await
+    }
+
+    /// Serve interactions on an already bound listener.
+    pub async fn serve_listener(self, listener: TcpListener) -> Result<(), HttpError> {
+        super::serve_listener(Arc::new(self), listener).await
+    }
+
+    /// Handle a raw request.
+    ///
+    /// Use this to mount the endpoint in another web framework: pass the
+    /// `X-Signature-Ed25519` and `X-Signature-Timestamp` header values and the
+    /// unmodified body, then reply with the returned status and body.
+    pub async fn process(
+        &self,
+        signature: Option<&str>,
+        timestamp: Option<&str>,
+        body: Vec<u8>,
+    ) -> WebhookResponse {
+        let (Some(signature), Some(timestamp)) = (signature, timestamp) else {
+            return WebhookResponse::error(401, "missing request signature");
+        };
+        if !self.verifier.verify(signature, timestamp, &body) {
+            return WebhookResponse::error(401, "invalid request signature");
+        }
+
+        let interaction: Interaction<'static> = match decode(&body) {
+            Ok(interaction) => interaction,
+            Err(e) => {
+                warn!(error = %e, "Failed to parse interaction");
+                return WebhookResponse::error(400, "invalid interaction payload");
+            }
+        };
+
+        if interaction.interaction_type == InteractionType::Ping {
+            return WebhookResponse::json(br#"{"type":1}"#.to_vec());
+        }
+
+        let interaction_id = interaction.id;
+        let token = interaction.token.clone();
+        let mut task = tokio::spawn((self.handler)(interaction));
+
+        match tokio::time::timeout(self.response_timeout, &mut task).await {
+            Ok(Ok(response)) => match simd_json::to_vec(&response) {
+                Ok(body) => WebhookResponse::json(body),
+                Err(e) => {
+                    error!(error = %e, "Failed to serialize interaction response");
+                    WebhookResponse::error(500, "failed to serialize response")
+                }
+            },
+            Ok(Err(e)) => {
+                error!(error = %e, "Interaction handler panicked");
+                WebhookResponse::error(500, "handler failed")
+            }
+            Err(_) => {
+                warn!(
+                    interaction_id = %interaction_id,
+                    "Interaction handler is slow, responding via REST callback"
+                );
+                let http = self.http.clone();
+                tokio::spawn(async move {
+                    let Ok(response) = task.await else {
+                        error!(interaction_id = %interaction_id, "Interaction handler panicked");
+                        return;
+                    };
+                    if let Err(e) = http
+                        .create_interaction_response(interaction_id, &token, &response)
+                        .await
+                    {
+                        error!(
+                            interaction_id = %interaction_id,
+                            error = %e,
+                            "Failed to send interaction callback"
+                        );
+                    }
+                });
+                WebhookResponse::empty(202)
+            }
+        }
+    }
+}
+
+impl SignedEndpoint for InteractionServer {
+    fn process(
+        &self,
+        signature: Option<&str>,
+        timestamp: Option<&str>,
+        body: Vec<u8>,
+    ) -> impl Future<Output = WebhookResponse> + Send {
+        InteractionServer::process(self, signature, timestamp, body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::verify::tests::{hex, sign, signing_key};
+
+    fn server() -> InteractionServer {
+        server_with_delay(Duration::ZERO)
+    }
+
+    fn server_with_delay(delay: Duration) -> InteractionServer {
+        let http = Arc::new(HttpClient::new("test_token").unwrap());
+        let key = hex(signing_key().verifying_key().as_bytes());
+        InteractionServer::new(&key, http, move |_| async move {
+            tokio::time::sleep(delay).await;
+            InteractionResponse::reply("pong").build()
+        })
+        .unwrap()
+    }
+
+    fn command_body() -> Vec<u8> {
+        br#"{"id":"1","application_id":"2","type":2,"token":"abc","version":1}"#.to_vec()
+    }
+
+    #[tokio::test]
+    async fn test_rejects_bad_signature() {
+        let server = server();
+        let body = command_body();
+
+        let response = server.process(None, None, body.clone()).await;
+        assert_eq!(response.status, 401);
+
+        let signature = sign("1", &body);
+        let response = server.process(Some(&signature), Some("2"), body).await;
+        assert_eq!(response.status, 401);
+    }
+
+    #[tokio::test]
+    async fn test_answers_ping() {
+        let server = server();
+        let body =
+            br#"{"id":"1","application_id":"2","type":1,"token":"abc","version":1}"#.to_vec();
+        let signature = sign("1", &body);
+
+        let response = server.process(Some(&signature), Some("1"), body).await;
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, br#"{"type":1}"#);
+    }
+
+    #[tokio::test]
+    async fn test_returns_handler_response() {
+        let server = server();
+        let body = command_body();
+        let signature = sign("1", &body);
+
+        let response = server.process(Some(&signature), Some("1"), body).await;
+        assert_eq!(response.status, 200);
+        let json = String::from_utf8(response.body).unwrap();
+        assert!(json.contains(r#""type":4"#));
+        assert!(json.contains("pong"));
+    }
+
+    #[tokio::test]
+    async fn test_slow_handler_gets_accepted() {
+        let server = server_with_delay(Duration::from_millis(200))
+            .with_response_timeout(Duration::from_millis(10));
+        let body = command_body();
+        let signature = sign("1", &body);
+
+        // The response follows through the REST callback
+        let response = server.process(Some(&signature), Some("1"), body).await;
+        assert_eq!(response.status, 202);
+        assert!(response.body.is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+//! Outgoing webhook receivers (requires the `server` feature).
+//!
+//! Discord can deliver interactions and application events over HTTP
+//! instead of the gateway. Every request is signed with the application's
+//! Ed25519 key, which [`SignatureVerifier`] checks before anything is parsed.
+//!
+//! - [`InteractionServer`] - Interactions endpoint URL (slash commands, components, ...)
+//! - [`WebhookEventServer`] - Webhook events URL (authorizations, entitlements, ...)
+//!
+//! Each receiver can run its own listener via `serve`, or be embedded in an
+//! existing web framework through `process`, which maps the raw signature
+//! headers and body to a [`WebhookResponse`].
+
+mod events;
+mod interactions;
+mod verify;
+
+pub use events::WebhookEventServer;
+pub use interactions::InteractionServer;
+pub use verify::SignatureVerifier;
+
+use crate::error::HttpError;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use std::convert::Infallible;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tracing::{debug, info};
+
+/// Header carrying the hex-encoded Ed25519 signature.
+pub const SIGNATURE_HEADER: &str = "X-Signature-Ed25519";
+
+/// Header carrying the timestamp that was signed along with the body.
+pub const TIMESTAMP_HEADER: &str = "X-Signature-Timestamp";
+
+/// Largest request body accepted by the built-in listener.
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// Response produced by a webhook receiver.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookResponse {
+    /// HTTP status code.
+    pub status: u16,
+    /// JSON response body (may be empty).
+    pub body: Vec<u8>,
+}
+
+impl WebhookResponse {
+    /// A `200 OK` response with a JSON body.
+    pub fn json(body: Vec<u8>) -> Self {
+        Self { status: 200, body }
+    }
+
+    /// A response with no body.
+    pub fn empty(status: u16) -> Self {
+        Self {
+            status,
+            body: Vec::new(),
+        }
+    }
+
+    /// An error response with a short plain message.
+    pub(crate) fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: message.as_bytes().to_vec(),
+        }
+    }
+}
+
+/// A receiver that handles signed webhook requests.
+pub(crate) trait SignedEndpoint: Send + Sync + 'static {
+    /// Verify and handle a request given its signature headers and raw body.
+    fn process(
+        &self,
+        signature: Option<&str>,
+        timestamp: Option<&str>,
+        body: Vec<u8>,
+    ) -> impl Future<Output = WebhookResponse> + Send;
+}
+
+/// Bind a listener and serve an endpoint over HTTP/1.1 until an I/O error occurs.
+pub(crate) async fn serve<E: SignedEndpoint>(
+    endpoint: Arc<E>,
+    addr: impl ToSocketAddrs,
+) -> Result<(), HttpError> {
+    let listener = TcpListener::bind(addr).await?;
+    info!(addr = ?listener.local_addr().ok(), "Webhook server listening");
+    serve_listener(endpoint, listener).await
+}
+
+/// Serve an endpoint on an already bound listener.
+pub(crate) async fn serve_listener<E: SignedEndpoint>(
+    endpoint: Arc<E>,
+    listener: TcpListener,
+) -> Result<(), HttpError> {
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let _ = stream.set_nodelay(true);
+        let endpoint = endpoint.clone();
+
+        tokio::spawn(async move {
+            let service = service_fn(move |req| {
+                let endpoint = endpoint.clone();
+                async move { Ok::<_, Infallible>(handle_request(&*endpoint, req).await) }
+            });
+
+            if let Err(e) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                debug!(peer = %peer, error = %e, "Webhook connection closed with error");
+            }
+        });
+    }
+}
+
+/// Translate a hyper request into a call to [`SignedEndpoint::process`].
+async fn handle_request<E: SignedEndpoint>(
+    endpoint: &E,
+    req: Request<Incoming>,
+) -> Response<Full<Bytes>> {
+    if req.method() != Method::POST {
+        return to_hyper(WebhookResponse::error(405, "method not allowed"));
+    }
+
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned)
+    };
+    let signature = header(SIGNATURE_HEADER);
+    let timestamp = header(TIMESTAMP_HEADER);
+
+    let body = match Limited::new(req.into_body(), MAX_BODY_SIZE).collect().await {
+        Ok(collected) => collected.to_bytes().to_vec(),
+        Err(_) => return to_hyper(WebhookResponse::error(413, "payload too large")),
+    };
+
+    let response = endpoint
+        .process(signature.as_deref(), timestamp.as_deref(), body)
+        .await;
+    to_hyper(response)
+}
+
+fn to_hyper(response: WebhookResponse) -> Response<Full<Bytes>> {
+    let status = StatusCode::from_u16(response.status).unwrap_or(StatusCode::OK);
+    let content_type = if status.is_success() {
+        "application/json"
+    } else {
+        "text/plain"
+    };
+
+    let mut builder = Response::builder().status(status);
+    if !response.body.is_empty() {
+        builder = builder.header("Content-Type", content_type);
+    }
+    builder
+        .body(Full::new(Bytes::from(response.body)))
+        .unwrap_or_else(|_| Response::new(Full::new(Bytes::new())))
+}
@@ -24,6 +24,7 @@ impl<'a> ButtonBuilder<'a> {
                 emoji: None,
                 custom_id: None,
                 url: None,
+                sku_id: None,
                 disabled: false,
                 component_type: crate::component::ComponentType::Button,
             },
@@ -87,6 +88,16 @@ impl<'a> ButtonBuilder<'a> {
             .url(url)
     }
 
+    /// Create a premium button that lets the user purchase a SKU.
+    ///
+    /// Premium buttons have no label, emoji, custom ID or URL.
+    #[inline]
+    pub fn premium(sku_id: impl Into<crate::Snowflake>) -> Self {
+        Self::new()
+            .style(crate::component::ButtonStyle::Premium)
+            .sku_id(sku_id)
+    }
+
     /// Set style.
     pub fn style(mut self, style: crate::component::ButtonStyle) -> Self {
         self.inner.style = style;
@@ -117,6 +128,12 @@ impl<'a> ButtonBuilder<'a> {
         self
     }
 
+    /// Set SKU ID (premium buttons).
+    pub fn sku_id(mut self, sku_id: impl Into<crate::Snowflake>) -> Self {
+        self.inner.sku_id = Some(sku_id.into());
+        self
+    }
+
     /// Set disabled.
     pub fn disabled(mut self, disabled: bool) -> Self {
         self.inner.disabled = disabled;
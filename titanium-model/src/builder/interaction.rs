@@ -129,6 +129,24 @@ impl<'a> InteractionResponseBuilder<'a> {
         self
     }
 
+    /// Sets response type to PremiumRequired (10), with no data.
+    ///
+    /// Prefer [`purchase_button`](Self::purchase_button); Discord has deprecated this type.
+    pub fn premium_required(mut self) -> Self {
+        self.response.response_type = crate::interaction::InteractionCallbackType::PremiumRequired;
+        self.response.data = None;
+        self
+    }
+
+    /// Add a row with a premium button that lets the user purchase the SKU.
+    pub fn purchase_button(self, sku_id: impl Into<crate::Snowflake>) -> Self {
+        self.component(
+            ActionRowBuilder::new()
+                .add_button(crate::builder::ButtonBuilder::premium(sku_id))
+                .build(),
+        )
+    }
+
     #[must_use]
     pub fn build(self) -> crate::interaction::InteractionResponse<'a> {
         self.response
@@ -34,3 +34,52 @@ impl CreateStickerBuilder {
         self.params
     }
 }
+
+/// Payload for modifying a sticker.
+#[derive(Debug, Clone, serde::Serialize, Default)]
+pub struct ModifySticker {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<String>,
+}
+
+/// Builder for modifying a Sticker.
+#[derive(Debug, Clone, Default)]
+pub struct ModifyStickerBuilder {
+    params: ModifySticker,
+}
+
+impl ModifyStickerBuilder {
+    /// Create a new `ModifyStickerBuilder`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set name.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.params.name = Some(name.into());
+        self
+    }
+
+    /// Set description.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.params.description = Some(description.into());
+        self
+    }
+
+    /// Set tags.
+    pub fn tags(mut self, tags: impl Into<String>) -> Self {
+        self.params.tags = Some(tags.into());
+        self
+    }
+
+    /// Build the `ModifySticker` payload.
+    #[must_use]
+    pub fn build(self) -> ModifySticker {
+        self.params
+    }
+}
@@ -0,0 +1,488 @@
+//! Discord CDN asset URLs.
+//!
+//! [`CdnUrl`] builds image URLs for every asset type Discord serves from its
+//! CDN, and checks that the requested [`ImageFormat`] and size are ones the
+//! CDN accepts for that asset.
+//!
+//! ```
+//! use titanium_model::cdn::{CdnUrl, ImageFormat};
+//! use titanium_model::Snowflake;
+//!
+//! let url = CdnUrl::user_avatar(Snowflake::new(80351110224678912), "a_1269e74af4df7417b13759eae50c83dc")
+//!     .with_format(ImageFormat::WebP)?
+//!     .with_size(256)?;
+//!
+//! assert_eq!(
+//!     url.to_string(),
+//!     "https://cdn.discordapp.com/avatars/80351110224678912/a_1269e74af4df7417b13759eae50c83dc.webp?size=256"
+//! );
+//! # Ok::<(), titanium_model::cdn::CdnError>(())
+//! ```
+
+use crate::Snowflake;
+use std::fmt;
+
+/// Base URL of Discord's CDN.
+pub const CDN_BASE: &str = "https://cdn.discordapp.com";
+
+/// Base URL of Discord's media proxy (used for GIF stickers).
+pub const MEDIA_BASE: &str = "https://media.discordapp.net";
+
+/// Application that owns the standard sticker pack banners.
+const STICKER_PACK_APPLICATION_ID: u64 = 710_982_414_301_790_216;
+
+/// Image format of a CDN asset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    /// PNG (also used for APNG stickers).
+    Png,
+    /// JPEG.
+    Jpeg,
+    /// WebP.
+    WebP,
+    /// GIF (animated assets only).
+    Gif,
+    /// Lottie JSON (Lottie stickers only).
+    Lottie,
+}
+
+impl ImageFormat {
+    /// File extension used in the URL.
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::WebP => "webp",
+            Self::Gif => "gif",
+            Self::Lottie => "json",
+        }
+    }
+}
+
+/// Requested image size: a power of two between 16 and 4096.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ImageSize(u16);
+
+impl ImageSize {
+    /// Smallest size the CDN serves.
+    pub const MIN: u16 = 16;
+    /// Largest size the CDN serves.
+    pub const MAX: u16 = 4096;
+
+    /// Validate a size.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CdnError::InvalidSize`] if `size` is not a power of two in `16..=4096`.
+    pub const fn new(size: u16) -> Result<Self, CdnError> {
+        if size.is_power_of_two() && size >= Self::MIN && size <= Self::MAX {
+            Ok(Self(size))
+        } else {
+            Err(CdnError::InvalidSize(size))
+        }
+    }
+
+    /// Size in pixels.
+    #[must_use]
+    pub const fn get(self) -> u16 {
+        self.0
+    }
+}
+
+/// Kind of CDN asset, which decides the formats it can be served in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AssetKind {
+    /// Custom emoji.
+    CustomEmoji,
+    /// Guild icon.
+    GuildIcon,
+    /// Guild invite splash.
+    GuildSplash,
+    /// Guild discovery splash.
+    GuildDiscoverySplash,
+    /// Guild banner.
+    GuildBanner,
+    /// User profile banner.
+    UserBanner,
+    /// Default (embed) user avatar.
+    DefaultUserAvatar,
+    /// User avatar.
+    UserAvatar,
+    /// Per-guild member avatar.
+    GuildMemberAvatar,
+    /// Per-guild member banner.
+    GuildMemberBanner,
+    /// Avatar decoration preset.
+    AvatarDecoration,
+    /// Application icon.
+    ApplicationIcon,
+    /// Application cover image.
+    ApplicationCover,
+    /// Standard sticker pack banner.
+    StickerPackBanner,
+    /// Team icon.
+    TeamIcon,
+    /// Sticker.
+    Sticker,
+    /// Role icon.
+    RoleIcon,
+    /// Guild scheduled event cover.
+    ScheduledEventCover,
+}
+
+/// Errors produced when building a CDN URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CdnError {
+    /// Size is not a power of two between 16 and 4096.
+    InvalidSize(u16),
+    /// The asset cannot be served in this format.
+    UnsupportedFormat {
+        /// Asset kind.
+        asset: AssetKind,
+        /// Requested format.
+        format: ImageFormat,
+    },
+    /// Lottie stickers are vector data and cannot be resized.
+    SizeNotSupported,
+}
+
+impl fmt::Display for CdnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSize(size) => write!(
+                f,
+                "invalid image size {size}, expected a power of two between 16 and 4096"
+            ),
+            Self::UnsupportedFormat { asset, format } => {
+                write!(f, "{asset:?} assets cannot be served as {format:?}")
+            }
+            Self::SizeNotSupported => f.write_str("Lottie stickers cannot be resized"),
+        }
+    }
+}
+
+impl std::error::Error for CdnError {}
+
+/// URL of an asset on Discord's CDN.
+///
+/// Constructors pick the asset's default format: GIF for animated hashes
+/// (prefixed with `a_`), PNG otherwise, and the native format for stickers.
+/// Use [`CdnUrl::with_format`] and [`CdnUrl::with_size`] to change them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CdnUrl {
+    kind: AssetKind,
+    base: &'static str,
+    path: String,
+    animated: bool,
+    native: ImageFormat,
+    format: ImageFormat,
+    size: Option<ImageSize>,
+}
+
+impl CdnUrl {
+    fn new(kind: AssetKind, path: String, animated: bool) -> Self {
+        let format = if animated {
+            ImageFormat::Gif
+        } else {
+            ImageFormat::Png
+        };
+        Self {
+            kind,
+            base: CDN_BASE,
+            path,
+            animated,
+            native: format,
+            format,
+            size: None,
+        }
+    }
+
+    fn hashed(kind: AssetKind, prefix: fmt::Arguments<'_>, hash: &str) -> Self {
+        Self::new(kind, format!("{prefix}/{hash}"), hash.starts_with("a_"))
+    }
+
+    /// Custom emoji.
+    #[must_use]
+    pub fn emoji(emoji_id: Snowflake, animated: bool) -> Self {
+        Self::new(
+            AssetKind::CustomEmoji,
+            format!("emojis/{emoji_id}"),
+            animated,
+        )
+    }
+
+    /// Guild icon.
+    #[must_use]
+    pub fn guild_icon(guild_id: Snowflake, hash: &str) -> Self {
+        Self::hashed(AssetKind::GuildIcon, format_args!("icons/{guild_id}"), hash)
+    }
+
+    /// Guild invite splash.
+    #[must_use]
+    pub fn guild_splash(guild_id: Snowflake, hash: &str) -> Self {
+        Self::hashed(
+            AssetKind::GuildSplash,
+            format_args!("splashes/{guild_id}"),
+            hash,
+        )
+    }
+
+    /// Guild discovery splash.
+    #[must_use]
+    pub fn guild_discovery_splash(guild_id: Snowflake, hash: &str) -> Self {
+        Self::hashed(
+            AssetKind::GuildDiscoverySplash,
+            format_args!("discovery-splashes/{guild_id}"),
+            hash,
+        )
+    }
+
+    /// Guild banner.
+    #[must_use]
+    pub fn guild_banner(guild_id: Snowflake, hash: &str) -> Self {
+        Self::hashed(
+            AssetKind::GuildBanner,
+            format_args!("banners/{guild_id}"),
+            hash,
+        )
+    }
+
+    /// User profile banner.
+    #[must_use]
+    pub fn user_banner(user_id: Snowflake, hash: &str) -> Self {
+        Self::hashed(
+            AssetKind::UserBanner,
+            format_args!("banners/{user_id}"),
+            hash,
+        )
+    }
+
+    /// Default avatar with the given index (see [`User::default_avatar_url`](crate::User::default_avatar_url)).
+    #[must_use]
+    pub fn default_avatar(index: u64) -> Self {
+        Self::new(
+            AssetKind::DefaultUserAvatar,
+            format!("embed/avatars/{index}"),
+            false,
+        )
+    }
+
+    /// User avatar.
+    #[must_use]
+    pub fn user_avatar(user_id: Snowflake, hash: &str) -> Self {
+        Self::hashed(
+            AssetKind::UserAvatar,
+            format_args!("avatars/{user_id}"),
+            hash,
+        )
+    }
+
+    /// Guild-specific member avatar.
+    #[must_use]
+    pub fn member_avatar(guild_id: Snowflake, user_id: Snowflake, hash: &str) -> Self {
+        Self::hashed(
+            AssetKind::GuildMemberAvatar,
+            format_args!("guilds/{guild_id}/users/{user_id}/avatars"),
+            hash,
+        )
+    }
+
+    /// Guild-specific member banner.
+    #[must_use]
+    pub fn member_banner(guild_id: Snowflake, user_id: Snowflake, hash: &str) -> Self {
+        Self::hashed(
+            AssetKind::GuildMemberBanner,
+            format_args!("guilds/{guild_id}/users/{user_id}/banners"),
+            hash,
+        )
+    }
+
+    /// Avatar decoration preset, from `avatar_decoration_data.asset`.
+    #[must_use]
+    pub fn avatar_decoration(asset: &str) -> Self {
+        Self::new(
+            AssetKind::AvatarDecoration,
+            format!("avatar-decoration-presets/{asset}"),
+            false,
+        )
+    }
+
+    /// Application icon.
+    #[must_use]
+    pub fn application_icon(application_id: Snowflake, hash: &str) -> Self {
+        Self::hashed(
+            AssetKind::ApplicationIcon,
+            format_args!("app-icons/{application_id}"),
+            hash,
+        )
+    }
+
+    /// Application cover image.
+    #[must_use]
+    pub fn application_cover(application_id: Snowflake, hash: &str) -> Self {
+        Self::hashed(
+            AssetKind::ApplicationCover,
+            format_args!("app-icons/{application_id}"),
+            hash,
+        )
+    }
+
+    /// Standard sticker pack banner.
+    #[must_use]
+    pub fn sticker_pack_banner(banner_asset_id: Snowflake) -> Self {
+        Self::new(
+            AssetKind::StickerPackBanner,
+            format!("app-assets/{STICKER_PACK_APPLICATION_ID}/store/{banner_asset_id}"),
+            false,
+        )
+    }
+
+    /// Team icon.
+    #[must_use]
+    pub fn team_icon(team_id: Snowflake, hash: &str) -> Self {
+        Self::hashed(
+            AssetKind::TeamIcon,
+            format_args!("team-icons/{team_id}"),
+            hash,
+        )
+    }
+
+    /// Sticker in its native format (`format_type`: 1 PNG, 2 APNG, 3 Lottie, 4 GIF).
+    ///
+    /// GIF stickers are served from [`MEDIA_BASE`] rather than the CDN.
+    #[must_use]
+    pub fn sticker(sticker_id: Snowflake, format_type: u8) -> Self {
+        let format = match format_type {
+            3 => ImageFormat::Lottie,
+            4 => ImageFormat::Gif,
+            _ => ImageFormat::Png,
+        };
+        Self {
+            kind: AssetKind::Sticker,
+            base: if format == ImageFormat::Gif {
+                MEDIA_BASE
+            } else {
+                CDN_BASE
+            },
+            path: format!("stickers/{sticker_id}"),
+            animated: format_type != 1,
+            native: format,
+            format,
+            size: None,
+        }
+    }
+
+    /// Role icon.
+    #[must_use]
+    pub fn role_icon(role_id: Snowflake, hash: &str) -> Self {
+        Self::hashed(
+            AssetKind::RoleIcon,
+            format_args!("role-icons/{role_id}"),
+            hash,
+        )
+    }
+
+    /// Guild scheduled event cover image.
+    #[must_use]
+    pub fn scheduled_event_cover(event_id: Snowflake, hash: &str) -> Self {
+        Self::hashed(
+            AssetKind::ScheduledEventCover,
+            format_args!("guild-events/{event_id}"),
+            hash,
+        )
+    }
+
+    /// Kind of asset.
+    #[must_use]
+    pub const fn kind(&self) -> AssetKind {
+        self.kind
+    }
+
+    /// Whether the asset is animated.
+    #[must_use]
+    pub const fn is_animated(&self) -> bool {
+        self.animated
+    }
+
+    /// Format the URL points to.
+    #[must_use]
+    pub const fn format(&self) -> ImageFormat {
+        self.format
+    }
+
+    /// Requested size, if any.
+    #[must_use]
+    pub const fn size(&self) -> Option<ImageSize> {
+        self.size
+    }
+
+    /// Whether this asset can be served in `format`.
+    #[must_use]
+    pub fn supports(&self, format: ImageFormat) -> bool {
+        use AssetKind::*;
+        use ImageFormat::*;
+
+        match (self.kind, format) {
+            (Sticker, _) => format == self.native,
+            (DefaultUserAvatar | AvatarDecoration, f) => f == Png,
+            (_, Png | Jpeg | WebP) => true,
+            (CustomEmoji | GuildIcon | GuildBanner | UserBanner | UserAvatar, Gif)
+            | (GuildMemberAvatar | GuildMemberBanner, Gif) => self.animated,
+            _ => false,
+        }
+    }
+
+    /// Request a different format.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CdnError::UnsupportedFormat`] if the asset cannot be served in
+    /// `format` (e.g. GIF for a static avatar), or [`CdnError::SizeNotSupported`]
+    /// when switching a resized asset to Lottie.
+    pub fn with_format(mut self, format: ImageFormat) -> Result<Self, CdnError> {
+        if !self.supports(format) {
+            return Err(CdnError::UnsupportedFormat {
+                asset: self.kind,
+                format,
+            });
+        }
+        if format == ImageFormat::Lottie && self.size.is_some() {
+            return Err(CdnError::SizeNotSupported);
+        }
+        self.format = format;
+        Ok(self)
+    }
+
+    /// Request a specific size in pixels.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CdnError::InvalidSize`] if `size` is not a power of two in
+    /// `16..=4096`, or [`CdnError::SizeNotSupported`] for Lottie stickers.
+    pub fn with_size(mut self, size: u16) -> Result<Self, CdnError> {
+        let size = ImageSize::new(size)?;
+        if self.format == ImageFormat::Lottie {
+            return Err(CdnError::SizeNotSupported);
+        }
+        self.size = Some(size);
+        Ok(self)
+    }
+}
+
+impl fmt::Display for CdnUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}.{}", self.base, self.path, self.format.extension())?;
+        if let Some(size) = self.size {
+            write!(f, "?size={}", size.get())?;
+        }
+        Ok(())
+    }
+}
+
+impl From<CdnUrl> for String {
+    fn from(url: CdnUrl) -> Self {
+        url.to_string()
+    }
+}
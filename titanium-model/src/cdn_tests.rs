@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::{
+        cdn::{AssetKind, CdnError, CdnUrl, ImageFormat},
         member::{Emoji, Sticker},
         Guild, Snowflake, User,
     };
@@ -136,4 +137,60 @@ mod tests {
         };
         assert_eq!(sticker.url(), "https://cdn.discordapp.com/stickers/888.png");
     }
+
+    #[test]
+    fn test_cdn_url_format_and_size() {
+        let url = CdnUrl::guild_icon(Snowflake(123), "iconhash")
+            .with_format(ImageFormat::WebP)
+            .unwrap()
+            .with_size(512)
+            .unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://cdn.discordapp.com/icons/123/iconhash.webp?size=512"
+        );
+
+        assert_eq!(
+            CdnUrl::member_avatar(Snowflake(1), Snowflake(2), "hash").to_string(),
+            "https://cdn.discordapp.com/guilds/1/users/2/avatars/hash.png"
+        );
+        assert_eq!(
+            CdnUrl::sticker(Snowflake(888), 4).to_string(),
+            "https://media.discordapp.net/stickers/888.gif"
+        );
+    }
+
+    #[test]
+    fn test_cdn_url_rejects_illegal_combinations() {
+        assert_eq!(
+            CdnUrl::user_avatar(Snowflake(1), "static").with_format(ImageFormat::Gif),
+            Err(CdnError::UnsupportedFormat {
+                asset: AssetKind::UserAvatar,
+                format: ImageFormat::Gif,
+            })
+        );
+        assert!(CdnUrl::user_avatar(Snowflake(1), "a_animated")
+            .with_format(ImageFormat::Gif)
+            .is_ok());
+        assert!(CdnUrl::role_icon(Snowflake(1), "a_hash")
+            .with_format(ImageFormat::Gif)
+            .is_err());
+        assert!(CdnUrl::default_avatar(0)
+            .with_format(ImageFormat::Jpeg)
+            .is_err());
+
+        assert_eq!(
+            CdnUrl::emoji(Snowflake(1), false).with_size(100),
+            Err(CdnError::InvalidSize(100))
+        );
+        assert!(CdnUrl::emoji(Snowflake(1), false).with_size(8192).is_err());
+
+        let lottie = CdnUrl::sticker(Snowflake(1), 3);
+        assert_eq!(lottie.format(), ImageFormat::Lottie);
+        assert_eq!(
+            lottie.clone().with_size(64),
+            Err(CdnError::SizeNotSupported)
+        );
+        assert!(lottie.with_format(ImageFormat::Png).is_err());
+    }
 }
@@ -3,7 +3,7 @@
 use crate::reaction::ReactionEmoji;
 // Imports removed
 
-use crate::TitanString;
+use crate::{Snowflake, TitanString};
 use serde::{Deserialize, Serialize};
 
 /// Top-level component type.
@@ -104,6 +104,9 @@ pub struct Button<'a> {
     /// URL for link buttons.
     #[serde(default)]
     pub url: Option<TitanString<'a>>,
+    /// SKU ID for premium buttons.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sku_id: Option<Snowflake>,
     /// Whether the button is disabled.
     #[serde(default)]
     pub disabled: bool,
@@ -126,6 +129,11 @@ impl<'a> Button<'a> {
             .url(url)
             .style(ButtonStyle::Link)
     }
+
+    /// Create a builder for a Premium (purchase) Button.
+    pub fn builder_premium(sku_id: impl Into<Snowflake>) -> crate::builder::ButtonBuilder<'a> {
+        crate::builder::ButtonBuilder::premium(sku_id)
+    }
 }
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(from = "u8", into = "u8")]
@@ -140,6 +148,8 @@ pub enum ButtonStyle {
     Danger = 4,
     /// Grey Link (Link).
     Link = 5,
+    /// Purchase button for a SKU (Premium).
+    Premium = 6,
 }
 
 impl From<u8> for ButtonStyle {
@@ -149,6 +159,7 @@ impl From<u8> for ButtonStyle {
             3 => ButtonStyle::Success,
             4 => ButtonStyle::Danger,
             5 => ButtonStyle::Link,
+            6 => ButtonStyle::Premium,
             _ => ButtonStyle::Primary,
         }
     }
@@ -1,3 +1,4 @@
+use crate::cdn::CdnUrl;
 use crate::member::{Emoji, Role, Sticker};
 use crate::snowflake::Snowflake;
 use crate::PartialVoiceState;
@@ -135,47 +136,33 @@ impl Guild<'_> {
     /// Returns the URL of the guild's icon.
     #[must_use]
     pub fn icon_url(&self) -> Option<String> {
-        self.icon.as_ref().map(|hash| {
-            let ext = if hash.starts_with("a_") { "gif" } else { "png" };
-            format!(
-                "https://cdn.discordapp.com/icons/{}/{}.{}",
-                self.id, hash, ext
-            )
-        })
+        self.icon
+            .as_ref()
+            .map(|hash| CdnUrl::guild_icon(self.id, hash).to_string())
     }
 
     /// Returns the URL of the guild's splash.
     #[must_use]
     pub fn splash_url(&self) -> Option<String> {
-        self.splash.as_ref().map(|hash| {
-            format!(
-                "https://cdn.discordapp.com/splashes/{}/{}.png",
-                self.id, hash
-            )
-        })
+        self.splash
+            .as_ref()
+            .map(|hash| CdnUrl::guild_splash(self.id, hash).to_string())
     }
 
     /// Returns the URL of the guild's discovery splash.
     #[must_use]
     pub fn discovery_splash_url(&self) -> Option<String> {
-        self.discovery_splash.as_ref().map(|hash| {
-            format!(
-                "https://cdn.discordapp.com/discovery-splashes/{}/{}.png",
-                self.id, hash
-            )
-        })
+        self.discovery_splash
+            .as_ref()
+            .map(|hash| CdnUrl::guild_discovery_splash(self.id, hash).to_string())
     }
 
     /// Returns the URL of the guild's banner.
     #[must_use]
     pub fn banner_url(&self) -> Option<String> {
-        self.banner.as_ref().map(|hash| {
-            let ext = if hash.starts_with("a_") { "gif" } else { "png" };
-            format!(
-                "https://cdn.discordapp.com/banners/{}/{}.{}",
-                self.id, hash, ext
-            )
-        })
+        self.banner
+            .as_ref()
+            .map(|hash| CdnUrl::guild_banner(self.id, hash).to_string())
     }
 }
 
@@ -262,6 +249,16 @@ pub struct GuildMembersChunkEvent<'a> {
     pub nonce: Option<String>,
 }
 
+/// A guild ban.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Ban<'a> {
+    /// The reason for the ban.
+    #[serde(default)]
+    pub reason: Option<TitanString<'a>>,
+    /// The banned user.
+    pub user: User<'a>,
+}
+
 /// Event data for `GUILD_BAN_ADD` / `GUILD_BAN_REMOVE`.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct GuildBanEvent<'a> {
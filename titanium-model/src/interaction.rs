@@ -3,6 +3,7 @@
 use crate::command::CommandType;
 use crate::component::ComponentType;
 use crate::member::GuildMember;
+use crate::monetization::Entitlement;
 use crate::snowflake::Snowflake;
 use crate::Message;
 use crate::TitanString;
@@ -47,6 +48,21 @@ pub struct Interaction<'a> {
     /// Guild locale.
     #[serde(default)]
     pub guild_locale: Option<TitanString<'a>>,
+    /// Entitlements of the invoking user or guild for this application.
+    #[serde(default)]
+    pub entitlements: Vec<Entitlement>,
+}
+
+impl Interaction<'_> {
+    /// Whether the user or guild holds an active entitlement to the given SKU.
+    ///
+    /// Deleted and already consumed entitlements are ignored.
+    #[must_use]
+    pub fn has_entitlement(&self, sku_id: Snowflake) -> bool {
+        self.entitlements
+            .iter()
+            .any(|e| e.sku_id == sku_id && !e.deleted && !e.consumed)
+    }
 }
 
 /// Interaction Type.
@@ -209,6 +225,10 @@ pub enum InteractionCallbackType {
     ApplicationCommandAutocompleteResult = 8,
     /// Modal.
     Modal = 9,
+    /// Premium Required (monetized apps).
+    ///
+    /// Deprecated by Discord in favour of premium buttons.
+    PremiumRequired = 10,
 }
 
 impl From<u8> for InteractionCallbackType {
@@ -221,6 +241,7 @@ impl From<u8> for InteractionCallbackType {
             7 => InteractionCallbackType::UpdateMessage,
             8 => InteractionCallbackType::ApplicationCommandAutocompleteResult,
             9 => InteractionCallbackType::Modal,
+            10 => InteractionCallbackType::PremiumRequired,
             _ => InteractionCallbackType::ChannelMessageWithSource,
         }
     }
@@ -137,3 +137,13 @@ pub fn to_value<T: Serialize>(value: T) -> Result<Value, Error> {
 pub fn from_slice_mut<T: DeserializeOwned>(json: &mut [u8]) -> Result<T, Error> {
     serde_json::from_slice(json)
 }
+
+#[cfg(all(not(feature = "simd"), feature = "serde"))]
+/// Deserialize a value from a JSON Value.
+///
+/// # Errors
+///
+/// Returns an error if deserialization fails.
+pub fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, Error> {
+    serde_json::from_value(value)
+}
@@ -10,6 +10,7 @@
 //!
 
 //! - [`automod`] - `AutoMod` rules and actions
+//! - [`cdn`] - CDN asset URLs
 //! - [`audit`] - Audit log entries
 //! - [`integration`] - Integrations and webhooks
 //! - [`invite`] - Guild invites
@@ -20,10 +21,12 @@
 //! - [`soundboard`] - Soundboard sounds
 //! - [`stage`] - Stage instances
 //! - [`thread`] - Thread channels and members
+//! - [`webhook_event`] - Application webhook events
 
 pub mod audit;
 pub mod automod;
 pub mod builder;
+pub mod cdn;
 pub mod cdn_tests;
 pub mod command;
 pub mod component;
@@ -44,6 +47,7 @@ pub mod string;
 pub mod thread;
 pub mod ui;
 pub mod voice;
+pub mod webhook_event;
 
 // New modules
 pub mod channel;
@@ -66,6 +70,7 @@ pub use builder::{
     ScheduledEventBuilder, SelectMenuBuilder, StageInstanceBuilder, StartThreadBuilder,
     WebhookExecuteBuilder,
 };
+pub use cdn::{CdnError, CdnUrl, ImageFormat, ImageSize};
 pub use command::{ApplicationCommand, CommandOption, CommandType};
 pub use component::{ActionRow, Button, Component, ComponentType, SelectMenu};
 pub use create_message::CreateMessage;
@@ -80,7 +85,7 @@ pub use interaction::{
     InteractionType,
 };
 pub use invite::{InviteCreateEvent, InviteDeleteEvent};
-pub use member::{Emoji, GuildMember, Role, RoleTags, Sticker};
+pub use member::{Emoji, GuildMember, Role, RoleTags, Sticker, StickerPack};
 pub use message::{Poll, PollAnswer, PollMedia, PollResults};
 pub use monetization::{
     Entitlement, EntitlementType, Sku, SkuType, Subscription, SubscriptionStatus,
@@ -97,7 +102,7 @@ pub use scheduled::{
 pub use snowflake::Snowflake;
 pub use soundboard::{
     GuildSoundboardSoundsUpdateEvent, SoundboardSound, SoundboardSoundDeleteEvent,
-    SoundboardSoundsUpdateEvent,
+    SoundboardSoundsEvent, SoundboardSoundsUpdateEvent,
 };
 pub use string::TitanString;
 pub use thread::{
@@ -106,11 +111,15 @@ pub use thread::{
 };
 pub use voice::PartialVoiceState;
 pub use voice::{StageInstance, StagePrivacyLevel};
+pub use webhook_event::{
+    ApplicationAuthorizedEvent, ApplicationDeauthorizedEvent, ApplicationEvent, WebhookEvent,
+    WebhookEventBody, WebhookEventPayload, WebhookType,
+};
 
 // Re-exports from new modules
 pub use channel::{Channel, ChannelMention, ChannelPinsUpdateEvent, PermissionOverwrite};
 pub use guild::{
-    Application, Guild, GuildBanEvent, GuildEmojisUpdateEvent, GuildMemberAddEvent,
+    Application, Ban, Guild, GuildBanEvent, GuildEmojisUpdateEvent, GuildMemberAddEvent,
     GuildMemberRemoveEvent, GuildMemberUpdateEvent, GuildMembersChunkEvent, GuildRoleDeleteEvent,
     GuildRoleEvent, GuildStickersUpdateEvent, ReadyEventData, UnavailableGuild,
 };
@@ -2,6 +2,7 @@
 //!
 //! Represents Discord guild members with roles, permissions, and presence.
 
+use crate::cdn::CdnUrl;
 use crate::Snowflake;
 use crate::TitanString;
 use serde::{Deserialize, Serialize};
@@ -57,6 +58,26 @@ pub struct GuildMember<'a> {
     pub communication_disabled_until: Option<TitanString<'a>>,
 }
 
+impl GuildMember<'_> {
+    /// Returns the URL of the member's guild-specific avatar.
+    ///
+    /// Requires `user` to be present, since the URL includes the user ID.
+    #[must_use]
+    pub fn avatar_url(&self, guild_id: Snowflake) -> Option<String> {
+        let user_id = self.user.as_ref()?.id;
+        self.avatar
+            .as_ref()
+            .map(|hash| CdnUrl::member_avatar(guild_id, user_id, hash).to_string())
+    }
+
+    /// Returns the member's displayed avatar URL (guild avatar, user avatar or default).
+    #[must_use]
+    pub fn face(&self, guild_id: Snowflake) -> Option<String> {
+        self.avatar_url(guild_id)
+            .or_else(|| self.user.as_ref().map(super::User::face))
+    }
+}
+
 /// A Discord role.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Role<'a> {
@@ -101,6 +122,16 @@ pub struct Role<'a> {
     pub flags: u64,
 }
 
+impl Role<'_> {
+    /// Returns the URL of the role's icon.
+    #[must_use]
+    pub fn icon_url(&self) -> Option<String> {
+        self.icon
+            .as_ref()
+            .map(|hash| CdnUrl::role_icon(self.id, hash).to_string())
+    }
+}
+
 /// Tags for a role.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct RoleTags {
@@ -169,10 +200,8 @@ impl Emoji<'_> {
     /// Returns the URL of the emoji.
     #[must_use]
     pub fn url(&self) -> Option<String> {
-        self.id.map(|id| {
-            let ext = if self.animated { "gif" } else { "png" };
-            format!("https://cdn.discordapp.com/emojis/{id}.{ext}")
-        })
+        self.id
+            .map(|id| CdnUrl::emoji(id, self.animated).to_string())
     }
 }
 
@@ -219,14 +248,45 @@ impl Sticker<'_> {
     /// Returns the URL of the sticker.
     #[must_use]
     pub fn url(&self) -> String {
-        // Sticker formats: 1 (PNG), 2 (APNG), 3 (LOTTIE), 4 (GIF)
-        // CDN: https://cdn.discordapp.com/stickers/{sticker_id}.{png|json|gif}
-        let ext = match self.format_type {
-            3 => "json", // Lottie
-            4 => "gif",  // GIF
-            _ => "png",  // PNG and APNG
-        };
-        format!("https://cdn.discordapp.com/stickers/{}.{}", self.id, ext)
+        CdnUrl::sticker(self.id, self.format_type).to_string()
+    }
+}
+
+/// A pack of standard stickers.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StickerPack<'a> {
+    /// ID of the sticker pack.
+    pub id: Snowflake,
+
+    /// The stickers in the pack.
+    #[serde(default)]
+    pub stickers: Vec<Sticker<'a>>,
+
+    /// Name of the sticker pack.
+    pub name: TitanString<'a>,
+
+    /// ID of the pack's SKU.
+    pub sku_id: Snowflake,
+
+    /// ID of a sticker in the pack which is shown as the pack's icon.
+    #[serde(default)]
+    pub cover_sticker_id: Option<Snowflake>,
+
+    /// Description of the sticker pack.
+    #[serde(default)]
+    pub description: TitanString<'a>,
+
+    /// ID of the sticker pack's banner image.
+    #[serde(default)]
+    pub banner_asset_id: Option<Snowflake>,
+}
+
+impl StickerPack<'_> {
+    /// Returns the URL of the sticker pack's banner.
+    #[must_use]
+    pub fn banner_url(&self) -> Option<String> {
+        self.banner_asset_id
+            .map(|id| CdnUrl::sticker_pack_banner(id).to_string())
     }
 }
 
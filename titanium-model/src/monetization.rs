@@ -189,4 +189,32 @@ mod tests {
         let subscription: Subscription = crate::json::from_str(json).unwrap();
         assert_eq!(subscription.status, SubscriptionStatus::Active);
     }
+
+    #[test]
+    fn test_interaction_has_entitlement() {
+        let json = r#"{
+            "id": "1",
+            "application_id": "789",
+            "type": 2,
+            "token": "abc",
+            "version": 1,
+            "entitlements": [
+                {"id": "10", "sku_id": "456", "application_id": "789", "type": 8},
+                {"id": "11", "sku_id": "654", "application_id": "789", "type": 8, "consumed": true}
+            ]
+        }"#;
+
+        let interaction: crate::Interaction = crate::json::from_str(json).unwrap();
+        assert!(interaction.has_entitlement(Snowflake::new(456)));
+        assert!(!interaction.has_entitlement(Snowflake::new(654)));
+        assert!(!interaction.has_entitlement(Snowflake::new(999)));
+    }
+
+    #[test]
+    fn test_premium_button_serialization() {
+        let button = crate::builder::ButtonBuilder::premium(456u64).build();
+        let json = crate::json::to_string(&button).unwrap();
+        assert!(json.contains(r#""style":6"#));
+        assert!(json.contains(r#""sku_id":"456""#));
+    }
 }
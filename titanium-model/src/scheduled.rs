@@ -2,6 +2,7 @@
 //!
 //! Scheduled events allow guilds to plan activities.
 
+use crate::cdn::CdnUrl;
 use crate::Snowflake;
 use crate::TitanString;
 use serde::{Deserialize, Serialize};
@@ -68,6 +69,16 @@ pub struct ScheduledEvent<'a> {
     pub image: Option<TitanString<'a>>,
 }
 
+impl ScheduledEvent<'_> {
+    /// Returns the URL of the scheduled event's cover image.
+    #[must_use]
+    pub fn cover_url(&self) -> Option<String> {
+        self.image
+            .as_ref()
+            .map(|hash| CdnUrl::scheduled_event_cover(self.id, hash).to_string())
+    }
+}
+
 /// Privacy level of a scheduled event.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize_repr, Deserialize_repr, Default)]
 #[repr(u8)]
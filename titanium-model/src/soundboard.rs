@@ -59,6 +59,18 @@ pub struct SoundboardSoundsUpdateEvent<'a> {
     pub guild_id: Option<Snowflake>,
 }
 
+/// Event data for `SOUNDBOARD_SOUNDS`.
+///
+/// Sent in response to a Request Soundboard Sounds (op 31) command.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SoundboardSoundsEvent<'a> {
+    /// The guild's soundboard sounds.
+    pub soundboard_sounds: Vec<SoundboardSound<'a>>,
+
+    /// The ID of the guild.
+    pub guild_id: Snowflake,
+}
+
 /// Event data for `GUILD_SOUNDBOARD_SOUNDS_UPDATE`.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct GuildSoundboardSoundsUpdateEvent<'a> {
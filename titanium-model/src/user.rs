@@ -1,3 +1,4 @@
+use crate::cdn::CdnUrl;
 use crate::snowflake::Snowflake;
 use crate::TitanString;
 use serde::{Deserialize, Serialize};
@@ -59,13 +60,17 @@ impl User<'_> {
     /// Returns the URL of the user's avatar.
     #[must_use]
     pub fn avatar_url(&self) -> Option<String> {
-        self.avatar.as_ref().map(|hash| {
-            let ext = if hash.starts_with("a_") { "gif" } else { "png" };
-            format!(
-                "https://cdn.discordapp.com/avatars/{}/{}.{}",
-                self.id, hash, ext
-            )
-        })
+        self.avatar
+            .as_ref()
+            .map(|hash| CdnUrl::user_avatar(self.id, hash).to_string())
+    }
+
+    /// Returns the URL of the user's profile banner.
+    #[must_use]
+    pub fn banner_url(&self) -> Option<String> {
+        self.banner
+            .as_ref()
+            .map(|hash| CdnUrl::user_banner(self.id, hash).to_string())
     }
 
     /// Returns the URL of the user's default avatar.
@@ -76,7 +81,7 @@ impl User<'_> {
         } else {
             self.discriminator.parse::<u64>().unwrap_or(0) % 5
         };
-        format!("https://cdn.discordapp.com/embed/avatars/{index}.png")
+        CdnUrl::default_avatar(index).to_string()
     }
 
     /// Returns the user's displayed avatar URL (avatar or default).
@@ -85,6 +90,19 @@ impl User<'_> {
         self.avatar_url()
             .unwrap_or_else(|| self.default_avatar_url())
     }
+
+    /// Returns the URL of the user's avatar decoration.
+    #[must_use]
+    pub fn avatar_decoration_url(&self) -> Option<String> {
+        #[derive(Deserialize)]
+        struct AvatarDecorationData {
+            asset: String,
+        }
+
+        let data: AvatarDecorationData =
+            crate::json::from_value(self.avatar_decoration_data.clone()?).ok()?;
+        Some(CdnUrl::avatar_decoration(&data.asset).to_string())
+    }
 }
 
 impl crate::Mention for User<'_> {
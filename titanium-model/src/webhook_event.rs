@@ -0,0 +1,226 @@
+//! Application webhook events.
+//!
+//! Discord can deliver some application-level events to an HTTP webhook
+//! instead of (or in addition to) the gateway.
+
+use crate::guild::Guild;
+use crate::monetization::Entitlement;
+use crate::snowflake::Snowflake;
+use crate::User;
+use serde::{Deserialize, Serialize};
+
+/// Raw webhook event request body.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WebhookEventPayload {
+    /// Version scheme for the webhook event (currently always 1).
+    pub version: u8,
+
+    /// ID of the application.
+    pub application_id: Snowflake,
+
+    /// Type of webhook.
+    #[serde(rename = "type")]
+    pub webhook_type: WebhookType,
+
+    /// Event data (absent for PINGs).
+    #[serde(default)]
+    pub event: Option<WebhookEventBody>,
+}
+
+/// Type of a webhook request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "u8", into = "u8")]
+pub enum WebhookType {
+    /// Sent by Discord to test the endpoint.
+    Ping = 0,
+    /// An event.
+    Event = 1,
+}
+
+impl From<u8> for WebhookType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => WebhookType::Ping,
+            _ => WebhookType::Event,
+        }
+    }
+}
+
+impl From<WebhookType> for u8 {
+    fn from(value: WebhookType) -> Self {
+        value as u8
+    }
+}
+
+/// Event body of a webhook request, with undecoded data.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WebhookEventBody {
+    /// Event type (e.g. `ENTITLEMENT_CREATE`).
+    #[serde(rename = "type")]
+    pub event_type: String,
+
+    /// When the event occurred (ISO8601 timestamp).
+    pub timestamp: String,
+
+    /// Event data.
+    #[serde(default)]
+    pub data: Option<crate::json::Value>,
+}
+
+/// A decoded application webhook event.
+#[derive(Debug, Clone)]
+pub struct ApplicationEvent<'a> {
+    /// ID of the application.
+    pub application_id: Snowflake,
+
+    /// When the event occurred (ISO8601 timestamp).
+    pub timestamp: String,
+
+    /// The event.
+    pub event: WebhookEvent<'a>,
+}
+
+/// Typed application webhook event.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum WebhookEvent<'a> {
+    /// The app was added to a server or user account.
+    ApplicationAuthorized(ApplicationAuthorizedEvent<'a>),
+
+    /// The app was deauthorized by a user.
+    ApplicationDeauthorized(ApplicationDeauthorizedEvent<'a>),
+
+    /// An entitlement was created (e.g. a purchase).
+    EntitlementCreate(Entitlement),
+
+    /// An entitlement was updated.
+    EntitlementUpdate(Entitlement),
+
+    /// An entitlement was deleted.
+    EntitlementDelete(Entitlement),
+
+    /// An event this library does not model yet.
+    Unknown {
+        /// Event type.
+        event_type: String,
+        /// Raw event data.
+        data: Option<crate::json::Value>,
+    },
+}
+
+/// Event data for `APPLICATION_AUTHORIZED`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ApplicationAuthorizedEvent<'a> {
+    /// Installation context: 0 for guild install, 1 for user install.
+    #[serde(default)]
+    pub integration_type: Option<u8>,
+
+    /// User who authorized the app.
+    pub user: User<'a>,
+
+    /// OAuth2 scopes the user authorized.
+    #[serde(default)]
+    pub scopes: Vec<String>,
+
+    /// Guild the app was added to (guild installs only).
+    #[serde(default)]
+    pub guild: Option<Box<Guild<'a>>>,
+}
+
+/// Event data for `APPLICATION_DEAUTHORIZED`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ApplicationDeauthorizedEvent<'a> {
+    /// User who deauthorized the app.
+    pub user: User<'a>,
+}
+
+impl WebhookEventPayload {
+    /// Decode the event data into a typed event.
+    ///
+    /// Returns `Ok(None)` for PINGs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data does not match the event type.
+    pub fn into_event(self) -> Result<Option<ApplicationEvent<'static>>, crate::json::Error> {
+        let Some(body) = self.event else {
+            return Ok(None);
+        };
+        if self.webhook_type == WebhookType::Ping {
+            return Ok(None);
+        }
+
+        let event = match body.event_type.as_str() {
+            "APPLICATION_AUTHORIZED" => {
+                WebhookEvent::ApplicationAuthorized(decode_data(body.data)?)
+            }
+            "APPLICATION_DEAUTHORIZED" => {
+                WebhookEvent::ApplicationDeauthorized(decode_data(body.data)?)
+            }
+            "ENTITLEMENT_CREATE" => WebhookEvent::EntitlementCreate(decode_data(body.data)?),
+            "ENTITLEMENT_UPDATE" => WebhookEvent::EntitlementUpdate(decode_data(body.data)?),
+            "ENTITLEMENT_DELETE" => WebhookEvent::EntitlementDelete(decode_data(body.data)?),
+            _ => WebhookEvent::Unknown {
+                event_type: body.event_type,
+                data: body.data,
+            },
+        };
+
+        Ok(Some(ApplicationEvent {
+            application_id: self.application_id,
+            timestamp: body.timestamp,
+            event,
+        }))
+    }
+}
+
+fn decode_data<T: serde::de::DeserializeOwned>(
+    data: Option<crate::json::Value>,
+) -> Result<T, crate::json::Error> {
+    crate::json::from_value(data.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entitlement_create_event() {
+        let json = r#"{
+            "version": 1,
+            "application_id": "789",
+            "type": 1,
+            "event": {
+                "type": "ENTITLEMENT_CREATE",
+                "timestamp": "2024-10-18T14:42:53.064834",
+                "data": {
+                    "id": "123",
+                    "sku_id": "456",
+                    "application_id": "789",
+                    "user_id": "42",
+                    "type": 8,
+                    "deleted": false,
+                    "consumed": false
+                }
+            }
+        }"#;
+
+        let payload: WebhookEventPayload = crate::json::from_str(json).unwrap();
+        let event = payload.into_event().unwrap().unwrap();
+        assert_eq!(event.application_id, Snowflake::new(789));
+        match event.event {
+            WebhookEvent::EntitlementCreate(entitlement) => {
+                assert_eq!(entitlement.sku_id, Snowflake::new(456));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_ping_has_no_event() {
+        let json = r#"{"version": 1, "application_id": "789", "type": 0}"#;
+        let payload: WebhookEventPayload = crate::json::from_str(json).unwrap();
+        assert_eq!(payload.webhook_type, WebhookType::Ping);
+        assert!(payload.into_event().unwrap().is_none());
+    }
+}
@@ -10,6 +10,7 @@ use parking_lot::RwLock;
 use std::sync::atomic::{AtomicU8, Ordering};
 
 use std::sync::Arc;
+use titanium_model::voice::{VoiceServerUpdateEvent, VoiceStateUpdateEvent};
 use tokio::sync::{mpsc, RwLock as AsyncRwLock};
 use tracing::{debug, error, info, warn};
 
@@ -62,6 +63,27 @@ pub struct VoiceConfig {
     pub token: String,
 }
 
+impl VoiceConfig {
+    /// Build a configuration from the bot's `VOICE_STATE_UPDATE` and the
+    /// matching `VOICE_SERVER_UPDATE`.
+    ///
+    /// Returns `None` if the bot is not in a voice channel or Discord has not
+    /// allocated a voice server yet.
+    pub fn from_events(
+        state: &VoiceStateUpdateEvent<'_>,
+        server: &VoiceServerUpdateEvent,
+    ) -> Option<Self> {
+        Some(Self {
+            guild_id: server.guild_id.get(),
+            channel_id: state.channel_id?.get(),
+            user_id: state.user_id.get(),
+            session_id: state.session_id.clone(),
+            endpoint: server.endpoint.clone()?,
+            token: server.token.clone(),
+        })
+    }
+}
+
 /// A Discord voice connection.
 pub struct VoiceConnection {
     /// Configuration.
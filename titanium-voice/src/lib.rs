@@ -26,15 +26,9 @@
 //! ```ignore
 //! use titanium_voice::{VoiceConnection, VoiceConfig};
 //!
-//! // Get voice_state_update and voice_server_update from gateway
-//! let config = VoiceConfig {
-//!     guild_id: guild_id,
-//!     channel_id: channel_id,
-//!     user_id: user_id,
-//!     session_id: voice_state.session_id,
-//!     endpoint: voice_server.endpoint.unwrap(),
-//!     token: voice_server.token,
-//! };
+//! // Send a voice state update and wait for both voice events
+//! let info = shard.update_voice_state(guild_id, Some(channel_id), false, false)?.await?;
+//! let config = VoiceConfig::from_events(&info.state, &info.server).unwrap();
 //!
 //! let connection = VoiceConnection::new(config);
 pub mod connection;
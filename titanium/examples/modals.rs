@@ -32,32 +32,31 @@ impl EventHandler for Handler {
     async fn interaction_create(&self, ctx: Context, i: Interaction<'_>) {
         let http = &ctx.http;
         match i.interaction_type {
-            InteractionType::ApplicationCommand => {
+            InteractionType::ApplicationCommand
                 if i.data
                     .as_ref()
-                    .is_some_and(|d| d.name.as_deref() == Some("modal"))
-                {
-                    let modal = ModalBuilder::new("my_modal", "Tell me about yourself")
-                        .row(ActionRowBuilder::new().input(TextInputBuilder::new(
-                            "name",
-                            TextInputStyle::Short,
-                            "What is your name?",
-                        )))
-                        .row(ActionRowBuilder::new().input(TextInputBuilder::new(
-                            "bio",
-                            TextInputStyle::Paragraph,
-                            "Tell us your story",
-                        )));
+                    .is_some_and(|d| d.name.as_deref() == Some("modal")) =>
+            {
+                let modal = ModalBuilder::new("my_modal", "Tell me about yourself")
+                    .row(ActionRowBuilder::new().input(TextInputBuilder::new(
+                        "name",
+                        TextInputStyle::Short,
+                        "What is your name?",
+                    )))
+                    .row(ActionRowBuilder::new().input(TextInputBuilder::new(
+                        "bio",
+                        TextInputStyle::Paragraph,
+                        "Tell us your story",
+                    )));
 
-                    let resp = InteractionResponseBuilder::new()
-                        .kind(InteractionCallbackType::Modal)
-                        .modal(modal.build())
-                        .build();
+                let resp = InteractionResponseBuilder::new()
+                    .kind(InteractionCallbackType::Modal)
+                    .modal(modal.build())
+                    .build();
 
-                    let _ = http
-                        .create_interaction_response(i.id, &i.token, &resp)
-                        .await;
-                }
+                let _ = http
+                    .create_interaction_response(i.id, &i.token, &resp)
+                    .await;
             }
             InteractionType::ModalSubmit => {
                 if let Some(data) = i.data {
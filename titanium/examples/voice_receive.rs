@@ -1,8 +1,10 @@
 use async_trait::async_trait;
 use std::env;
+use std::sync::Arc;
 use titanium_rs::prelude::*;
+use titanium_rs::voice::VoiceConnection;
 
-// Example: Voice Receive Bot (Placeholder)
+// Example: Voice Receive Bot
 // Demonstrates connecting to a voice channel.
 // Note: Full voice receive requires complex UDP handling which is handled by titanium-voice crate.
 
@@ -32,23 +34,34 @@ struct VoiceHandler;
 impl EventHandler for VoiceHandler {
     async fn ready(&self, _ctx: Context, ready: ReadyEventData<'_>) {
         println!("Voice Bot ready: {}", ready.user.username);
-        println!("Use !join to make the bot join your voice channel.");
+        println!("Use !join <channel id> to make the bot join a voice channel.");
     }
 
     async fn message_create(&self, ctx: Context, msg: Message<'_>) {
-        if msg.content == "!join" {
-            // In a real bot, we would look up the user's voice state in the cache
-            // let guild = ctx.cache.guild(msg.guild_id.unwrap()).await;
-            // let channel_id = guild.voice_states.get(&msg.author.id).unwrap().channel_id;
-
-            // Then send a Gateway Update Voice State op
-            // ctx.shard.voice_state_update(msg.guild_id.unwrap(), Some(channel_id), false, false).await;
-
-            let response = titanium_model::CreateMessage {
-                content: Some("Attempting to join voice channel... (Logic placeholder)".into()),
-                ..Default::default()
-            };
-            let _ = ctx.http.create_message(msg.channel_id, &response).await;
-        }
+        // Usage: !join <voice channel id>
+        let Some(arg) = msg.content.strip_prefix("!join ") else {
+            return;
+        };
+        let (Some(guild_id), Ok(channel_id)) = (msg.guild_id, arg.trim().parse::<u64>()) else {
+            return;
+        };
+
+        // Send a Gateway Update Voice State op and wait for Discord's answer
+        let content = match ctx.join_voice(guild_id, channel_id, false, false).await {
+            Ok(config) => {
+                let connection = Arc::new(VoiceConnection::new(config));
+                match connection.connect().await {
+                    Ok(()) => "Joined voice channel.".to_string(),
+                    Err(why) => format!("Voice connection failed: {why}"),
+                }
+            }
+            Err(why) => format!("Could not join voice channel: {why}"),
+        };
+
+        let response = titanium_model::CreateMessage {
+            content: Some(content.into()),
+            ..Default::default()
+        };
+        let _ = ctx.http.create_message(msg.channel_id, &response).await;
     }
 }
@@ -48,7 +48,7 @@ use crate::error::TitaniumError;
 use crate::framework::Framework;
 use std::sync::Arc;
 use titanium_cache::{Cache, InMemoryCache};
-use titanium_gateway::{Cluster, ClusterConfig, Event};
+use titanium_gateway::{Cluster, ClusterConfig, Event, PresenceUpdate};
 use titanium_http::HttpClient;
 use titanium_model::Intents;
 
@@ -122,10 +122,8 @@ impl Client {
                 Event::GuildUpdate(guild) => {
                     self.cache.insert_guild(Arc::clone(guild));
                 }
-                Event::GuildDelete(unavailable) => {
-                    if !unavailable.unavailable {
-                        self.cache.remove_guild(unavailable.id);
-                    }
+                Event::GuildDelete(unavailable) if !unavailable.unavailable => {
+                    self.cache.remove_guild(unavailable.id);
                 }
                 Event::ChannelCreate(channel) => {
                     self.cache.insert_channel(Arc::clone(channel));
@@ -284,6 +282,7 @@ pub struct ClientBuilder {
     intents: Intents,
     framework: Option<Framework>,
     event_handler: Option<Arc<dyn EventHandler>>,
+    presence: Option<PresenceUpdate>,
 }
 
 impl ClientBuilder {
@@ -294,6 +293,7 @@ impl ClientBuilder {
             intents: Intents::default(),
             framework: None,
             event_handler: None,
+            presence: None,
         }
     }
 
@@ -304,6 +304,13 @@ impl ClientBuilder {
         self
     }
 
+    /// Set the presence every shard identifies with.
+    #[must_use]
+    pub fn presence(mut self, presence: PresenceUpdate) -> Self {
+        self.presence = Some(presence);
+        self
+    }
+
     /// Set the command framework.
     #[must_use]
     pub fn framework(mut self, framework: Framework) -> Self {
@@ -322,7 +329,8 @@ impl ClientBuilder {
 
         // Use auto-scaling cluster configuration
         // This fetches the recommended shard count from Discord
-        let config = ClusterConfig::autoscaled(self.token.clone(), self.intents).await?;
+        let mut config = ClusterConfig::autoscaled(self.token.clone(), self.intents).await?;
+        config.presence = self.presence;
 
         // Initialize Cluster
         let (cluster, rx) = Cluster::new(config);
@@ -36,9 +36,14 @@
 use crate::error::{ContextError, TitaniumError};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use titanium_gateway::Shard;
 use titanium_http::HttpClient;
 use titanium_model::{Embed, Interaction, Message, Snowflake, User};
+use titanium_voice::VoiceConfig;
+
+/// How long [`Context::join_voice`] waits for Discord's voice events.
+const VOICE_JOIN_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Context for Discord interaction handling.
 ///
@@ -485,6 +490,157 @@ impl Context {
         self.reply_embed(embed).await
     }
 
+    /// Whether the invoking user or guild is entitled to the given SKU.
+    ///
+    /// Uses the entitlements Discord attaches to the interaction, so no
+    /// request is made. Returns `false` outside of interactions.
+    pub fn has_entitlement(&self, sku_id: impl Into<Snowflake>) -> bool {
+        let sku_id = sku_id.into();
+        self.interaction
+            .as_ref()
+            .is_some_and(|i| i.has_entitlement(sku_id))
+    }
+
+    /// Check for an entitlement, prompting the user to purchase it if missing.
+    ///
+    /// If the user is not entitled, replies with an ephemeral message holding
+    /// a premium button for the SKU and returns `false`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use titanium_rs::prelude::*;
+    /// # async fn example(ctx: Context) -> Result<(), TitaniumError> {
+    /// if !ctx.require_entitlement(123456789u64, "This is a premium command.").await? {
+    ///     return Ok(());
+    /// }
+    /// ctx.reply("Premium content!").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `TitaniumError` if there is no interaction or the HTTP request fails.
+    pub async fn require_entitlement(
+        &self,
+        sku_id: impl Into<Snowflake>,
+        content: impl Into<String>,
+    ) -> Result<bool, TitaniumError> {
+        let sku_id = sku_id.into();
+        let interaction = self
+            .interaction
+            .as_ref()
+            .ok_or(ContextError::NoInteraction)?;
+
+        if interaction.has_entitlement(sku_id) {
+            return Ok(true);
+        }
+
+        if self
+            .has_responded
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return Err(ContextError::AlreadyResponded.into());
+        }
+
+        let response = titanium_model::builder::InteractionResponseBuilder::new()
+            .content(content.into())
+            .purchase_button(sku_id)
+            .ephemeral(true)
+            .build();
+
+        self.http
+            .create_interaction_response(interaction.id, &interaction.token, &response)
+            .await?;
+
+        Ok(false)
+    }
+
+    /// Consume a one-time purchase entitlement of the invoking user for the SKU.
+    ///
+    /// Returns `false` if no unconsumed entitlement to the SKU is attached to the interaction.
+    ///
+    /// # Errors
+    /// Returns `TitaniumError` if there is no interaction or the HTTP request fails.
+    pub async fn consume_entitlement(
+        &self,
+        sku_id: impl Into<Snowflake>,
+    ) -> Result<bool, TitaniumError> {
+        let sku_id = sku_id.into();
+        let interaction = self
+            .interaction
+            .as_ref()
+            .ok_or(ContextError::NoInteraction)?;
+
+        let Some(entitlement) = interaction
+            .entitlements
+            .iter()
+            .find(|e| e.sku_id == sku_id && !e.deleted && !e.consumed)
+        else {
+            return Ok(false);
+        };
+
+        self.http
+            .consume_entitlement(interaction.application_id, entitlement.id)
+            .await?;
+
+        Ok(true)
+    }
+
+    /// Join a voice channel in a guild served by this context's shard.
+    ///
+    /// Sends a voice state update and waits up to 10 seconds for Discord's
+    /// answer, returning the configuration for a
+    /// `titanium_voice::VoiceConnection`.
+    ///
+    /// # Errors
+    /// Returns `TitaniumError::Gateway` if the update could not be sent or was
+    /// superseded, `ContextError::VoiceTimeout` if Discord did not answer in
+    /// time (e.g. missing permissions or a full channel), and
+    /// `ContextError::NoVoiceServer` if Discord did not allocate a voice
+    /// server.
+    pub async fn join_voice(
+        &self,
+        guild_id: impl Into<Snowflake>,
+        channel_id: impl Into<Snowflake>,
+        self_mute: bool,
+        self_deaf: bool,
+    ) -> Result<VoiceConfig, TitaniumError> {
+        let join = async {
+            let future = self
+                .shard
+                .update_voice_state_async(
+                    guild_id.into(),
+                    Some(channel_id.into()),
+                    self_mute,
+                    self_deaf,
+                )
+                .await?;
+            future.await
+        };
+        // Dropping the future on timeout cancels the pending update
+        let info = tokio::time::timeout(VOICE_JOIN_TIMEOUT, join)
+            .await
+            .map_err(|_| ContextError::VoiceTimeout)??;
+
+        VoiceConfig::from_events(&info.state, &info.server)
+            .ok_or_else(|| ContextError::NoVoiceServer.into())
+    }
+
+    /// Leave the voice channel of a guild served by this context's shard.
+    ///
+    /// # Errors
+    /// Returns `TitaniumError::Gateway` if the update could not be sent.
+    pub fn leave_voice(&self, guild_id: impl Into<Snowflake>) -> Result<(), TitaniumError> {
+        // Leaving completes no pairing, so the returned future is not needed
+        drop(
+            self.shard
+                .update_voice_state(guild_id.into(), None, false, false)?,
+        );
+        Ok(())
+    }
+
     /// Send a message to a specific channel (bypass interaction).
     #[inline]
     pub async fn send(
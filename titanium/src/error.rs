@@ -31,6 +31,10 @@ pub enum ContextError {
     NoInteraction,
     #[error("Already responded to interaction")]
     AlreadyResponded,
+    #[error("No voice server allocated for the guild")]
+    NoVoiceServer,
+    #[error("Timed out waiting for the voice server")]
+    VoiceTimeout,
     #[error("HTTP error: {0}")]
     Http(#[from] titanium_http::error::HttpError),
 }